@@ -0,0 +1,25 @@
+use crate::utils::config::optional_var;
+
+/// Rules on buys made alongside a launch. Buys in the creation slot and the
+/// `window_slots` after it are correlated; a token whose bundled wallets
+/// bought more than `max_bundled_pct` of the supply is skipped.
+#[derive(Debug, Clone)]
+pub struct BundleConfig {
+    /// Correlate early buys at all
+    pub enabled: bool,
+    pub window_slots: u64,
+    /// Look up who funded each early buyer over RPC, two calls per wallet
+    pub resolve_funders: bool,
+    pub max_bundled_pct: Option<f64>,
+}
+
+impl BundleConfig {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(BundleConfig {
+            enabled: optional_var("BUNDLE_DETECTION")?.unwrap_or(true),
+            window_slots: optional_var("BUNDLE_WINDOW_SLOTS")?.unwrap_or(2),
+            resolve_funders: optional_var("BUNDLE_RESOLVE_FUNDERS")?.unwrap_or(false),
+            max_bundled_pct: optional_var("BUNDLE_MAX_BUNDLED_PCT")?,
+        })
+    }
+}
@@ -0,0 +1,678 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use helius_laserstream::grpc::SubscribeUpdateTransactionInfo;
+use serde_json::{Value, json};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_request::RpcRequest;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use tracing::debug;
+
+use crate::bundles::BundleConfig;
+use crate::pipeline::Verdict;
+use crate::types::TokenInfo;
+
+// Wallets with a longer history are not fresh, their funder is left unknown
+const FUNDER_HISTORY_LIMIT: usize = 100;
+
+/// How the early buys of a launch cluster, as shares of its total supply.
+/// The creator's own buys are not counted as bundled supply.
+#[derive(Debug, Clone)]
+pub struct BundleStats {
+    /// Wallets other than the creator that bought within the window
+    pub buyers: usize,
+    /// Buyers sharing a funder, a fee payer or a slot with another
+    /// participant
+    pub bundled_wallets: usize,
+    pub clusters: usize,
+    pub bundled_pct: f64,
+    pub largest_cluster_pct: f64,
+}
+
+struct Buy {
+    wallet: Pubkey,
+    fee_payer: Pubkey,
+    slot: u64,
+    amount: u64,
+}
+
+enum Funder {
+    Pending,
+    Unknown,
+    Known(Pubkey),
+}
+
+struct Launch {
+    created_slot: u64,
+    creator: Pubkey,
+    bonding_curve: Pubkey,
+    /// Set once a transaction past the window has been seen
+    closed: bool,
+    buys: Vec<Buy>,
+    funders: HashMap<Pubkey, Funder>,
+}
+
+/// What links two participants of a launch.
+#[derive(PartialEq, Eq, Hash)]
+enum Link {
+    Wallet(Pubkey),
+    Slot(u64),
+}
+
+/// Union-find over the participants, joined by shared links.
+struct Clusters {
+    parent: Vec<usize>,
+    owners: HashMap<Link, usize>,
+}
+
+impl Clusters {
+    fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+            owners: HashMap::new(),
+        }
+    }
+
+    fn root(&mut self, mut node: usize) -> usize {
+        while self.parent[node] != node {
+            self.parent[node] = self.parent[self.parent[node]];
+            node = self.parent[node];
+        }
+        node
+    }
+
+    fn link(&mut self, node: usize, link: Link) {
+        let owner = *self.owners.entry(link).or_insert(node);
+        let (a, b) = (self.root(owner), self.root(node));
+        self.parent[a] = b;
+    }
+}
+
+impl Launch {
+    fn settled(&self) -> bool {
+        self.closed
+            && self
+                .funders
+                .values()
+                .all(|funder| !matches!(funder, Funder::Pending))
+    }
+
+    fn funder(&self, wallet: &Pubkey) -> Option<Pubkey> {
+        match self.funders.get(wallet) {
+            Some(Funder::Known(funder)) => Some(*funder),
+            _ => None,
+        }
+    }
+
+    fn stats(&self, total_supply: u64) -> BundleStats {
+        // The creator is participant 0, buyers follow in order of first buy
+        let mut participants: HashMap<Pubkey, usize> = HashMap::from([(self.creator, 0)]);
+        let mut bought = vec![0u64];
+        let mut nodes = Vec::new();
+        for buy in &self.buys {
+            let next = bought.len();
+            let node = *participants.entry(buy.wallet).or_insert(next);
+            if node == next {
+                bought.push(0);
+            }
+            bought[node] += buy.amount;
+            nodes.push(node);
+        }
+
+        let mut clusters = Clusters::new(bought.len());
+        clusters.link(0, Link::Wallet(self.creator));
+        clusters.link(0, Link::Slot(self.created_slot));
+        if let Some(funder) = self.funder(&self.creator) {
+            clusters.link(0, Link::Wallet(funder));
+        }
+        for (buy, &node) in self.buys.iter().zip(&nodes) {
+            clusters.link(node, Link::Wallet(buy.wallet));
+            clusters.link(node, Link::Wallet(buy.fee_payer));
+            clusters.link(node, Link::Slot(buy.slot));
+            if let Some(funder) = self.funder(&buy.wallet) {
+                clusters.link(node, Link::Wallet(funder));
+            }
+        }
+
+        let mut members: HashMap<usize, (usize, u64)> = HashMap::new();
+        for (node, &amount) in bought.iter().enumerate() {
+            let cluster = members.entry(clusters.root(node)).or_default();
+            cluster.0 += 1;
+            if node != 0 {
+                cluster.1 += amount;
+            }
+        }
+        // The creator is not a bundled buyer of their own launch
+        let creators = clusters.root(0);
+        let bundled: Vec<(usize, u64)> = members
+            .into_iter()
+            .filter(|(_, (size, _))| *size > 1)
+            .map(|(root, (size, amount))| (if root == creators { size - 1 } else { size }, amount))
+            .collect();
+
+        let share = |amount: u64| {
+            if total_supply == 0 {
+                0.0
+            } else {
+                amount as f64 / total_supply as f64 * 100.0
+            }
+        };
+        BundleStats {
+            buyers: bought.len() - 1,
+            bundled_wallets: bundled.iter().map(|(size, _)| size).sum(),
+            clusters: bundled.len(),
+            bundled_pct: share(bundled.iter().map(|(_, amount)| amount).sum()),
+            largest_cluster_pct: share(
+                bundled
+                    .iter()
+                    .map(|(_, amount)| *amount)
+                    .max()
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Early buys of the tokens in flight. The transaction monitor registers
+/// every launch and feeds it the stream; buys within the window are
+/// clustered by funder, fee payer and slot, and the strategy reads how much
+/// of the supply the clusters took before buying.
+pub struct BundleDetector {
+    config: BundleConfig,
+    rpc_client: Arc<RpcClient>,
+    launches: RwLock<HashMap<String, Launch>>,
+    last_slot: AtomicU64,
+}
+
+impl BundleDetector {
+    pub fn new(config: BundleConfig, rpc_client: Arc<RpcClient>) -> Self {
+        Self {
+            config,
+            rpc_client,
+            launches: RwLock::new(HashMap::new()),
+            last_slot: AtomicU64::new(0),
+        }
+    }
+
+    /// Starts collecting the early buys of a new token.
+    pub fn launch(self: &Arc<Self>, token: &TokenInfo) {
+        if !self.config.enabled {
+            return;
+        }
+        let (Ok(creator), Ok(bonding_curve)) = (
+            Pubkey::from_str(&token.creator),
+            Pubkey::from_str(&token.bonding_curve),
+        ) else {
+            return;
+        };
+        let mut funders = HashMap::new();
+        if self.config.resolve_funders {
+            funders.insert(creator, Funder::Pending);
+        }
+        self.launches
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                token.mint.clone(),
+                Launch {
+                    created_slot: token.created_slot,
+                    creator,
+                    bonding_curve,
+                    closed: false,
+                    buys: Vec::new(),
+                    funders,
+                },
+            );
+        if self.config.resolve_funders {
+            self.spawn_funder_lookup(token.mint.clone(), creator);
+        }
+    }
+
+    /// Records the buys a transaction made on launches still in their
+    /// window. Buys are read from the token balance changes, so they are
+    /// found whichever program routed them.
+    pub fn observe(self: &Arc<Self>, slot: u64, info: &SubscribeUpdateTransactionInfo) {
+        if !self.config.enabled {
+            return;
+        }
+        self.close_windows(slot);
+
+        let (Some(transaction), Some(meta)) = (&info.transaction, &info.meta) else {
+            return;
+        };
+        let Some(fee_payer) = transaction
+            .message
+            .as_ref()
+            .and_then(|message| message.account_keys.first())
+            .and_then(|key| Pubkey::try_from(key.as_slice()).ok())
+        else {
+            return;
+        };
+
+        // Net change per mint and owner; accounts created here have no pre balance
+        let mut deltas: HashMap<(&str, &str), i128> = HashMap::new();
+        {
+            let launches = self.launches.read().unwrap_or_else(|e| e.into_inner());
+            let balances = meta
+                .post_token_balances
+                .iter()
+                .map(|balance| (balance, 1))
+                .chain(meta.pre_token_balances.iter().map(|balance| (balance, -1)));
+            for (balance, sign) in balances {
+                if !launches.contains_key(&balance.mint) {
+                    continue;
+                }
+                let amount = balance
+                    .ui_token_amount
+                    .as_ref()
+                    .and_then(|amount| amount.amount.parse::<u64>().ok())
+                    .unwrap_or_default();
+                *deltas
+                    .entry((balance.mint.as_str(), balance.owner.as_str()))
+                    .or_default() += sign * amount as i128;
+            }
+        }
+        if deltas.is_empty() {
+            return;
+        }
+
+        let mut lookups = Vec::new();
+        {
+            let mut launches = self.launches.write().unwrap_or_else(|e| e.into_inner());
+            for ((mint, owner), delta) in deltas {
+                let (Some(launch), Ok(wallet)) = (launches.get_mut(mint), Pubkey::from_str(owner))
+                else {
+                    continue;
+                };
+                if delta <= 0
+                    || launch.closed
+                    || slot > launch.created_slot + self.config.window_slots
+                    || wallet == launch.bonding_curve
+                {
+                    continue;
+                }
+                launch.buys.push(Buy {
+                    wallet,
+                    fee_payer,
+                    slot,
+                    amount: delta as u64,
+                });
+                if self.config.resolve_funders && !launch.funders.contains_key(&wallet) {
+                    launch.funders.insert(wallet, Funder::Pending);
+                    lookups.push((mint.to_string(), wallet));
+                }
+            }
+        }
+        for (mint, wallet) in lookups {
+            self.spawn_funder_lookup(mint, wallet);
+        }
+    }
+
+    /// Clustering of the early buys, once the window has closed and every
+    /// funder lookup has finished.
+    pub fn stats(&self, mint: &str, total_supply: u64) -> Option<BundleStats> {
+        let launches = self.launches.read().unwrap_or_else(|e| e.into_inner());
+        let launch = launches.get(mint).filter(|launch| launch.settled())?;
+        Some(launch.stats(total_supply))
+    }
+
+    /// Rejects a token whose bundled wallets took too much of the supply.
+    pub fn verdict(&self, mint: &str, total_supply: u64) -> Verdict {
+        if !self.config.enabled {
+            return Verdict::Pass;
+        }
+        let Some(max_pct) = self.config.max_bundled_pct else {
+            return Verdict::Pass;
+        };
+        match self.stats(mint, total_supply) {
+            None => Verdict::Pending,
+            Some(stats) if stats.bundled_pct > max_pct => Verdict::Reject(vec![format!(
+                "{:.1}% of the supply bought by bundled wallets, limit {:.1}%",
+                stats.bundled_pct, max_pct
+            )]),
+            Some(_) => Verdict::Pass,
+        }
+    }
+
+    /// Drops the launches of tokens that are no longer monitored.
+    pub fn remove(&self, mints: &[String]) {
+        let mut launches = self.launches.write().unwrap_or_else(|e| e.into_inner());
+        for mint in mints {
+            launches.remove(mint);
+        }
+    }
+
+    fn close_windows(&self, slot: u64) {
+        // Only the first transaction of a new slot can close anything
+        if slot <= self.last_slot.fetch_max(slot, Ordering::Relaxed) {
+            return;
+        }
+        let mut launches = self.launches.write().unwrap_or_else(|e| e.into_inner());
+        for launch in launches.values_mut() {
+            if slot > launch.created_slot + self.config.window_slots {
+                launch.closed = true;
+            }
+        }
+    }
+
+    fn spawn_funder_lookup(self: &Arc<Self>, mint: String, wallet: Pubkey) {
+        let detector = self.clone();
+        tokio::spawn(async move {
+            let funder = match detector.funder(wallet).await {
+                Ok(Some(funder)) => Funder::Known(funder),
+                Ok(None) => Funder::Unknown,
+                Err(e) => {
+                    debug!(%mint, %wallet, error = %e, "Funder lookup failed");
+                    Funder::Unknown
+                }
+            };
+            if let Some(launch) = detector
+                .launches
+                .write()
+                .unwrap_or_else(|e| e.into_inner())
+                .get_mut(&mint)
+            {
+                launch.funders.insert(wallet, funder);
+            }
+        });
+    }
+
+    /// Fee payer of a fresh wallet's first transaction, which for a wallet
+    /// created to snipe is the transfer that funded it.
+    async fn funder(
+        &self,
+        wallet: Pubkey,
+    ) -> Result<Option<Pubkey>, Box<dyn std::error::Error + Send + Sync>> {
+        let signatures = self
+            .rpc_client
+            .get_signatures_for_address_with_config(
+                &wallet,
+                GetConfirmedSignaturesForAddress2Config {
+                    limit: Some(FUNDER_HISTORY_LIMIT),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        if signatures.len() >= FUNDER_HISTORY_LIMIT {
+            return Ok(None);
+        }
+        // Newest first, so the last one is the wallet's first transaction
+        let Some(first) = signatures.last() else {
+            return Ok(None);
+        };
+        let transaction: Value = self
+            .rpc_client
+            .send(
+                RpcRequest::GetTransaction,
+                json!([first.signature, {
+                    "encoding": "json",
+                    "commitment": "confirmed",
+                    "maxSupportedTransactionVersion": 0
+                }]),
+            )
+            .await?;
+        let fee_payer = transaction["transaction"]["message"]["accountKeys"][0]
+            .as_str()
+            .and_then(|key| Pubkey::from_str(key).ok());
+        Ok(fee_payer.filter(|fee_payer| *fee_payer != wallet))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use helius_laserstream::solana::storage::confirmed_block::{
+        Message, TokenBalance, Transaction, TransactionStatusMeta, UiTokenAmount,
+    };
+
+    use super::*;
+
+    const CREATED_SLOT: u64 = 100;
+
+    fn launch(creator: Pubkey) -> Launch {
+        Launch {
+            created_slot: CREATED_SLOT,
+            creator,
+            bonding_curve: Pubkey::new_unique(),
+            closed: true,
+            buys: Vec::new(),
+            funders: HashMap::new(),
+        }
+    }
+
+    fn buy(wallet: Pubkey, fee_payer: Pubkey, slot: u64, amount: u64) -> Buy {
+        Buy {
+            wallet,
+            fee_payer,
+            slot,
+            amount,
+        }
+    }
+
+    /// A buy paid for by the wallet itself.
+    fn own_buy(wallet: Pubkey, slot: u64, amount: u64) -> Buy {
+        buy(wallet, wallet, slot, amount)
+    }
+
+    #[test]
+    fn clusters_join_transitively() {
+        let mut clusters = Clusters::new(4);
+        clusters.link(0, Link::Slot(1));
+        clusters.link(1, Link::Slot(1));
+        clusters.link(1, Link::Slot(2));
+        clusters.link(2, Link::Slot(2));
+
+        let root = clusters.root(0);
+        assert_eq!(clusters.root(1), root);
+        assert_eq!(clusters.root(2), root);
+        assert_ne!(clusters.root(3), root);
+    }
+
+    #[test]
+    fn independent_buyers_are_not_bundled() {
+        let mut launch = launch(Pubkey::new_unique());
+        launch.buys = vec![
+            own_buy(Pubkey::new_unique(), 101, 100),
+            own_buy(Pubkey::new_unique(), 102, 100),
+        ];
+
+        let stats = launch.stats(1_000);
+        assert_eq!(stats.buyers, 2);
+        assert_eq!(stats.bundled_wallets, 0);
+        assert_eq!(stats.clusters, 0);
+        assert_eq!(stats.bundled_pct, 0.0);
+    }
+
+    #[test]
+    fn clusters_buyers_sharing_a_fee_payer() {
+        let fee_payer = Pubkey::new_unique();
+        let mut launch = launch(Pubkey::new_unique());
+        launch.buys = vec![
+            buy(Pubkey::new_unique(), fee_payer, 101, 100),
+            buy(Pubkey::new_unique(), fee_payer, 102, 150),
+            own_buy(Pubkey::new_unique(), 103, 200),
+        ];
+
+        let stats = launch.stats(1_000);
+        assert_eq!(stats.bundled_wallets, 2);
+        assert_eq!(stats.clusters, 1);
+        assert_eq!(stats.bundled_pct, 25.0);
+    }
+
+    #[test]
+    fn clusters_buyers_sharing_a_funder() {
+        let funder = Pubkey::new_unique();
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut launch = launch(Pubkey::new_unique());
+        launch.buys = vec![own_buy(a, 101, 100), own_buy(b, 102, 100)];
+        launch.funders = HashMap::from([(a, Funder::Known(funder)), (b, Funder::Known(funder))]);
+
+        let stats = launch.stats(1_000);
+        assert_eq!(stats.bundled_wallets, 2);
+        assert_eq!(stats.bundled_pct, 20.0);
+
+        launch.funders.insert(b, Funder::Unknown);
+        assert_eq!(launch.stats(1_000).bundled_wallets, 0);
+    }
+
+    #[test]
+    fn counts_creation_slot_buyers_but_not_the_creator() {
+        let creator = Pubkey::new_unique();
+        let fee_payer = Pubkey::new_unique();
+        let mut launch = launch(creator);
+        launch.buys = vec![
+            own_buy(creator, CREATED_SLOT, 100),
+            own_buy(Pubkey::new_unique(), CREATED_SLOT, 50),
+            buy(Pubkey::new_unique(), fee_payer, 101, 100),
+            buy(Pubkey::new_unique(), fee_payer, 102, 150),
+            own_buy(Pubkey::new_unique(), 103, 200),
+            // A second buy adds to the same participant
+            own_buy(creator, 104, 100),
+        ];
+
+        let stats = launch.stats(1_000);
+        assert_eq!(stats.buyers, 4);
+        assert_eq!(stats.bundled_wallets, 3);
+        assert_eq!(stats.clusters, 2);
+        assert_eq!(stats.bundled_pct, 30.0);
+        assert_eq!(stats.largest_cluster_pct, 25.0);
+        assert_eq!(launch.stats(0).bundled_pct, 0.0);
+    }
+
+    fn detector() -> Arc<BundleDetector> {
+        Arc::new(BundleDetector::new(
+            BundleConfig {
+                enabled: true,
+                window_slots: 2,
+                resolve_funders: false,
+                max_bundled_pct: Some(20.0),
+            },
+            Arc::new(RpcClient::new("http://127.0.0.1:1".to_string())),
+        ))
+    }
+
+    fn token(creator: Pubkey, bonding_curve: Pubkey) -> TokenInfo {
+        TokenInfo::new(
+            Pubkey::new_unique().to_string(),
+            bonding_curve.to_string(),
+            "Pepe".to_string(),
+            "PEPE".to_string(),
+            "ipfs://doc".to_string(),
+            creator.to_string(),
+            CREATED_SLOT,
+        )
+    }
+
+    fn balance(mint: &str, owner: Pubkey, amount: u64) -> TokenBalance {
+        TokenBalance {
+            mint: mint.to_string(),
+            owner: owner.to_string(),
+            ui_token_amount: Some(UiTokenAmount {
+                amount: amount.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Transaction paid by `fee_payer` that moved the given `(owner, pre,
+    /// post)` balances of `mint`.
+    fn transaction(
+        fee_payer: Pubkey,
+        mint: &str,
+        balances: &[(Pubkey, u64, u64)],
+    ) -> SubscribeUpdateTransactionInfo {
+        SubscribeUpdateTransactionInfo {
+            transaction: Some(Transaction {
+                message: Some(Message {
+                    account_keys: vec![fee_payer.to_bytes().to_vec()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            meta: Some(TransactionStatusMeta {
+                pre_token_balances: balances
+                    .iter()
+                    .filter(|(_, pre, _)| *pre > 0)
+                    .map(|(owner, pre, _)| balance(mint, *owner, *pre))
+                    .collect(),
+                post_token_balances: balances
+                    .iter()
+                    .map(|(owner, _, post)| balance(mint, *owner, *post))
+                    .collect(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn records_buys_from_balance_changes() {
+        let detector = detector();
+        let (creator, curve) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let token = token(creator, curve);
+        detector.launch(&token);
+        let mint = token.mint.as_str();
+        let fee_payer = Pubkey::new_unique();
+        let (a, b, seller) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        // Two wallets bought through one fee payer, out of the curve
+        detector.observe(
+            101,
+            &transaction(fee_payer, mint, &[(curve, 1_000, 700), (a, 0, 300)]),
+        );
+        detector.observe(
+            102,
+            &transaction(fee_payer, mint, &[(curve, 700, 600), (b, 0, 100)]),
+        );
+        // A sale moves tokens into the curve, which is no buy
+        detector.observe(
+            102,
+            &transaction(seller, mint, &[(curve, 600, 650), (seller, 80, 30)]),
+        );
+        assert!(detector.stats(mint, 1_000).is_none(), "window still open");
+
+        // Past the window
+        detector.observe(
+            103,
+            &transaction(seller, mint, &[(curve, 650, 600), (seller, 30, 80)]),
+        );
+        let stats = detector.stats(mint, 1_000).expect("settled");
+        assert_eq!(stats.buyers, 2);
+        assert_eq!(stats.bundled_wallets, 2);
+        assert_eq!(stats.bundled_pct, 40.0);
+        assert!(matches!(detector.verdict(mint, 1_000), Verdict::Reject(_)));
+    }
+
+    #[test]
+    fn ignores_untracked_mints() {
+        let detector = detector();
+        let token = token(Pubkey::new_unique(), Pubkey::new_unique());
+        detector.launch(&token);
+        let other = Pubkey::new_unique().to_string();
+
+        detector.observe(
+            101,
+            &transaction(
+                Pubkey::new_unique(),
+                &other,
+                &[(Pubkey::new_unique(), 0, 500)],
+            ),
+        );
+        detector.observe(103, &transaction(Pubkey::new_unique(), &other, &[]));
+        let stats = detector.stats(&token.mint, 1_000).expect("settled");
+        assert_eq!(stats.buyers, 0);
+        assert!(matches!(
+            detector.verdict(&token.mint, 1_000),
+            Verdict::Pass
+        ));
+    }
+}
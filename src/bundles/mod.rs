@@ -0,0 +1,5 @@
+pub mod config;
+pub mod detector;
+
+pub use config::BundleConfig;
+pub use detector::BundleDetector;
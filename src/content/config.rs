@@ -0,0 +1,152 @@
+use std::env;
+use std::str::FromStr;
+
+use regex::Regex;
+
+use crate::content::copycat::CopycatKey;
+use crate::utils::config::optional_var;
+
+/// Social link a token's metadata can carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Social {
+    Twitter,
+    Telegram,
+    Website,
+}
+
+impl FromStr for Social {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "twitter" => Ok(Social::Twitter),
+            "telegram" => Ok(Social::Telegram),
+            "website" => Ok(Social::Website),
+            other => Err(format!(
+                "unknown social '{}', expected twitter, telegram or website",
+                other
+            )),
+        }
+    }
+}
+
+/// Socials a token must link to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocialsRule {
+    None,
+    /// At least one of them
+    Any,
+    All(Vec<Social>),
+}
+
+/// Content rules on a token's name, symbol and off-chain metadata, and how
+/// that metadata is fetched. Unset rules are not applied.
+#[derive(Debug, Clone)]
+pub struct ContentConfig {
+    /// Apply the rules at all
+    pub enabled: bool,
+    pub name_allow: Option<Regex>,
+    pub name_deny: Option<Regex>,
+    pub symbol_allow: Option<Regex>,
+    pub symbol_deny: Option<Regex>,
+    pub description_allow: Option<Regex>,
+    pub description_deny: Option<Regex>,
+    pub require_socials: SocialsRule,
+    /// How long launches stay in the copycat index
+    pub copycat_window_secs: u64,
+    pub copycat_match: Vec<CopycatKey>,
+    /// Only accept the first launch of a name, symbol or image in the window
+    pub copycat_require_first: bool,
+    pub copycat_max_clones: Option<usize>,
+    /// Only accept tokens copying something launched this many times, to
+    /// follow a trend
+    pub copycat_min_clones: Option<usize>,
+    pub fetch_timeout_ms: u64,
+    pub ipfs_gateway: String,
+    pub arweave_gateway: String,
+    /// Resolved metadata documents kept in memory
+    pub cache_capacity: usize,
+}
+
+impl ContentConfig {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let require_socials = match env::var("CONTENT_REQUIRE_SOCIALS") {
+            Ok(value) if value.trim() == "any" => SocialsRule::Any,
+            Ok(value) if !value.trim().is_empty() => SocialsRule::All(
+                value
+                    .split(',')
+                    .map(Social::from_str)
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => SocialsRule::None,
+        };
+
+        let copycat_match = match env::var("CONTENT_COPYCAT_MATCH") {
+            Ok(value) if !value.trim().is_empty() => value
+                .split(',')
+                .map(CopycatKey::from_str)
+                .collect::<Result<_, _>>()?,
+            _ => vec![CopycatKey::Name],
+        };
+
+        Ok(ContentConfig {
+            enabled: optional_var("CONTENT_FILTERS")?.unwrap_or(true),
+            name_allow: optional_var("CONTENT_NAME_ALLOW")?,
+            name_deny: optional_var("CONTENT_NAME_DENY")?,
+            symbol_allow: optional_var("CONTENT_SYMBOL_ALLOW")?,
+            symbol_deny: optional_var("CONTENT_SYMBOL_DENY")?,
+            description_allow: optional_var("CONTENT_DESCRIPTION_ALLOW")?,
+            description_deny: optional_var("CONTENT_DESCRIPTION_DENY")?,
+            require_socials,
+            copycat_window_secs: optional_var("CONTENT_COPYCAT_WINDOW_SECS")?.unwrap_or(600),
+            copycat_match,
+            copycat_require_first: optional_var("CONTENT_COPYCAT_REQUIRE_FIRST")?.unwrap_or(false),
+            copycat_max_clones: optional_var("CONTENT_COPYCAT_MAX_CLONES")?,
+            copycat_min_clones: optional_var("CONTENT_COPYCAT_MIN_CLONES")?,
+            fetch_timeout_ms: optional_var("CONTENT_FETCH_TIMEOUT_MS")?.unwrap_or(3000),
+            ipfs_gateway: env::var("CONTENT_IPFS_GATEWAY")
+                .ok()
+                .filter(|gateway| !gateway.is_empty())
+                .unwrap_or_else(|| "https://ipfs.io/ipfs/".to_string()),
+            arweave_gateway: env::var("CONTENT_ARWEAVE_GATEWAY")
+                .ok()
+                .filter(|gateway| !gateway.is_empty())
+                .unwrap_or_else(|| "https://arweave.net/".to_string()),
+            cache_capacity: optional_var("CONTENT_CACHE_CAPACITY")?.unwrap_or(10_000),
+        })
+    }
+
+    /// Whether any rule needs the off-chain metadata document.
+    pub fn needs_metadata(&self) -> bool {
+        self.description_allow.is_some()
+            || self.description_deny.is_some()
+            || self.require_socials != SocialsRule::None
+            || self.copycat_match.contains(&CopycatKey::Image)
+    }
+}
+
+#[cfg(test)]
+impl ContentConfig {
+    /// No rules, metadata fetched from `gateway` for IPFS and Arweave alike.
+    pub fn with_gateway(gateway: &str) -> Self {
+        ContentConfig {
+            enabled: true,
+            name_allow: None,
+            name_deny: None,
+            symbol_allow: None,
+            symbol_deny: None,
+            description_allow: None,
+            description_deny: None,
+            require_socials: SocialsRule::None,
+            copycat_window_secs: 600,
+            copycat_match: vec![CopycatKey::Name],
+            copycat_require_first: false,
+            copycat_max_clones: None,
+            copycat_min_clones: None,
+            fetch_timeout_ms: 1000,
+            ipfs_gateway: format!("{}/ipfs", gateway),
+            arweave_gateway: format!("{}/ar", gateway),
+            cache_capacity: 16,
+        }
+    }
+}
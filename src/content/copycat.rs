@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// What two launches can share to count as clones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopycatKey {
+    Name,
+    Symbol,
+    Image,
+}
+
+impl FromStr for CopycatKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "name" => Ok(CopycatKey::Name),
+            "symbol" => Ok(CopycatKey::Symbol),
+            "image" => Ok(CopycatKey::Image),
+            other => Err(format!(
+                "unknown copycat key '{}', expected name, symbol or image",
+                other
+            )),
+        }
+    }
+}
+
+/// How a launch relates to the earlier ones in the window.
+#[derive(Debug, Clone, Default)]
+pub struct CopycatInfo {
+    /// No earlier launch in the window shares a key with it
+    pub is_first: bool,
+    /// Earlier launches in the window sharing a key with it
+    pub clone_count: usize,
+    /// Earliest of those launches
+    pub original: Option<String>,
+}
+
+struct Launch {
+    seen_at: Instant,
+    created_slot: u64,
+    mint: String,
+    name: String,
+    symbol: String,
+    image: Option<String>,
+}
+
+/// Rolling index of recent launches by normalized name, symbol and image.
+/// Launches are kept in creation order and stay in it for the window whether
+/// or not they are still tracked. Images are attached once the metadata is
+/// resolved, so only launches whose image is known match on it.
+pub struct CopycatIndex {
+    window: Duration,
+    keys: Vec<CopycatKey>,
+    launches: Mutex<VecDeque<Launch>>,
+}
+
+impl CopycatIndex {
+    pub fn new(window: Duration, keys: Vec<CopycatKey>) -> Self {
+        Self {
+            window,
+            keys,
+            launches: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Adds a launch by name and symbol when it is created, before anything
+    /// about it is fetched.
+    pub fn record(&self, mint: &str, name: &str, symbol: &str, created_slot: u64) {
+        let mut launches = self.launches.lock().unwrap_or_else(|e| e.into_inner());
+        while launches
+            .front()
+            .is_some_and(|earlier| earlier.seen_at.elapsed() > self.window)
+        {
+            launches.pop_front();
+        }
+        if launches.iter().any(|launch| launch.mint == mint) {
+            return;
+        }
+
+        // Launches mostly arrive in slot order, search from the back
+        let position = launches
+            .iter()
+            .rposition(|earlier| earlier.created_slot <= created_slot)
+            .map_or(0, |index| index + 1);
+        launches.insert(
+            position,
+            Launch {
+                seen_at: Instant::now(),
+                created_slot,
+                mint: mint.to_string(),
+                name: normalize(name),
+                symbol: normalize(symbol),
+                image: None,
+            },
+        );
+    }
+
+    /// Attaches the image of a recorded launch once its metadata is resolved.
+    pub fn attach_image(&self, mint: &str, image: &str) {
+        let mut launches = self.launches.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(launch) = launches.iter_mut().rev().find(|launch| launch.mint == mint) {
+            launch.image = Some(image_hash(image));
+        }
+    }
+
+    /// Counts the launches created before this one that it duplicates. A
+    /// launch no longer (or never) in the index counts as first.
+    pub fn standing(&self, mint: &str) -> CopycatInfo {
+        let launches = self.launches.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(position) = launches.iter().position(|launch| launch.mint == mint) else {
+            return CopycatInfo {
+                is_first: true,
+                ..Default::default()
+            };
+        };
+
+        let launch = &launches[position];
+        let clones: Vec<&Launch> = launches
+            .iter()
+            .take(position)
+            .filter(|earlier| self.is_clone(earlier, launch))
+            .collect();
+        CopycatInfo {
+            is_first: clones.is_empty(),
+            clone_count: clones.len(),
+            original: clones.first().map(|earlier| earlier.mint.clone()),
+        }
+    }
+
+    fn is_clone(&self, earlier: &Launch, launch: &Launch) -> bool {
+        self.keys.iter().any(|key| match key {
+            CopycatKey::Name => !launch.name.is_empty() && earlier.name == launch.name,
+            CopycatKey::Symbol => !launch.symbol.is_empty() && earlier.symbol == launch.symbol,
+            CopycatKey::Image => launch.image.is_some() && earlier.image == launch.image,
+        })
+    }
+}
+
+/// Lowercase letters and digits only, so "PEPE 2.0" and "pepe20" match.
+pub fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Identifies an image independently of the gateway serving it. IPFS paths
+/// are content hashes already, so the CID is kept; other URLs are compared
+/// whole.
+fn image_hash(image: &str) -> String {
+    let image = image.trim();
+    let path = match image.rfind("/ipfs/") {
+        Some(start) => &image[start + "/ipfs/".len()..],
+        None => image.strip_prefix("ipfs://").unwrap_or(image),
+    };
+    path.split(['?', '#']).next().unwrap_or(path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> CopycatIndex {
+        CopycatIndex::new(
+            Duration::from_secs(600),
+            vec![CopycatKey::Name, CopycatKey::Image],
+        )
+    }
+
+    #[test]
+    fn earlier_launch_stays_original_whatever_the_arrival_order() {
+        let index = index();
+        index.record("clone", "PEPE 2.0", "PEPE", 12);
+        index.record("original", "pepe20", "P2", 10);
+
+        let original = index.standing("original");
+        assert!(original.is_first);
+        assert_eq!(original.clone_count, 0);
+
+        let clone = index.standing("clone");
+        assert!(!clone.is_first);
+        assert_eq!(clone.clone_count, 1);
+        assert_eq!(clone.original.as_deref(), Some("original"));
+    }
+
+    #[test]
+    fn images_match_once_attached() {
+        let index = index();
+        index.record("a", "alpha", "A", 1);
+        index.record("b", "beta", "B", 2);
+        index.attach_image("b", "https://gateway.example/ipfs/Qmcid?x=1");
+        assert!(index.standing("b").is_first);
+
+        index.attach_image("a", "ipfs://Qmcid");
+        let b = index.standing("b");
+        assert_eq!(b.clone_count, 1);
+        assert_eq!(b.original.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn unknown_launch_counts_as_first() {
+        assert!(index().standing("missing").is_first);
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use regex::Regex;
+use tracing::{debug, warn};
+
+use crate::content::config::{ContentConfig, SocialsRule};
+use crate::content::copycat::{CopycatIndex, CopycatInfo};
+use crate::content::resolver::{MetadataResolver, OffchainMetadata};
+use crate::pipeline::Verdict;
+use crate::types::TokenInfo;
+
+/// Content verdicts of the tracked tokens. The tracker screens a token when
+/// it joins a batch, the strategy reads the verdict before buying.
+pub struct ContentFilter {
+    config: ContentConfig,
+    resolver: MetadataResolver,
+    copycats: CopycatIndex,
+    verdicts: RwLock<HashMap<String, Verdict>>,
+    /// Copycat standing of the tracked tokens
+    copycat_info: RwLock<HashMap<String, CopycatInfo>>,
+}
+
+impl ContentFilter {
+    pub fn new(config: ContentConfig) -> Self {
+        Self {
+            resolver: MetadataResolver::new(&config),
+            copycats: CopycatIndex::new(
+                Duration::from_secs(config.copycat_window_secs),
+                config.copycat_match.clone(),
+            ),
+            config,
+            verdicts: RwLock::new(HashMap::new()),
+            copycat_info: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn verdict(&self, mint: &str) -> Verdict {
+        if !self.config.enabled {
+            return Verdict::Pass;
+        }
+        self.verdicts
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+            .cloned()
+            .unwrap_or(Verdict::Pending)
+    }
+
+    /// Whether the token is the first of its name (or symbol, or image) in
+    /// the copycat window and how many launches it copies.
+    pub fn copycat(&self, mint: &str) -> Option<CopycatInfo> {
+        self.copycat_info
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+            .cloned()
+    }
+
+    /// Records a new launch in the copycat index, whether or not the filters
+    /// are enabled. Called as tokens arrive so the index follows creation
+    /// order rather than the order metadata fetches complete in.
+    pub fn index(&self, token: &TokenInfo) {
+        self.copycats
+            .record(&token.mint, &token.name, &token.symbol, token.created_slot);
+    }
+
+    /// Fetches the off-chain metadata when a rule needs it, attaches the
+    /// image to the launch in the copycat index and applies every rule. A document that
+    /// cannot be fetched counts as one without description, socials or
+    /// image.
+    pub async fn screen(&self, token: &TokenInfo) {
+        if !self.config.enabled {
+            return;
+        }
+        let config = &self.config;
+
+        let mut violations = Vec::new();
+        check_text(
+            "name",
+            &token.name,
+            &config.name_allow,
+            &config.name_deny,
+            &mut violations,
+        );
+        check_text(
+            "symbol",
+            &token.symbol,
+            &config.symbol_allow,
+            &config.symbol_deny,
+            &mut violations,
+        );
+
+        let metadata = if config.needs_metadata() {
+            match self.resolver.resolve(&token.uri).await {
+                Ok(metadata) => {
+                    token.span().in_scope(|| {
+                        debug!(
+                            image = ?metadata.image,
+                            twitter = ?metadata.twitter,
+                            telegram = ?metadata.telegram,
+                            website = ?metadata.website,
+                            "📝 Metadata resolved"
+                        )
+                    });
+                    metadata
+                }
+                Err(e) => {
+                    warn!(mint = %token.mint, uri = %token.uri, error = %e, "⚠️ Metadata fetch failed");
+                    Default::default()
+                }
+            }
+        } else {
+            Arc::default()
+        };
+        self.check_metadata(&metadata, &mut violations);
+
+        if let Some(image) = &metadata.image {
+            self.copycats.attach_image(&token.mint, image);
+        }
+        let copycat = self.copycats.standing(&token.mint);
+        if !copycat.is_first {
+            token.span().in_scope(|| {
+                debug!(
+                    clone_count = copycat.clone_count,
+                    original = ?copycat.original,
+                    "📝 Copy of an earlier launch"
+                )
+            });
+        }
+        self.check_copycat(&copycat, &mut violations);
+        self.copycat_info
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(token.mint.clone(), copycat);
+
+        let verdict = if violations.is_empty() {
+            Verdict::Pass
+        } else {
+            Verdict::Reject(violations)
+        };
+        self.verdicts
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(token.mint.clone(), verdict);
+    }
+
+    /// Drops the verdicts of tokens that are no longer monitored.
+    pub fn remove(&self, mints: &[String]) {
+        let mut verdicts = self.verdicts.write().unwrap_or_else(|e| e.into_inner());
+        let mut copycat_info = self.copycat_info.write().unwrap_or_else(|e| e.into_inner());
+        for mint in mints {
+            verdicts.remove(mint);
+            copycat_info.remove(mint);
+        }
+    }
+
+    fn check_copycat(&self, copycat: &CopycatInfo, violations: &mut Vec<String>) {
+        let config = &self.config;
+        if config.copycat_require_first && !copycat.is_first {
+            violations.push(format!("copies {} earlier launch(es)", copycat.clone_count));
+        }
+        if let Some(max) = config.copycat_max_clones
+            && copycat.clone_count > max
+        {
+            violations.push(format!("{} clones above {}", copycat.clone_count, max));
+        }
+        if let Some(min) = config.copycat_min_clones
+            && copycat.clone_count < min
+        {
+            violations.push(format!("{} clones below {}", copycat.clone_count, min));
+        }
+    }
+
+    fn check_metadata(&self, metadata: &OffchainMetadata, violations: &mut Vec<String>) {
+        let config = &self.config;
+        let description = metadata.description.as_deref().unwrap_or_default();
+        check_text(
+            "description",
+            description,
+            &config.description_allow,
+            &config.description_deny,
+            violations,
+        );
+
+        match &config.require_socials {
+            SocialsRule::None => {}
+            SocialsRule::Any => {
+                if metadata.twitter.is_none()
+                    && metadata.telegram.is_none()
+                    && metadata.website.is_none()
+                {
+                    violations.push("no socials".to_string());
+                }
+            }
+            SocialsRule::All(socials) => {
+                for social in socials {
+                    if metadata.social(*social).is_none() {
+                        violations.push(format!("no {:?} link", social).to_lowercase());
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn check_text(
+    field: &str,
+    value: &str,
+    allow: &Option<Regex>,
+    deny: &Option<Regex>,
+    violations: &mut Vec<String>,
+) {
+    if let Some(allow) = allow
+        && !allow.is_match(value)
+    {
+        violations.push(format!("{} '{}' not allowed", field, value));
+    }
+    if let Some(deny) = deny
+        && deny.is_match(value)
+    {
+        violations.push(format!("{} '{}' denied", field, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::config::Social;
+    use crate::utils::stub_server::{StubResponse, StubServer};
+
+    fn token(mint: &str, name: &str, symbol: &str, uri: &str) -> TokenInfo {
+        TokenInfo::new(
+            mint.to_string(),
+            "curve".to_string(),
+            name.to_string(),
+            symbol.to_string(),
+            uri.to_string(),
+            "creator".to_string(),
+            1,
+        )
+    }
+
+    async fn screen(config: ContentConfig, token: &TokenInfo) -> Verdict {
+        let filter = ContentFilter::new(config);
+        filter.index(token);
+        filter.screen(token).await;
+        filter.verdict(&token.mint)
+    }
+
+    fn violations(verdict: Verdict) -> Vec<String> {
+        match verdict {
+            Verdict::Reject(violations) => violations,
+            other => panic!("expected a rejection, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn applies_name_and_symbol_patterns() {
+        let mut config = ContentConfig::with_gateway("http://127.0.0.1:9");
+        config.name_allow = Some(Regex::new("(?i)pepe").unwrap());
+        config.symbol_deny = Some(Regex::new("^SCAM$").unwrap());
+
+        let allowed = token("a", "Pepe Classic", "PEPE", "ipfs://unused");
+        assert!(matches!(
+            screen(config.clone(), &allowed).await,
+            Verdict::Pass
+        ));
+
+        let rejected = token("b", "Doge", "SCAM", "ipfs://unused");
+        assert_eq!(
+            violations(screen(config, &rejected).await),
+            vec!["name 'Doge' not allowed", "symbol 'SCAM' denied"]
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_description_patterns_to_the_fetched_document() {
+        let server = StubServer::start(vec![(
+            "/ipfs/QmRug",
+            StubResponse::json(r#"{"description": "guaranteed 100x, rug free"}"#),
+        )])
+        .await;
+        let mut config = ContentConfig::with_gateway(&server.url);
+        config.description_deny = Some(Regex::new("(?i)guaranteed").unwrap());
+
+        let rugged = token("a", "Rug", "RUG", "ipfs://QmRug");
+        assert_eq!(
+            violations(screen(config, &rugged).await),
+            vec!["description 'guaranteed 100x, rug free' denied"]
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_each_socials_rule() {
+        let server = StubServer::start(vec![
+            (
+                "/ipfs/QmTwitter",
+                StubResponse::json(r#"{"twitter": "https://x.com/token"}"#),
+            ),
+            ("/ipfs/QmBare", StubResponse::json("{}")),
+        ])
+        .await;
+        let with_twitter = token("a", "Linked", "LNK", "ipfs://QmTwitter");
+        let bare = token("b", "Bare", "BARE", "ipfs://QmBare");
+        // Unreachable documents count as ones without socials
+        let missing = token("c", "Gone", "GONE", "ipfs://QmMissing");
+        let config = |rule| {
+            let mut config = ContentConfig::with_gateway(&server.url);
+            config.require_socials = rule;
+            config
+        };
+
+        assert!(matches!(
+            screen(config(SocialsRule::None), &bare).await,
+            Verdict::Pass
+        ));
+
+        assert!(matches!(
+            screen(config(SocialsRule::Any), &with_twitter).await,
+            Verdict::Pass
+        ));
+        assert_eq!(
+            violations(screen(config(SocialsRule::Any), &bare).await),
+            vec!["no socials"]
+        );
+        assert_eq!(
+            violations(screen(config(SocialsRule::Any), &missing).await),
+            vec!["no socials"]
+        );
+
+        let all = || SocialsRule::All(vec![Social::Twitter, Social::Telegram]);
+        assert_eq!(
+            violations(screen(config(all()), &with_twitter).await),
+            vec!["no telegram link"]
+        );
+        assert_eq!(
+            violations(screen(config(all()), &bare).await),
+            vec!["no twitter link", "no telegram link"]
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_everything_when_disabled() {
+        let mut config = ContentConfig::with_gateway("http://127.0.0.1:9");
+        config.enabled = false;
+        config.name_deny = Some(Regex::new(".*").unwrap());
+
+        let denied = token("a", "Anything", "ANY", "ipfs://unused");
+        assert!(matches!(screen(config, &denied).await, Verdict::Pass));
+    }
+}
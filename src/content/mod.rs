@@ -0,0 +1,7 @@
+pub mod config;
+pub mod copycat;
+pub mod filters;
+pub mod resolver;
+
+pub use config::ContentConfig;
+pub use filters::ContentFilter;
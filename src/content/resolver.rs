@@ -0,0 +1,300 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::content::config::{ContentConfig, Social};
+
+// Token metadata documents are a few hundred bytes, anything far larger is not one
+const MAX_DOCUMENT_BYTES: usize = 256 * 1024;
+
+/// Fields of a token's off-chain metadata JSON used by the filters.
+#[derive(Debug, Clone, Default)]
+pub struct OffchainMetadata {
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub website: Option<String>,
+}
+
+impl OffchainMetadata {
+    /// Reads the usual top-level fields, falling back to an `extensions`
+    /// object for the socials.
+    pub fn from_json(document: &Value) -> Self {
+        let field = |name: &str| {
+            [&document[name], &document["extensions"][name]]
+                .into_iter()
+                .find_map(|value| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        OffchainMetadata {
+            description: field("description"),
+            image: field("image"),
+            twitter: field("twitter"),
+            telegram: field("telegram"),
+            website: field("website"),
+        }
+    }
+
+    pub fn social(&self, social: Social) -> Option<&str> {
+        match social {
+            Social::Twitter => self.twitter.as_deref(),
+            Social::Telegram => self.telegram.as_deref(),
+            Social::Website => self.website.as_deref(),
+        }
+    }
+}
+
+/// Fetches metadata documents from HTTP(S), IPFS and Arweave URIs with a
+/// timeout, keeping the most recent ones in memory.
+pub struct MetadataResolver {
+    client: reqwest::Client,
+    ipfs_gateway: String,
+    arweave_gateway: String,
+    cache: Mutex<Cache>,
+}
+
+struct Cache {
+    entries: HashMap<String, Arc<OffchainMetadata>>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl Cache {
+    /// Adds `uri`, evicting the oldest entry when full. A URI resolved
+    /// twice concurrently is already cached and left as it is.
+    fn insert(&mut self, uri: &str, metadata: Arc<OffchainMetadata>) {
+        if self.capacity == 0 || self.entries.contains_key(uri) {
+            return;
+        }
+        if self.order.len() >= self.capacity
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(uri.to_string(), metadata);
+        self.order.push_back(uri.to_string());
+    }
+}
+
+impl MetadataResolver {
+    pub fn new(config: &ContentConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.fetch_timeout_ms))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            ipfs_gateway: with_trailing_slash(&config.ipfs_gateway),
+            arweave_gateway: with_trailing_slash(&config.arweave_gateway),
+            cache: Mutex::new(Cache {
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+                capacity: config.cache_capacity,
+            }),
+        }
+    }
+
+    pub async fn resolve(
+        &self,
+        uri: &str,
+    ) -> Result<Arc<OffchainMetadata>, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(cached) = self
+            .cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .get(uri)
+        {
+            return Ok(cached.clone());
+        }
+
+        let url = self.gateway_url(uri)?;
+        let mut response = self.client.get(&url).send().await?.error_for_status()?;
+        if response
+            .content_length()
+            .is_some_and(|len| len as usize > MAX_DOCUMENT_BYTES)
+        {
+            return Err(format!("metadata at {} is too large", url).into());
+        }
+        // The length may be missing or wrong, stop reading once past the limit
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > MAX_DOCUMENT_BYTES {
+                return Err(format!("metadata at {} is too large", url).into());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let document: Value = serde_json::from_slice(&body)?;
+        let metadata = Arc::new(OffchainMetadata::from_json(&document));
+
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(uri, metadata.clone());
+        Ok(metadata)
+    }
+
+    /// HTTP URL to fetch a metadata URI from.
+    fn gateway_url(&self, uri: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let uri = uri.trim();
+        if let Some(path) = uri.strip_prefix("ipfs://") {
+            return Ok(format!(
+                "{}{}",
+                self.ipfs_gateway,
+                path.trim_start_matches("ipfs/")
+            ));
+        }
+        if let Some(path) = uri.strip_prefix("ar://") {
+            return Ok(format!("{}{}", self.arweave_gateway, path));
+        }
+        if uri.starts_with("https://") || uri.starts_with("http://") {
+            return Ok(uri.to_string());
+        }
+        Err(format!("unsupported metadata uri '{}'", uri).into())
+    }
+}
+
+fn with_trailing_slash(gateway: &str) -> String {
+    format!("{}/", gateway.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use axum::http::StatusCode;
+
+    use super::*;
+    use crate::utils::stub_server::{StubResponse, StubServer};
+
+    const DOCUMENT: &str = r#"{
+        "description": "to the moon",
+        "image": "ipfs://QmImage",
+        "twitter": "https://x.com/token",
+        "extensions": { "telegram": "https://t.me/token" }
+    }"#;
+
+    #[tokio::test]
+    async fn rewrites_ipfs_and_arweave_uris_to_the_gateways() {
+        let server = StubServer::start(vec![
+            ("/ipfs/QmDoc", StubResponse::json(DOCUMENT)),
+            ("/ar/TxDoc", StubResponse::json(DOCUMENT)),
+        ])
+        .await;
+        let resolver = MetadataResolver::new(&ContentConfig::with_gateway(&server.url));
+
+        let metadata = resolver.resolve("ipfs://QmDoc").await.unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("to the moon"));
+        assert_eq!(metadata.telegram.as_deref(), Some("https://t.me/token"));
+        assert!(resolver.resolve("ipfs://ipfs/QmDoc").await.is_ok());
+        assert!(resolver.resolve("ar://TxDoc").await.is_ok());
+        assert!(
+            resolver
+                .resolve(&format!("{}/ipfs/QmDoc", server.url))
+                .await
+                .is_ok()
+        );
+        assert!(resolver.resolve("ftp://elsewhere").await.is_err());
+
+        // Cached by URI, so the three spellings of the same CID each fetch once
+        assert_eq!(server.hits("/ipfs/QmDoc"), 3);
+        assert_eq!(server.hits("/ar/TxDoc"), 1);
+    }
+
+    #[tokio::test]
+    async fn times_out_slow_gateways() {
+        let server = StubServer::start(vec![(
+            "/ipfs/QmSlow",
+            StubResponse::json(DOCUMENT).delayed(Duration::from_secs(2)),
+        )])
+        .await;
+        let mut config = ContentConfig::with_gateway(&server.url);
+        config.fetch_timeout_ms = 100;
+        let resolver = MetadataResolver::new(&config);
+
+        let error = resolver.resolve("ipfs://QmSlow").await.unwrap_err();
+        assert!(
+            error
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(reqwest::Error::is_timeout)
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_oversized_documents() {
+        let oversized = format!(r#"{{"description": "{}"}}"#, "x".repeat(MAX_DOCUMENT_BYTES));
+        let server = StubServer::start(vec![("/ipfs/QmBig", StubResponse::json(oversized))]).await;
+        let resolver = MetadataResolver::new(&ContentConfig::with_gateway(&server.url));
+
+        let error = resolver.resolve("ipfs://QmBig").await.unwrap_err();
+        assert!(error.to_string().contains("too large"));
+    }
+
+    #[tokio::test]
+    async fn stops_reading_oversized_streamed_documents() {
+        let oversized = format!(r#"{{"description": "{}"}}"#, "x".repeat(MAX_DOCUMENT_BYTES));
+        let server = StubServer::start(vec![
+            ("/ipfs/QmBig", StubResponse::json(oversized).chunked()),
+            ("/ipfs/QmDoc", StubResponse::json(DOCUMENT).chunked()),
+        ])
+        .await;
+        let resolver = MetadataResolver::new(&ContentConfig::with_gateway(&server.url));
+
+        let error = resolver.resolve("ipfs://QmBig").await.unwrap_err();
+        assert!(error.to_string().contains("too large"));
+        let metadata = resolver.resolve("ipfs://QmDoc").await.unwrap();
+        assert_eq!(metadata.description.as_deref(), Some("to the moon"));
+    }
+
+    #[test]
+    fn repeated_inserts_keep_other_entries() {
+        let mut cache = Cache {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity: 2,
+        };
+        let metadata = Arc::new(OffchainMetadata::default());
+        cache.insert("a", metadata.clone());
+        cache.insert("b", metadata.clone());
+        cache.insert("a", metadata.clone());
+        assert!(cache.entries.contains_key("a"));
+        assert!(cache.entries.contains_key("b"));
+        assert_eq!(cache.order.len(), 2);
+
+        cache.insert("c", metadata);
+        assert!(!cache.entries.contains_key("a"));
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn serves_repeated_uris_from_the_cache() {
+        let server = StubServer::start(vec![("/ipfs/QmDoc", StubResponse::json(DOCUMENT))]).await;
+        let resolver = MetadataResolver::new(&ContentConfig::with_gateway(&server.url));
+
+        let first = resolver.resolve("ipfs://QmDoc").await.unwrap();
+        let second = resolver.resolve("ipfs://QmDoc").await.unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(server.hits("/ipfs/QmDoc"), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_fetches_are_not_cached() {
+        let server = StubServer::start(vec![(
+            "/ipfs/QmMissing",
+            StubResponse::status(StatusCode::BAD_GATEWAY),
+        )])
+        .await;
+        let resolver = MetadataResolver::new(&ContentConfig::with_gateway(&server.url));
+
+        assert!(resolver.resolve("ipfs://QmMissing").await.is_err());
+        assert!(resolver.resolve("ipfs://QmMissing").await.is_err());
+        assert_eq!(server.hits("/ipfs/QmMissing"), 2);
+    }
+}
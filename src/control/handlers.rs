@@ -0,0 +1,429 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::control::server::ControlState;
+use crate::execute_ixs::accounts;
+use crate::pipeline::executor::{ExecutorCommand, SellOrder};
+use crate::pipeline::{Signal, SniperEvent};
+use crate::types::{BondingCurve, TokenInfo};
+
+type AppState = State<Arc<ControlState>>;
+type ApiResult = Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)>;
+
+fn ok(body: Value) -> ApiResult {
+    Ok((StatusCode::OK, Json(body)))
+}
+
+fn error(status: StatusCode, message: impl std::fmt::Display) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({ "error": message.to_string() })))
+}
+
+#[derive(Deserialize)]
+pub struct SettingsUpdate {
+    pub min_market_cap_usd: Option<f64>,
+    pub buy_amount_lamports: Option<u64>,
+    pub slippage_bps: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct KillSwitchRequest {
+    pub engaged: bool,
+}
+
+#[derive(Deserialize)]
+pub struct MintRequest {
+    pub mint: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreatorRequest {
+    pub creator: String,
+}
+
+#[derive(Deserialize)]
+pub struct SellRequest {
+    pub mint: String,
+    /// Raw token amount, defaults to the whole open position
+    pub amount: Option<u64>,
+}
+
+pub async fn health(State(state): AppState) -> ApiResult {
+    let snapshot = state.snapshot.read().await;
+    let streams: Vec<Value> = snapshot
+        .streams
+        .iter()
+        .map(|(name, stream)| {
+            json!({
+                "stream": name,
+                "status": format!("{:?}", stream.status),
+                "last_slot": stream.last_slot,
+            })
+        })
+        .collect();
+
+    ok(json!({
+        "status": "ok",
+        "uptime_secs": state.started_at.elapsed().as_secs(),
+        "paused": state.controls.is_paused(),
+        "collecting": snapshot.collecting.len(),
+        "monitoring": snapshot.monitoring.len(),
+        "streams": streams,
+    }))
+}
+
+pub async fn tokens(State(state): AppState) -> ApiResult {
+    let snapshot = state.snapshot.read().await;
+    let collecting: Vec<Value> = snapshot
+        .collecting
+        .iter()
+        .map(|token| {
+            json!({
+                "mint": token.mint,
+                "name": token.name,
+                "symbol": token.symbol,
+                "creator": token.creator,
+                "created_slot": token.created_slot,
+            })
+        })
+        .collect();
+    let monitoring: Vec<Value> = snapshot
+        .monitoring
+        .iter()
+        .map(|monitored| {
+            json!({
+                "mint": monitored.token.mint,
+                "name": monitored.token.name,
+                "symbol": monitored.token.symbol,
+                "creator": monitored.token.creator,
+                "market_cap_sol": monitored.market_cap_sol,
+                "market_cap_usd": monitored.market_cap_usd,
+                "completion_percent": monitored.completion_percent,
+                "fdv_sol": monitored.fdv_sol,
+                "sol_to_graduate": monitored.sol_to_graduate,
+                "elapsed_secs": monitored.elapsed_secs,
+            })
+        })
+        .collect();
+
+    ok(json!({ "collecting": collecting, "monitoring": monitoring }))
+}
+
+pub async fn positions(State(state): AppState) -> ApiResult {
+    let snapshot = state.snapshot.read().await;
+    let positions: Vec<Value> = snapshot
+        .positions
+        .iter()
+        .map(|position| {
+            json!({
+                "mint": position.mint,
+                "symbol": position.symbol,
+                "amount_lamports": position.amount_lamports,
+                "tokens": position.tokens,
+                "value_lamports": position.value_lamports,
+            })
+        })
+        .collect();
+
+    ok(json!(positions))
+}
+
+pub async fn signals(State(state): AppState) -> ApiResult {
+    let snapshot = state.snapshot.read().await;
+    let signals: Vec<Value> = snapshot
+        .signals
+        .iter()
+        .map(|signal| {
+            json!({
+                "mint": signal.mint,
+                "name": signal.name,
+                "symbol": signal.symbol,
+                "market_cap_sol": signal.market_cap_sol,
+                "market_cap_usd": signal.market_cap_usd,
+            })
+        })
+        .collect();
+
+    ok(json!(signals))
+}
+
+pub async fn settings(State(state): AppState) -> ApiResult {
+    ok(json!(state.controls.settings().await))
+}
+
+pub async fn update_settings(
+    State(state): AppState,
+    Json(update): Json<SettingsUpdate>,
+) -> ApiResult {
+    if update.min_market_cap_usd.is_some_and(|usd| usd < 0.0) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "min_market_cap_usd must be >= 0",
+        ));
+    }
+    if update.buy_amount_lamports == Some(0) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "buy_amount_lamports must be > 0",
+        ));
+    }
+    if update.slippage_bps.is_some_and(|bps| bps > 10_000) {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "slippage_bps must be <= 10000",
+        ));
+    }
+
+    let settings = state
+        .controls
+        .update_settings(|settings| {
+            if let Some(usd) = update.min_market_cap_usd {
+                settings.min_market_cap_usd = usd;
+            }
+            if let Some(lamports) = update.buy_amount_lamports {
+                settings.buy_amount_lamports = lamports;
+            }
+            if let Some(bps) = update.slippage_bps {
+                settings.slippage_bps = bps;
+            }
+        })
+        .await;
+    tracing::info!(?settings, "🎛️ Settings updated");
+    state
+        .bus
+        .publish(SniperEvent::SettingsUpdated(settings.clone()));
+
+    ok(json!(settings))
+}
+
+pub async fn pause(State(state): AppState) -> ApiResult {
+    state.controls.set_paused(true);
+    tracing::info!("⏸️ Automatic buying paused");
+    ok(json!({ "paused": true }))
+}
+
+pub async fn resume(State(state): AppState) -> ApiResult {
+    state.controls.set_paused(false);
+    tracing::info!("▶️ Automatic buying resumed");
+    ok(json!({ "paused": false }))
+}
+
+pub async fn wallets(State(state): AppState) -> ApiResult {
+    let wallets: Vec<Value> = state
+        .wallets
+        .iter()
+        .map(|pooled| {
+            let holdings: Vec<Value> = pooled
+                .wallet
+                .holdings()
+                .iter()
+                .map(
+                    |holding| json!({ "mint": holding.mint.to_string(), "amount": holding.amount }),
+                )
+                .collect();
+            json!({
+                "wallet": pooled.wallet.owner().to_string(),
+                "lamports": pooled.wallet.lamports(),
+                "limits": pooled.limits.status(),
+                "holdings": holdings,
+            })
+        })
+        .collect();
+
+    ok(json!(wallets))
+}
+
+pub async fn risk(State(state): AppState) -> ApiResult {
+    ok(json!(state.risk.status()))
+}
+
+/// Engages or releases the API kill switch. A kill switch file, if
+/// configured, keeps orders blocked until it is removed.
+pub async fn kill_switch(
+    State(state): AppState,
+    Json(request): Json<KillSwitchRequest>,
+) -> ApiResult {
+    state.risk.set_kill_switch(request.engaged);
+    if request.engaged {
+        tracing::warn!("🛑 Kill switch engaged, all orders are refused");
+    } else {
+        tracing::info!("✅ Kill switch released");
+    }
+    ok(json!({ "kill_switch": state.risk.kill_switch_engaged() }))
+}
+
+pub async fn blocklist(State(state): AppState) -> ApiResult {
+    let (mints, creators) = state.controls.blocklists().await;
+    ok(json!({ "mints": mints, "creators": creators }))
+}
+
+pub async fn block_mint(State(state): AppState, Json(request): Json<MintRequest>) -> ApiResult {
+    parse_pubkey(&request.mint)?;
+    state.controls.block_mint(request.mint.clone()).await;
+    ok(json!({ "blocked": request.mint }))
+}
+
+pub async fn unblock_mint(State(state): AppState, Path(mint): Path<String>) -> ApiResult {
+    if !state.controls.unblock_mint(&mint).await {
+        return Err(error(StatusCode::NOT_FOUND, "mint is not blocked"));
+    }
+    ok(json!({ "unblocked": mint }))
+}
+
+pub async fn block_creator(
+    State(state): AppState,
+    Json(request): Json<CreatorRequest>,
+) -> ApiResult {
+    parse_pubkey(&request.creator)?;
+    state.controls.block_creator(request.creator.clone()).await;
+    ok(json!({ "blocked": request.creator }))
+}
+
+pub async fn unblock_creator(State(state): AppState, Path(creator): Path<String>) -> ApiResult {
+    if !state.controls.unblock_creator(&creator).await {
+        return Err(error(StatusCode::NOT_FOUND, "creator is not blocked"));
+    }
+    ok(json!({ "unblocked": creator }))
+}
+
+/// Queues a buy with the current settings, whether or not the token is
+/// tracked. Manual orders ignore pause and blocklists.
+pub async fn buy(State(state): AppState, Json(request): Json<MintRequest>) -> ApiResult {
+    let mint = parse_pubkey(&request.mint)?;
+    let bonding_curve = accounts::bonding_curve(&mint);
+    let curve = fetch_curve(&state, &bonding_curve).await?;
+
+    let tracked = state
+        .snapshot
+        .read()
+        .await
+        .monitoring
+        .iter()
+        .find(|monitored| monitored.token.mint == request.mint)
+        .map(|monitored| monitored.token.clone());
+    let token = tracked.unwrap_or_else(|| {
+        TokenInfo::new(
+            request.mint.clone(),
+            bonding_curve.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            bs58::encode(curve.creator).into_string(),
+            0,
+        )
+    });
+
+    let market_cap = state
+        .market_cap_metric
+        .market_cap(&curve, state.sol_usd_price);
+    let signal = Signal {
+        token,
+        curve,
+        market_cap_sol: market_cap.0,
+        market_cap_usd: market_cap.1,
+        // Manual orders are not subject to the threshold
+        min_market_cap_sol: 0.0,
+        slot: 0,
+        emitted_at: std::time::Instant::now(),
+    };
+
+    queue(&state, ExecutorCommand::Buy(signal)).await?;
+    tracing::info!(mint = %request.mint, "🎛️ Manual buy queued");
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "queued": "buy", "mint": request.mint })),
+    ))
+}
+
+/// Queues a sell of `amount` tokens, or of the whole open position.
+pub async fn sell(State(state): AppState, Json(request): Json<SellRequest>) -> ApiResult {
+    let mint = parse_pubkey(&request.mint)?;
+
+    let token_amount = match request.amount {
+        Some(amount) => amount,
+        None => state
+            .snapshot
+            .read()
+            .await
+            .positions
+            .iter()
+            .find(|position| position.mint == request.mint)
+            .map(|position| position.tokens)
+            .ok_or_else(|| {
+                error(
+                    StatusCode::BAD_REQUEST,
+                    "no open position for mint, pass an amount",
+                )
+            })?,
+    };
+    if token_amount == 0 {
+        return Err(error(StatusCode::BAD_REQUEST, "amount must be > 0"));
+    }
+
+    let bonding_curve = accounts::bonding_curve(&mint);
+    let curve = fetch_curve(&state, &bonding_curve).await?;
+
+    let order = SellOrder {
+        mint: request.mint.clone(),
+        bonding_curve: bonding_curve.to_string(),
+        token_amount,
+        curve,
+    };
+
+    queue(&state, ExecutorCommand::Sell(order)).await?;
+    tracing::info!(mint = %request.mint, token_amount, "🎛️ Manual sell queued");
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(json!({ "queued": "sell", "mint": request.mint, "amount": token_amount })),
+    ))
+}
+
+fn parse_pubkey(value: &str) -> Result<Pubkey, (StatusCode, Json<Value>)> {
+    Pubkey::from_str(value)
+        .map_err(|e| error(StatusCode::BAD_REQUEST, format!("invalid pubkey: {}", e)))
+}
+
+async fn fetch_curve(
+    state: &ControlState,
+    bonding_curve: &Pubkey,
+) -> Result<BondingCurve, (StatusCode, Json<Value>)> {
+    let data = state
+        .rpc_client
+        .get_account_data(bonding_curve)
+        .await
+        .map_err(|e| {
+            error(
+                StatusCode::BAD_GATEWAY,
+                format!("bonding curve lookup failed: {}", e),
+            )
+        })?;
+    let curve =
+        BondingCurve::from_account_data(&data).map_err(|e| error(StatusCode::BAD_GATEWAY, e))?;
+
+    if curve.complete {
+        return Err(error(
+            StatusCode::CONFLICT,
+            "bonding curve is complete, token has migrated",
+        ));
+    }
+    Ok(curve)
+}
+
+async fn queue(
+    state: &ControlState,
+    command: ExecutorCommand,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    state
+        .orders
+        .send(command)
+        .await
+        .map_err(|_| error(StatusCode::SERVICE_UNAVAILABLE, "executor has stopped"))
+}
@@ -0,0 +1,4 @@
+pub mod handlers;
+pub mod server;
+
+pub use server::{ControlState, serve_control_api};
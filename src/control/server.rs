@@ -0,0 +1,123 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    Router,
+    routing::{delete, get, post},
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{RwLock, broadcast, mpsc};
+
+use crate::control::handlers;
+use crate::pipeline::controls::RuntimeControls;
+use crate::pipeline::executor::ExecutorCommand;
+use crate::pipeline::snapshot::PipelineSnapshot;
+use crate::pipeline::{EventBus, Shutdown, SniperEvent};
+use crate::risk::RiskGuard;
+use crate::utils::valuation::MarketCapMetric;
+use crate::wallet::WalletPool;
+
+/// Everything the handlers need, shared behind an `Arc`.
+pub struct ControlState {
+    pub controls: Arc<RuntimeControls>,
+    pub risk: Arc<RiskGuard>,
+    pub wallets: Arc<WalletPool>,
+    pub snapshot: Arc<RwLock<PipelineSnapshot>>,
+    pub orders: mpsc::Sender<ExecutorCommand>,
+    pub rpc_client: Arc<RpcClient>,
+    pub bus: EventBus,
+    pub sol_usd_price: f64,
+    pub market_cap_metric: MarketCapMetric,
+    pub started_at: std::time::Instant,
+}
+
+impl ControlState {
+    pub fn new(
+        controls: Arc<RuntimeControls>,
+        risk: Arc<RiskGuard>,
+        wallets: Arc<WalletPool>,
+        orders: mpsc::Sender<ExecutorCommand>,
+        rpc_client: Arc<RpcClient>,
+        snapshot: PipelineSnapshot,
+        bus: EventBus,
+    ) -> Self {
+        Self {
+            controls,
+            risk,
+            wallets,
+            sol_usd_price: snapshot.sol_usd_price,
+            market_cap_metric: snapshot.market_cap_metric,
+            snapshot: Arc::new(RwLock::new(snapshot)),
+            orders,
+            rpc_client,
+            bus,
+            started_at: std::time::Instant::now(),
+        }
+    }
+}
+
+/// Serves the control API until shutdown. Only loopback addresses are
+/// accepted since the API can place orders and has no authentication.
+pub async fn serve_control_api(
+    addr: String,
+    state: ControlState,
+    events: broadcast::Receiver<SniperEvent>,
+    shutdown: Shutdown,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let socket_addr: SocketAddr = addr.parse()?;
+    if !socket_addr.ip().is_loopback() {
+        return Err(format!("CONTROL_API_ADDR must be a loopback address, got {}", addr).into());
+    }
+
+    tokio::spawn(fold_events(events, state.snapshot.clone()));
+    let state = Arc::new(state);
+
+    let app = Router::new()
+        .route("/health", get(handlers::health))
+        .route("/tokens", get(handlers::tokens))
+        .route("/positions", get(handlers::positions))
+        .route("/signals", get(handlers::signals))
+        .route(
+            "/settings",
+            get(handlers::settings).patch(handlers::update_settings),
+        )
+        .route("/pause", post(handlers::pause))
+        .route("/resume", post(handlers::resume))
+        .route("/risk", get(handlers::risk))
+        .route("/wallets", get(handlers::wallets))
+        .route("/kill-switch", post(handlers::kill_switch))
+        .route("/blocklist", get(handlers::blocklist))
+        .route("/blocklist/mints", post(handlers::block_mint))
+        .route("/blocklist/mints/{mint}", delete(handlers::unblock_mint))
+        .route("/blocklist/creators", post(handlers::block_creator))
+        .route(
+            "/blocklist/creators/{creator}",
+            delete(handlers::unblock_creator),
+        )
+        .route("/buy", post(handlers::buy))
+        .route("/sell", post(handlers::sell))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(socket_addr).await?;
+    tracing::info!("🎛️ Control API listening on http://{}", addr);
+    // Graceful shutdown drops the executor sender so the pipeline can drain
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move { shutdown.wait().await })
+        .await?;
+    Ok(())
+}
+
+async fn fold_events(
+    mut events: broadcast::Receiver<SniperEvent>,
+    snapshot: Arc<RwLock<PipelineSnapshot>>,
+) {
+    loop {
+        match events.recv().await {
+            Ok(event) => snapshot.write().await.apply(&event),
+            // Missed events only make the view slightly stale
+            Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::utils::constants::PUMP_PROGRAM;
+
+pub const PUMP_GLOBAL: &str = "4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf";
+pub const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+pub const FEE_PROGRAM: &str = "pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ";
+
+// Second seed of the fee config PDA: ["fee_config", FEE_CONFIG_SEED] under the fee program
+const FEE_CONFIG_SEED: [u8; 32] = [
+    1, 86, 224, 246, 147, 102, 90, 207, 68, 219, 21, 104, 191, 23, 91, 170, 81, 137, 203, 151, 245,
+    210, 255, 59, 101, 93, 43, 182, 253, 109, 24, 176,
+];
+
+pub fn pump_program() -> Pubkey {
+    Pubkey::from_str_const(PUMP_PROGRAM)
+}
+
+/// Fetch the global account and read its fee_recipient
+pub async fn fetch_fee_recipient(
+    rpc_client: &RpcClient,
+) -> Result<Pubkey, Box<dyn std::error::Error + Send + Sync>> {
+    // Global PDA
+    let (global_pda, _bump) = Pubkey::find_program_address(&[b"global"], &pump_program());
+    // Anchor: 8-byte discriminator + fields
+    let global_acc = rpc_client.get_account(&global_pda).await?;
+    parse_fee_recipient(&global_acc.data)
+}
+
+/// Token program that owns the mint, SPL Token or Token-2022
+pub async fn fetch_token_program(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+) -> Result<Pubkey, Box<dyn std::error::Error + Send + Sync>> {
+    let mint_acc = rpc_client.get_account(mint).await?;
+    token_program_of(mint, &mint_acc.owner)
+}
+
+/// Checks the mint's owner is one of the token programs
+pub fn token_program_of(
+    mint: &Pubkey,
+    owner: &Pubkey,
+) -> Result<Pubkey, Box<dyn std::error::Error + Send + Sync>> {
+    let owner_str = owner.to_string();
+    if owner_str == TOKEN_PROGRAM || owner_str == TOKEN_2022_PROGRAM {
+        Ok(*owner)
+    } else {
+        Err(format!("mint {} is owned by {}, not a token program", mint, owner).into())
+    }
+}
+
+/// Layout per IDL: bool initialized (1), authority pubkey (32), fee_recipient pubkey (32)
+pub fn parse_fee_recipient(
+    data: &[u8],
+) -> Result<Pubkey, Box<dyn std::error::Error + Send + Sync>> {
+    let fee_recipient_start = 8 + 1 + 32;
+    let fee_recipient_end = fee_recipient_start + 32;
+    let bytes: [u8; 32] = data
+        .get(fee_recipient_start..fee_recipient_end)
+        .ok_or("global account too short")?
+        .try_into()
+        .map_err(|_| "fee_recipient slice error")?;
+    Ok(Pubkey::new_from_array(bytes))
+}
+
+/// Event authority PDA
+pub fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &pump_program()).0
+}
+
+/// Creator vault PDA
+pub fn creator_vault(creator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"creator-vault", &creator.to_bytes()], &pump_program()).0
+}
+
+/// Global volume accumulator PDA
+pub fn global_volume_accumulator() -> Pubkey {
+    Pubkey::find_program_address(&[b"global_volume_accumulator"], &pump_program()).0
+}
+
+/// User volume accumulator PDA
+pub fn user_volume_accumulator(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"user_volume_accumulator", &user.to_bytes()],
+        &pump_program(),
+    )
+    .0
+}
+
+/// Fee config PDA
+pub fn fee_config() -> Pubkey {
+    let fee_program = Pubkey::from_str_const(FEE_PROGRAM);
+    Pubkey::find_program_address(&[b"fee_config", &FEE_CONFIG_SEED], &fee_program).0
+}
+
+/// Bonding curve PDA of a mint
+pub fn bonding_curve(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bonding-curve", &mint.to_bytes()], &pump_program()).0
+}
@@ -0,0 +1,64 @@
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use tracing::warn;
+
+use crate::pipeline::Shutdown;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+// Blockhashes stay valid for ~60s, stop trusting the cached one well before
+const MAX_AGE: Duration = Duration::from_secs(30);
+
+/// Latest blockhash, refreshed in the background so signing a templated buy
+/// doesn't wait on an RPC round trip.
+#[derive(Default)]
+pub struct BlockhashCache {
+    latest: RwLock<Option<(Hash, Instant)>>,
+}
+
+impl BlockhashCache {
+    /// The cached blockhash, if it is recent enough to use.
+    pub fn get(&self) -> Option<Hash> {
+        let latest = *self.latest.read().unwrap_or_else(|e| e.into_inner());
+        latest
+            .filter(|(_, fetched_at)| fetched_at.elapsed() < MAX_AGE)
+            .map(|(hash, _)| hash)
+    }
+
+    /// The cached blockhash, or a fresh one from RPC if it went stale.
+    pub async fn get_or_fetch(
+        &self,
+        rpc_client: &RpcClient,
+    ) -> Result<Hash, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(hash) = self.get() {
+            return Ok(hash);
+        }
+        let hash = rpc_client.get_latest_blockhash().await?;
+        self.set(hash);
+        Ok(hash)
+    }
+
+    fn set(&self, hash: Hash) {
+        *self.latest.write().unwrap_or_else(|e| e.into_inner()) = Some((hash, Instant::now()));
+    }
+}
+
+/// Keeps the cache current until shutdown.
+pub async fn run_blockhash_refresher(
+    cache: Arc<BlockhashCache>,
+    rpc_client: Arc<RpcClient>,
+    shutdown: Shutdown,
+) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = shutdown.wait() => break,
+            _ = interval.tick() => match rpc_client.get_latest_blockhash().await {
+                Ok(hash) => cache.set(hash),
+                Err(e) => warn!(error = %e, "⚠️ Blockhash refresh failed"),
+            },
+        }
+    }
+}
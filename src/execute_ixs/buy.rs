@@ -1,34 +1,34 @@
 use std::str::FromStr;
 
-use solana_client::rpc_client::RpcClient;
-use solana_commitment_config::CommitmentConfig;
+use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
     message::Message,
     pubkey::Pubkey,
-    signature::Keypair,
     signer::Signer,
     transaction::Transaction,
 };
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use tracing::{debug, info};
 
-const PUMP_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
-const PUMP_GLOBAL: &str = "4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf";
-const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
-const FEE_PROGRAM: &str = "pfeeUxB6jkeY1Hxd7CsFCAjcbHA9rWtchMGdZ6VojVZ";
+use crate::execute_ixs::accounts::{self, FEE_PROGRAM, PUMP_GLOBAL, SYSTEM_PROGRAM};
+use crate::signer::{SharedSigner, sign_transaction};
+use crate::utils::constants::PUMP_PROGRAM;
 
 // Buy instruction discriminator
 const BUY_DISCRIMINATOR: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
 
+// Buffer added on top of the buy amount for protocol and creator fees (2%)
+pub const FEE_BUFFER_BPS: u64 = 200;
+
 pub struct BuyParams {
     pub mint: String,
     pub bonding_curve: String,
-    pub associated_bonding_curve: String,
     pub creator: String,
     pub amount_sol: f64,
     pub slippage_bps: u64, // basis points (e.g., 500 = 5%)
-    pub buyer_keypair: Keypair,
+    pub signer: SharedSigner,
 }
 
 pub struct BuyTransaction {
@@ -131,7 +131,9 @@ fn build_buy_instruction(
     }
 }
 
-struct BuyAccounts {
+/// Every account a buy touches. None of them depend on the amount, so a
+/// template can derive them ahead of the signal.
+pub struct BuyAccounts {
     mint: Pubkey,
     bonding_curve: Pubkey,
     associated_bonding_curve: Pubkey,
@@ -148,21 +150,95 @@ struct BuyAccounts {
     fee_recipient: Pubkey,
 }
 
+impl BuyAccounts {
+    /// Derives the PDAs and token accounts of a buy by `buyer`. The token
+    /// accounts live under `token_program`, the owner of the mint.
+    pub fn derive(
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        creator: &Pubkey,
+        buyer: Pubkey,
+        fee_recipient: Pubkey,
+        token_program: Pubkey,
+    ) -> Self {
+        Self {
+            mint,
+            bonding_curve,
+            associated_bonding_curve: get_associated_token_address_with_program_id(
+                &bonding_curve,
+                &mint,
+                &token_program,
+            ),
+            associated_user: get_associated_token_address_with_program_id(
+                &buyer,
+                &mint,
+                &token_program,
+            ),
+            user: buyer,
+            system_program: Pubkey::from_str_const(SYSTEM_PROGRAM),
+            token_program,
+            creator_vault: accounts::creator_vault(creator),
+            event_authority: accounts::event_authority(),
+            global_volume_accumulator: accounts::global_volume_accumulator(),
+            user_volume_accumulator: accounts::user_volume_accumulator(&buyer),
+            fee_config: accounts::fee_config(),
+            fee_program: Pubkey::from_str_const(FEE_PROGRAM),
+            fee_recipient,
+        }
+    }
+
+    pub fn buyer_token_account(&self) -> Pubkey {
+        self.associated_user
+    }
+}
+
 /// Build a complete buy transaction with compute budget
-pub fn build_buy_transaction(
+pub async fn build_buy_transaction(
     params: BuyParams,
     rpc_client: &RpcClient,
     virtual_sol_reserves: u64,
     virtual_token_reserves: u64,
-) -> Result<BuyTransaction, Box<dyn std::error::Error>> {
-    let buyer = params.buyer_keypair.pubkey();
+) -> Result<BuyTransaction, Box<dyn std::error::Error + Send + Sync>> {
+    let buyer = params.signer.try_pubkey()?;
     let mint = Pubkey::from_str(&params.mint)?;
     let bonding_curve = Pubkey::from_str(&params.bonding_curve)?;
-    let associated_bonding_curve = Pubkey::from_str(&params.associated_bonding_curve)?;
     let creator = Pubkey::from_str(&params.creator)?;
 
-    // Get buyer's associated token account
-    let buyer_token_account = get_associated_token_address(&buyer, &mint);
+    let fee_recipient = accounts::fetch_fee_recipient(rpc_client).await?;
+    let token_program = accounts::fetch_token_program(rpc_client, &mint).await?;
+    let accounts = BuyAccounts::derive(
+        mint,
+        bonding_curve,
+        &creator,
+        buyer,
+        fee_recipient,
+        token_program,
+    );
+
+    // Get recent blockhash
+    let recent_blockhash = rpc_client.get_latest_blockhash().await?;
+
+    assemble_buy_transaction(
+        &accounts,
+        &params,
+        virtual_sol_reserves,
+        virtual_token_reserves,
+        recent_blockhash,
+    )
+    .await
+}
+
+/// Fills the amounts and blockhash into a buy over already derived accounts
+/// and signs it. Makes no RPC calls.
+pub async fn assemble_buy_transaction(
+    accounts: &BuyAccounts,
+    params: &BuyParams,
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    recent_blockhash: Hash,
+) -> Result<BuyTransaction, Box<dyn std::error::Error + Send + Sync>> {
+    let buyer = accounts.user;
+    let buyer_token_account = accounts.associated_user;
 
     // Convert SOL to lamports
     let amount_lamports = (params.amount_sol * 1_000_000_000.0) as u64;
@@ -175,127 +251,51 @@ pub fn build_buy_transaction(
         params.slippage_bps,
     );
 
-    println!("💰 Buy Calculation:");
-    println!(
-        "   SOL Amount: {} SOL ({} lamports)",
-        params.amount_sol, amount_lamports
-    );
-    println!("   Estimated Tokens Out: {}", estimated_tokens);
-    println!(
-        "   Min Tokens Out ({}% slippage): {}",
-        params.slippage_bps as f64 / 100.0,
-        min_tokens_out
-    );
-
-    // Build instructions
-    let mut instructions = Vec::new();
-
-    // Check if buyer token account exists, if not create ATA instruction
-    match rpc_client.get_account(&buyer_token_account) {
-        Ok(_) => {
-            println!("   ✓ Token account exists: {}", buyer_token_account);
-        }
-        Err(_) => {
-            println!(
-                "   ⚠ Creating associated token account: {}",
-                buyer_token_account
-            );
-            let create_ata_ix =
-                spl_associated_token_account::instruction::create_associated_token_account(
-                    &buyer,
-                    &buyer,
-                    &mint,
-                    &Pubkey::from_str(TOKEN_PROGRAM)?,
-                );
-            instructions.push(create_ata_ix);
-        }
-    }
-
-    // Derive PDAs and required accounts per IDL
-    let pump_program = Pubkey::from_str(PUMP_PROGRAM)?;
-    let system_program = Pubkey::from_str(SYSTEM_PROGRAM)?;
-    let token_program = Pubkey::from_str(TOKEN_PROGRAM)?;
-    let fee_program = Pubkey::from_str(FEE_PROGRAM)?;
-
-    // Global PDA
-    let (global_pda, _bump) = Pubkey::find_program_address(&[b"global"], &pump_program);
-    // Fetch global to read fee_recipient (Anchor: 8-byte discriminator + fields)
-    let global_acc = rpc_client.get_account(&global_pda)?;
-    let data = global_acc.data;
-    // Layout per IDL: bool initialized (1), authority pubkey (32), fee_recipient pubkey (32)
-    let fee_recipient_start = 8 + 1 + 32;
-    let fee_recipient_end = fee_recipient_start + 32;
-    let fee_recipient = Pubkey::new_from_array(
-        data[fee_recipient_start..fee_recipient_end]
-            .try_into()
-            .map_err(|_| "fee_recipient slice error")?,
-    );
-
-    // Event authority PDA
-    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &pump_program);
-
-    // Creator vault PDA
-    let (creator_vault, _) =
-        Pubkey::find_program_address(&[b"creator-vault", &creator.to_bytes()], &pump_program);
-
-    // Global volume accumulator PDA
-    let (global_volume_accumulator, _) =
-        Pubkey::find_program_address(&[b"global_volume_accumulator"], &pump_program);
-
-    // User volume accumulator PDA
-    let (user_volume_accumulator, _) = Pubkey::find_program_address(
-        &[b"user_volume_accumulator", &buyer.to_bytes()],
-        &pump_program,
+    info!(
+        amount_sol = params.amount_sol,
+        amount_lamports,
+        estimated_tokens,
+        min_tokens_out,
+        slippage_bps = params.slippage_bps,
+        "💰 Buy calculation"
     );
 
-    // Fee config PDA: seeds ["fee_config", CONST_32], program = fee_program
-    let fee_config_seed2: [u8; 32] = [
-        1, 86, 224, 246, 147, 102, 90, 207, 68, 219, 21, 104, 191, 23, 91, 170, 81, 137, 203, 151,
-        245, 210, 255, 59, 101, 93, 43, 182, 253, 109, 24, 176,
+    // Idempotent, so the buy goes through whether or not the token account exists yet
+    let mut instructions = vec![
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+            &buyer,
+            &buyer,
+            &accounts.mint,
+            &accounts.token_program,
+        ),
     ];
-    let (fee_config, _) =
-        Pubkey::find_program_address(&[b"fee_config", &fee_config_seed2], &fee_program);
-
-    let accounts = BuyAccounts {
-        mint,
-        bonding_curve,
-        associated_bonding_curve,
-        associated_user: buyer_token_account,
-        user: buyer,
-        system_program,
-        token_program,
-        creator_vault,
-        event_authority,
-        global_volume_accumulator,
-        user_volume_accumulator,
-        fee_config,
-        fee_program,
-        fee_recipient,
-    };
 
     // Add fee buffer (2%) to max_sol_cost to account for protocol fees, creator fees, etc.
     // The program needs ~0.89% more, so 2% should be safe
-    let fee_buffer = (amount_lamports as f64 * 0.02) as u64;
+    let fee_buffer = amount_lamports * FEE_BUFFER_BPS / 10_000;
     let max_sol_cost_with_fees = amount_lamports + fee_buffer;
 
-    println!("   💰 SOL Budget: {} lamports", amount_lamports);
-    println!("   💰 Fee Buffer (2%): {} lamports", fee_buffer);
-    println!("   💰 Max SOL Cost: {} lamports", max_sol_cost_with_fees);
+    debug!(
+        amount_lamports,
+        fee_buffer,
+        max_sol_cost = max_sol_cost_with_fees,
+        "💰 SOL budget"
+    );
 
     // Add the buy instruction matching IDL order
-    let buy_ix = build_buy_instruction(&accounts, min_tokens_out, max_sol_cost_with_fees);
+    let buy_ix = build_buy_instruction(accounts, min_tokens_out, max_sol_cost_with_fees);
     instructions.push(buy_ix);
 
-    // Get recent blockhash
-    let recent_blockhash = rpc_client.get_latest_blockhash()?;
-
     // Create message and transaction
     let message = Message::new(&instructions, Some(&buyer));
-    let mut transaction = Transaction::new_unsigned(message);
-    transaction.sign(&[&params.buyer_keypair], recent_blockhash);
+    let transaction = sign_transaction(
+        Transaction::new_unsigned(message),
+        &params.signer,
+        recent_blockhash,
+    )
+    .await?;
 
-    println!("   ✓ Transaction built successfully");
-    println!("   Buyer Token Account: {}", buyer_token_account);
+    info!(%buyer_token_account, "✓ Transaction built successfully");
 
     Ok(BuyTransaction {
         transaction,
@@ -303,43 +303,3 @@ pub fn build_buy_transaction(
         estimated_tokens,
     })
 }
-
-/// Simulate the transaction without sending it
-pub fn simulate_buy_transaction(
-    transaction: &Transaction,
-    rpc_client: &RpcClient,
-) -> Result<(), Box<dyn std::error::Error>> {
-    println!("\n🔍 Simulating transaction...");
-
-    let config = solana_client::rpc_config::RpcSimulateTransactionConfig {
-        commitment: Some(CommitmentConfig::confirmed()),
-        ..Default::default()
-    };
-
-    match rpc_client.simulate_transaction_with_config(transaction, config) {
-        Ok(response) => {
-            if let Some(err) = response.value.err {
-                println!("   ❌ Simulation failed: {:?}", err);
-                return Err(format!("Simulation error: {:?}", err).into());
-            }
-
-            println!("   ✅ Simulation successful!");
-            if let Some(logs) = response.value.logs {
-                println!("   Logs:");
-                for log in logs.iter() {
-                    println!("      {}", log);
-                }
-            }
-
-            if let Some(units) = response.value.units_consumed {
-                println!("   Compute Units: {}", units);
-            }
-        }
-        Err(e) => {
-            println!("   ❌ Simulation error: {}", e);
-            return Err(e.into());
-        }
-    }
-
-    Ok(())
-}
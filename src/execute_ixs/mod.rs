@@ -1 +1,6 @@
+pub mod accounts;
+pub mod blockhash;
 pub mod buy;
+pub mod sell;
+pub mod simulate;
+pub mod template;
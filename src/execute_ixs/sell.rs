@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use tracing::info;
+
+use crate::execute_ixs::accounts::{self, FEE_PROGRAM, PUMP_GLOBAL, SYSTEM_PROGRAM};
+use crate::signer::{SharedSigner, sign_transaction};
+use crate::utils::constants::PUMP_PROGRAM;
+
+// Sell instruction discriminator
+const SELL_DISCRIMINATOR: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+pub struct SellParams {
+    pub mint: String,
+    pub bonding_curve: String,
+    pub creator: String,
+    pub token_amount: u64,
+    pub slippage_bps: u64, // basis points (e.g., 500 = 5%)
+    pub signer: SharedSigner,
+}
+
+pub struct SellTransaction {
+    pub transaction: Transaction,
+    pub seller_token_account: String,
+    pub estimated_sol_out: u64,
+}
+
+/// Calculate SOL out with slippage
+fn calculate_sol_with_slippage(
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    token_amount: u64,
+    slippage_bps: u64,
+) -> (u64, u64) {
+    let sol_reserves_f64 = virtual_sol_reserves as f64;
+    let token_reserves_f64 = virtual_token_reserves as f64;
+    let token_amount_f64 = token_amount as f64;
+
+    // Calculate expected SOL out
+    let sol_out = (sol_reserves_f64 * token_amount_f64) / (token_reserves_f64 + token_amount_f64);
+
+    // Apply slippage
+    let slippage_multiplier = 1.0 - (slippage_bps as f64 / 10000.0);
+    let min_sol_out = (sol_out * slippage_multiplier) as u64;
+
+    (sol_out as u64, min_sol_out)
+}
+
+/// Build a complete sell transaction, account order per IDL
+pub async fn build_sell_transaction(
+    params: SellParams,
+    rpc_client: &RpcClient,
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+) -> Result<SellTransaction, Box<dyn std::error::Error + Send + Sync>> {
+    let seller = params.signer.try_pubkey()?;
+    let mint = Pubkey::from_str(&params.mint)?;
+    let bonding_curve = Pubkey::from_str(&params.bonding_curve)?;
+    let creator = Pubkey::from_str(&params.creator)?;
+    let token_program = accounts::fetch_token_program(rpc_client, &mint).await?;
+    let associated_bonding_curve =
+        get_associated_token_address_with_program_id(&bonding_curve, &mint, &token_program);
+    let seller_token_account =
+        get_associated_token_address_with_program_id(&seller, &mint, &token_program);
+
+    let (estimated_sol_out, min_sol_out) = calculate_sol_with_slippage(
+        virtual_sol_reserves,
+        virtual_token_reserves,
+        params.token_amount,
+        params.slippage_bps,
+    );
+
+    info!(
+        token_amount = params.token_amount,
+        estimated_sol_out,
+        min_sol_out,
+        slippage_bps = params.slippage_bps,
+        "💰 Sell calculation"
+    );
+
+    let fee_recipient = accounts::fetch_fee_recipient(rpc_client).await?;
+
+    let metas = vec![
+        AccountMeta::new_readonly(Pubkey::from_str(PUMP_GLOBAL)?, false),
+        AccountMeta::new(fee_recipient, false),
+        AccountMeta::new_readonly(mint, false),
+        AccountMeta::new(bonding_curve, false),
+        AccountMeta::new(associated_bonding_curve, false),
+        AccountMeta::new(seller_token_account, false),
+        AccountMeta::new(seller, true),
+        AccountMeta::new_readonly(Pubkey::from_str(SYSTEM_PROGRAM)?, false),
+        AccountMeta::new(accounts::creator_vault(&creator), false),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new_readonly(accounts::event_authority(), false),
+        AccountMeta::new_readonly(Pubkey::from_str(PUMP_PROGRAM)?, false),
+        AccountMeta::new_readonly(accounts::fee_config(), false),
+        AccountMeta::new_readonly(Pubkey::from_str(FEE_PROGRAM)?, false),
+    ];
+
+    // Instruction data: discriminator + amount + min_sol_output
+    let mut data = Vec::new();
+    data.extend_from_slice(&SELL_DISCRIMINATOR);
+    data.extend_from_slice(&params.token_amount.to_le_bytes());
+    data.extend_from_slice(&min_sol_out.to_le_bytes());
+
+    let sell_ix = Instruction {
+        program_id: Pubkey::from_str(PUMP_PROGRAM)?,
+        accounts: metas,
+        data,
+    };
+
+    let recent_blockhash = rpc_client.get_latest_blockhash().await?;
+
+    let message = Message::new(&[sell_ix], Some(&seller));
+    let transaction = sign_transaction(
+        Transaction::new_unsigned(message),
+        &params.signer,
+        recent_blockhash,
+    )
+    .await?;
+
+    info!(%seller_token_account, "✓ Sell transaction built successfully");
+
+    Ok(SellTransaction {
+        transaction,
+        seller_token_account: seller_token_account.to_string(),
+        estimated_sol_out,
+    })
+}
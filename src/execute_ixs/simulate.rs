@@ -0,0 +1,45 @@
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_sdk::transaction::Transaction;
+use tracing::{debug, info, warn};
+
+/// Simulate the transaction without sending it, returning the compute units consumed
+pub async fn simulate_transaction(
+    transaction: &Transaction,
+    rpc_client: &RpcClient,
+) -> Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>> {
+    debug!("🔍 Simulating transaction...");
+
+    let config = solana_client::rpc_config::RpcSimulateTransactionConfig {
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..Default::default()
+    };
+
+    match rpc_client
+        .simulate_transaction_with_config(transaction, config)
+        .await
+    {
+        Ok(response) => {
+            if let Some(err) = response.value.err {
+                warn!(error = ?err, "❌ Simulation failed");
+                return Err(format!("Simulation error: {:?}", err).into());
+            }
+
+            info!(
+                compute_units = response.value.units_consumed,
+                "✅ Simulation successful"
+            );
+            if let Some(logs) = response.value.logs {
+                for log in logs.iter() {
+                    debug!(target: "pump_sniper_grpc::simulation", "{}", log);
+                }
+            }
+
+            Ok(response.value.units_consumed)
+        }
+        Err(e) => {
+            warn!(error = %e, "❌ Simulation error");
+            Err(e.into())
+        }
+    }
+}
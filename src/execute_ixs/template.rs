@@ -0,0 +1,373 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{hash::Hash, pubkey::Pubkey};
+use tracing::{debug, info};
+
+use crate::execute_ixs::accounts;
+use crate::execute_ixs::buy::{self, BuyAccounts, BuyParams, BuyTransaction};
+use crate::types::TokenInfo;
+
+// The mint was created moments ago, give a lagging RPC node a few tries
+const LOOKUP_ATTEMPTS: u32 = 3;
+const LOOKUP_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Buy accounts for one tracked token, derived for every wallet of the pool
+/// when the token joins a batch. On a signal only the amounts and blockhash
+/// are filled in before signing.
+pub struct BuyTemplate {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub creator: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub token_program: Pubkey,
+    buyers: HashMap<Pubkey, BuyAccounts>,
+}
+
+impl BuyTemplate {
+    fn new(
+        mint: Pubkey,
+        bonding_curve: Pubkey,
+        creator: Pubkey,
+        fee_recipient: Pubkey,
+        token_program: Pubkey,
+        owners: &[Pubkey],
+    ) -> Self {
+        let buyers = owners
+            .iter()
+            .map(|owner| {
+                let accounts = BuyAccounts::derive(
+                    mint,
+                    bonding_curve,
+                    &creator,
+                    *owner,
+                    fee_recipient,
+                    token_program,
+                );
+                (*owner, accounts)
+            })
+            .collect();
+
+        Self {
+            mint,
+            bonding_curve,
+            creator,
+            fee_recipient,
+            token_program,
+            buyers,
+        }
+    }
+
+    /// Same token and token program, with a new creator or fee recipient.
+    fn rebuild(&self, creator: Pubkey, fee_recipient: Pubkey, owners: &[Pubkey]) -> Self {
+        Self::new(
+            self.mint,
+            self.bonding_curve,
+            creator,
+            fee_recipient,
+            self.token_program,
+            owners,
+        )
+    }
+
+    /// `buyer`'s associated token account for this mint.
+    pub fn token_account(&self, buyer: &Pubkey) -> Option<Pubkey> {
+        self.buyers.get(buyer).map(BuyAccounts::buyer_token_account)
+    }
+
+    /// Fills in and signs the buy from `buyer`'s precomputed accounts.
+    pub async fn build(
+        &self,
+        buyer: &Pubkey,
+        params: &BuyParams,
+        virtual_sol_reserves: u64,
+        virtual_token_reserves: u64,
+        recent_blockhash: Hash,
+    ) -> Result<BuyTransaction, Box<dyn std::error::Error + Send + Sync>> {
+        let accounts = self
+            .buyers
+            .get(buyer)
+            .ok_or_else(|| format!("no buy template for wallet {}", buyer))?;
+        buy::assemble_buy_transaction(
+            accounts,
+            params,
+            virtual_sol_reserves,
+            virtual_token_reserves,
+            recent_blockhash,
+        )
+        .await
+    }
+}
+
+/// Buy templates of the tracked tokens, shared by the tracker that builds
+/// them and the executor that fires them. Templates are rebuilt when a
+/// token's creator or the global fee recipient changes.
+pub struct BuyTemplates {
+    owners: Vec<Pubkey>,
+    rpc_client: Arc<RpcClient>,
+    fee_recipient: RwLock<Option<Pubkey>>,
+    templates: RwLock<HashMap<String, Arc<BuyTemplate>>>,
+}
+
+impl BuyTemplates {
+    pub fn new(owners: Vec<Pubkey>, rpc_client: Arc<RpcClient>) -> Self {
+        Self {
+            owners,
+            rpc_client,
+            fee_recipient: RwLock::new(None),
+            templates: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, mint: &str) -> Option<Arc<BuyTemplate>> {
+        self.templates
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+            .cloned()
+    }
+
+    /// Builds the template of a token entering a batch, looking up the mint's
+    /// token program with a few retries. Skipped while the fee recipient is
+    /// unknown; the executor then derives accounts itself.
+    pub async fn prepare(&self, token: &TokenInfo) {
+        let (Ok(mint), Ok(bonding_curve), Ok(creator)) = (
+            Pubkey::from_str(&token.mint),
+            Pubkey::from_str(&token.bonding_curve),
+            Pubkey::from_str(&token.creator),
+        ) else {
+            debug!(mint = %token.mint, "Unparseable token accounts, no buy template");
+            return;
+        };
+
+        let mut attempt = 1;
+        let token_program = loop {
+            match accounts::fetch_token_program(&self.rpc_client, &mint).await {
+                Ok(token_program) => break token_program,
+                Err(e) if attempt < LOOKUP_ATTEMPTS => {
+                    debug!(mint = %token.mint, attempt, error = %e, "Token program lookup failed, retrying");
+                    tokio::time::sleep(LOOKUP_RETRY_DELAY).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    debug!(mint = %token.mint, error = %e, "Token program lookup failed, no buy template");
+                    return;
+                }
+            }
+        };
+
+        // Read after the lookup, the recipient may have changed during it
+        let Some(fee_recipient) = *self.fee_recipient.read().unwrap_or_else(|e| e.into_inner())
+        else {
+            debug!(mint = %token.mint, "Fee recipient unknown, no buy template");
+            return;
+        };
+        let mut template = BuyTemplate::new(
+            mint,
+            bonding_curve,
+            creator,
+            fee_recipient,
+            token_program,
+            &self.owners,
+        );
+
+        let mut templates = self.templates.write().unwrap_or_else(|e| e.into_inner());
+        // A change that landed while building already rebuilt the other
+        // templates, this one has to follow it
+        let current = *self.fee_recipient.read().unwrap_or_else(|e| e.into_inner());
+        if let Some(current) = current
+            && current != fee_recipient
+        {
+            template = template.rebuild(creator, current, &self.owners);
+        }
+        templates.insert(token.mint.clone(), Arc::new(template));
+    }
+
+    /// Rebuilds a token's template if the curve reports a different creator.
+    /// Runs on every curve update, so the write lock is only taken on a
+    /// mismatch.
+    pub fn check_creator(&self, mint: &str, creator: Pubkey) {
+        let unchanged = self
+            .templates
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+            .is_none_or(|template| template.creator == creator);
+        if unchanged {
+            return;
+        }
+
+        let mut templates = self.templates.write().unwrap_or_else(|e| e.into_inner());
+        // Checked again, another update may have rebuilt it in between
+        let Some(template) = templates.get(mint) else {
+            return;
+        };
+        if template.creator == creator {
+            return;
+        }
+
+        info!(%mint, old = %template.creator, new = %creator, "♻️ Creator changed, rebuilding buy template");
+        let template = template.rebuild(creator, template.fee_recipient, &self.owners);
+        templates.insert(mint.to_string(), Arc::new(template));
+    }
+
+    /// Records the global fee recipient and rebuilds every template if it changed.
+    pub fn set_fee_recipient(&self, fee_recipient: Pubkey) {
+        let previous = self
+            .fee_recipient
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(fee_recipient);
+        if previous == Some(fee_recipient) {
+            return;
+        }
+
+        let mut templates = self.templates.write().unwrap_or_else(|e| e.into_inner());
+        if previous.is_some() {
+            info!(%fee_recipient, templates = templates.len(), "♻️ Fee recipient changed, rebuilding buy templates");
+        }
+        for template in templates.values_mut() {
+            *template = Arc::new(template.rebuild(template.creator, fee_recipient, &self.owners));
+        }
+    }
+
+    /// Drops the templates of tokens that are no longer monitored.
+    pub fn remove(&self, mints: &[String]) {
+        let mut templates = self.templates.write().unwrap_or_else(|e| e.into_inner());
+        for mint in mints {
+            templates.remove(mint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+
+    use super::*;
+    use crate::execute_ixs::accounts::TOKEN_2022_PROGRAM;
+    use crate::utils::stub_server::{StubResponse, StubServer};
+
+    fn mint_account() -> StubResponse {
+        StubResponse::json(format!(
+            r#"{{"jsonrpc": "2.0", "id": 1, "result": {{
+                "context": {{ "slot": 1 }},
+                "value": {{
+                    "data": ["", "base64"],
+                    "executable": false,
+                    "lamports": 1461600,
+                    "owner": "{}",
+                    "rentEpoch": 0,
+                    "space": 0
+                }}
+            }}}}"#,
+            TOKEN_2022_PROGRAM
+        ))
+    }
+
+    fn token() -> TokenInfo {
+        TokenInfo::new(
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+            "Pepe".to_string(),
+            "PEPE".to_string(),
+            "ipfs://doc".to_string(),
+            Pubkey::new_unique().to_string(),
+            1,
+        )
+    }
+
+    fn templates(server: &StubServer) -> Arc<BuyTemplates> {
+        Arc::new(BuyTemplates::new(
+            vec![Pubkey::new_unique()],
+            Arc::new(RpcClient::new(server.url.clone())),
+        ))
+    }
+
+    #[tokio::test]
+    async fn builds_with_the_looked_up_token_program() {
+        let server = StubServer::start(vec![("/", mint_account())]).await;
+        let templates = templates(&server);
+        let fee_recipient = Pubkey::new_unique();
+        templates.set_fee_recipient(fee_recipient);
+
+        let token = token();
+        templates.prepare(&token).await;
+        let template = templates.get(&token.mint).expect("template");
+        assert_eq!(template.token_program.to_string(), TOKEN_2022_PROGRAM);
+        assert_eq!(template.fee_recipient, fee_recipient);
+    }
+
+    #[tokio::test]
+    async fn skips_the_template_while_the_fee_recipient_is_unknown() {
+        let server = StubServer::start(vec![("/", mint_account())]).await;
+        let templates = templates(&server);
+
+        let token = token();
+        templates.prepare(&token).await;
+        assert!(templates.get(&token.mint).is_none());
+    }
+
+    #[tokio::test]
+    async fn follows_a_fee_recipient_change_during_the_lookup() {
+        let server = StubServer::start(vec![(
+            "/",
+            mint_account().delayed(Duration::from_millis(300)),
+        )])
+        .await;
+        let templates = templates(&server);
+        templates.set_fee_recipient(Pubkey::new_unique());
+
+        let token = token();
+        let preparing = {
+            let templates = templates.clone();
+            let token = token.clone();
+            tokio::spawn(async move { templates.prepare(&token).await })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let fee_recipient = Pubkey::new_unique();
+        templates.set_fee_recipient(fee_recipient);
+        preparing.await.unwrap();
+
+        assert_eq!(
+            templates.get(&token.mint).expect("template").fee_recipient,
+            fee_recipient
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_the_lookup_before_giving_up() {
+        let server = StubServer::start(vec![(
+            "/",
+            StubResponse::status(StatusCode::SERVICE_UNAVAILABLE),
+        )])
+        .await;
+        let templates = templates(&server);
+        templates.set_fee_recipient(Pubkey::new_unique());
+
+        let token = token();
+        templates.prepare(&token).await;
+        assert!(templates.get(&token.mint).is_none());
+        assert_eq!(server.hits("/"), LOOKUP_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn rebuilds_only_when_the_creator_changes() {
+        let server = StubServer::start(vec![("/", mint_account())]).await;
+        let templates = templates(&server);
+        templates.set_fee_recipient(Pubkey::new_unique());
+        let token = token();
+        templates.prepare(&token).await;
+        let before = templates.get(&token.mint).unwrap();
+
+        templates.check_creator(&token.mint, before.creator);
+        assert!(Arc::ptr_eq(&before, &templates.get(&token.mint).unwrap()));
+
+        let creator = Pubkey::new_unique();
+        templates.check_creator(&token.mint, creator);
+        assert_eq!(templates.get(&token.mint).unwrap().creator, creator);
+    }
+}
@@ -0,0 +1,28 @@
+use crate::utils::config::optional_var;
+
+/// Holder distribution rules. Unset rules are not applied; a token breaking
+/// one is held back until its distribution improves or its batch ends.
+#[derive(Debug, Clone)]
+pub struct HolderConfig {
+    /// Track holders at all
+    pub enabled: bool,
+    /// Largest share of the supply the ten biggest holders may own
+    pub max_top10_pct: Option<f64>,
+    pub max_creator_pct: Option<f64>,
+    pub min_holders: Option<usize>,
+}
+
+impl HolderConfig {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(HolderConfig {
+            enabled: optional_var("HOLDERS_TRACKING")?.unwrap_or(true),
+            max_top10_pct: optional_var("HOLDERS_MAX_TOP10_PCT")?,
+            max_creator_pct: optional_var("HOLDERS_MAX_CREATOR_PCT")?,
+            min_holders: optional_var("HOLDERS_MIN_COUNT")?,
+        })
+    }
+
+    pub fn has_rules(&self) -> bool {
+        self.max_top10_pct.is_some() || self.max_creator_pct.is_some() || self.min_holders.is_some()
+    }
+}
@@ -0,0 +1,5 @@
+pub mod config;
+pub mod tracker;
+
+pub use config::HolderConfig;
+pub use tracker::HolderTracker;
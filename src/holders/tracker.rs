@@ -0,0 +1,524 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use helius_laserstream::grpc::{
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+    SubscribeRequestFilterAccountsFilter, SubscribeRequestFilterAccountsFilterMemcmp,
+    subscribe_request_filter_accounts_filter::Filter,
+    subscribe_request_filter_accounts_filter_memcmp::Data, subscribe_update::UpdateOneof,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+use tracing::{debug, warn};
+
+use crate::execute_ixs::accounts::{TOKEN_2022_PROGRAM, TOKEN_PROGRAM};
+use crate::holders::HolderConfig;
+use crate::monitors::supervisor::spawn_supervised_stream;
+use crate::pipeline::EventBus;
+use crate::pipeline::Verdict;
+use crate::types::TokenInfo;
+use crate::utils::config::Config;
+use crate::wallet::state::{ACCOUNT_TYPE_ACCOUNT, TOKEN_ACCOUNT_LEN, parse_token_account};
+
+// getProgramAccounts is heavy and rate limited, back off between tries
+const LOAD_ATTEMPTS: u32 = 3;
+const LOAD_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Holder distribution of a token, as shares of its total supply.
+#[derive(Debug, Clone)]
+pub struct HolderStats {
+    /// Wallets holding a non-zero balance, the bonding curve excluded
+    pub holder_count: usize,
+    pub top10_pct: f64,
+    pub creator_pct: f64,
+}
+
+struct TokenAccount {
+    owner: Pubkey,
+    amount: u64,
+    slot: u64,
+}
+
+struct HolderTable {
+    bonding_curve: Pubkey,
+    creator: Pubkey,
+    /// Set once the token accounts have been loaded over RPC
+    loaded: bool,
+    /// Set when every load attempt failed
+    load_failed: bool,
+    accounts: HashMap<Pubkey, TokenAccount>,
+}
+
+impl HolderTable {
+    fn stats(&self, total_supply: u64) -> HolderStats {
+        let mut by_owner: HashMap<Pubkey, u64> = HashMap::new();
+        for account in self.accounts.values() {
+            // The curve's own token account holds the unsold supply
+            if account.owner != self.bonding_curve && account.amount > 0 {
+                *by_owner.entry(account.owner).or_default() += account.amount;
+            }
+        }
+        let share = |amount: u64| {
+            if total_supply == 0 {
+                0.0
+            } else {
+                amount as f64 / total_supply as f64 * 100.0
+            }
+        };
+
+        let mut balances: Vec<u64> = by_owner.values().copied().collect();
+        balances.sort_unstable_by(|a, b| b.cmp(a));
+        HolderStats {
+            holder_count: balances.len(),
+            top10_pct: share(balances.iter().take(10).sum()),
+            creator_pct: share(by_owner.get(&self.creator).copied().unwrap_or_default()),
+        }
+    }
+}
+
+/// Live holder tables of the tracked tokens. The tracker registers a token
+/// when it joins a batch and watches its token accounts while the batch is
+/// monitored; the strategy reads the distribution before buying.
+pub struct HolderTracker {
+    config: HolderConfig,
+    rpc_client: Arc<RpcClient>,
+    tables: RwLock<HashMap<Pubkey, HolderTable>>,
+}
+
+impl HolderTracker {
+    pub fn new(config: HolderConfig, rpc_client: Arc<RpcClient>) -> Self {
+        Self {
+            config,
+            rpc_client,
+            tables: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn track(&self, token: &TokenInfo) {
+        let (Ok(mint), Ok(bonding_curve), Ok(creator)) = (
+            Pubkey::from_str(&token.mint),
+            Pubkey::from_str(&token.bonding_curve),
+            Pubkey::from_str(&token.creator),
+        ) else {
+            return;
+        };
+        self.tables
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(mint)
+            .or_insert_with(|| HolderTable {
+                bonding_curve,
+                creator,
+                loaded: false,
+                load_failed: false,
+                accounts: HashMap::new(),
+            });
+    }
+
+    /// Distribution once the token accounts have been loaded.
+    pub fn stats(&self, mint: &str, total_supply: u64) -> Option<HolderStats> {
+        let mint = Pubkey::from_str(mint).ok()?;
+        let tables = self.tables.read().unwrap_or_else(|e| e.into_inner());
+        let table = tables.get(&mint).filter(|table| table.loaded)?;
+        Some(table.stats(total_supply))
+    }
+
+    /// Holds a token back while its distribution breaks a rule, and rejects
+    /// it when its holders could not be loaded.
+    pub fn verdict(&self, mint: &str, total_supply: u64) -> Verdict {
+        if !self.config.enabled || !self.config.has_rules() {
+            return Verdict::Pass;
+        }
+        if self.load_failed(mint) {
+            return Verdict::Reject(vec!["holder load failed".to_string()]);
+        }
+        let Some(stats) = self.stats(mint, total_supply) else {
+            return Verdict::Pending;
+        };
+        let config = &self.config;
+        let breaks_rule = config
+            .max_top10_pct
+            .is_some_and(|max| stats.top10_pct > max)
+            || config
+                .max_creator_pct
+                .is_some_and(|max| stats.creator_pct > max)
+            || config
+                .min_holders
+                .is_some_and(|min| stats.holder_count < min);
+        if breaks_rule {
+            Verdict::Pending
+        } else {
+            Verdict::Pass
+        }
+    }
+
+    /// Registers the batch's tokens and loads their token accounts over
+    /// RPC, then keeps them current from a Laserstream subscription. Runs until the stream ends
+    /// or the task is aborted with the batch.
+    pub async fn watch(&self, batch: &[TokenInfo], config: &Config, bus: EventBus) {
+        if !self.config.enabled {
+            return;
+        }
+        for token in batch {
+            self.track(token);
+        }
+        let mints: Vec<Pubkey> = batch
+            .iter()
+            .filter_map(|token| Pubkey::from_str(&token.mint).ok())
+            .collect();
+
+        // Filters within an entry are ANDed, so each mint needs its own entry
+        let accounts = mints
+            .iter()
+            .enumerate()
+            .map(|(index, mint)| {
+                let mint_filter = SubscribeRequestFilterAccountsFilter {
+                    filter: Some(Filter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                        offset: 0,
+                        data: Some(Data::Bytes(mint.to_bytes().to_vec())),
+                    })),
+                };
+                (
+                    format!("holders_{}", index),
+                    SubscribeRequestFilterAccounts {
+                        owner: vec![TOKEN_PROGRAM.to_string(), TOKEN_2022_PROGRAM.to_string()],
+                        filters: vec![mint_filter],
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        let request = SubscribeRequest {
+            accounts,
+            commitment: Some(CommitmentLevel::Confirmed.into()),
+            ..Default::default()
+        };
+        // Subscribe first so nothing between the load and the stream is lost
+        let mut updates = spawn_supervised_stream("holders", request, config, bus);
+
+        join_all(mints.iter().map(|mint| self.load(*mint))).await;
+
+        while let Some(update) = updates.recv().await {
+            if let Some(UpdateOneof::Account(account_update)) = update.update_oneof
+                && let Some(account) = account_update.account
+                && let Ok(address) = Pubkey::try_from(account.pubkey.as_slice())
+            {
+                self.apply(address, &account.data, account_update.slot);
+            }
+        }
+    }
+
+    /// Drops the tables of tokens that are no longer monitored.
+    pub fn remove(&self, mints: &[String]) {
+        let mut tables = self.tables.write().unwrap_or_else(|e| e.into_inner());
+        for mint in mints {
+            if let Ok(mint) = Pubkey::from_str(mint) {
+                tables.remove(&mint);
+            }
+        }
+    }
+
+    fn load_failed(&self, mint: &str) -> bool {
+        let Ok(mint) = Pubkey::from_str(mint) else {
+            return false;
+        };
+        self.tables
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&mint)
+            .is_some_and(|table| table.load_failed)
+    }
+
+    /// Loads the mint's token accounts, retrying with backoff.
+    async fn load(&self, mint: Pubkey) {
+        let mut backoff = LOAD_INITIAL_BACKOFF;
+        for attempt in 1..=LOAD_ATTEMPTS {
+            match self.fetch(mint).await {
+                Ok((slot, token_accounts)) => {
+                    let count = token_accounts.len();
+                    for (address, account) in token_accounts {
+                        self.apply(address, &account.data, slot);
+                    }
+                    self.set_loaded(mint, true);
+                    debug!(%mint, token_accounts = count, "👥 Holders loaded");
+                    return;
+                }
+                Err(e) if attempt < LOAD_ATTEMPTS => {
+                    debug!(%mint, attempt, error = %e, "Holder load failed, retrying");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    warn!(%mint, error = %e, "⚠️ Holder load failed");
+                    self.set_loaded(mint, false);
+                }
+            }
+        }
+    }
+
+    fn set_loaded(&self, mint: Pubkey, loaded: bool) {
+        if let Some(table) = self
+            .tables
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&mint)
+        {
+            table.loaded = loaded;
+            table.load_failed = !loaded;
+        }
+    }
+
+    /// The slot and every token account of the mint.
+    async fn fetch(
+        &self,
+        mint: Pubkey,
+    ) -> Result<(u64, Vec<(Pubkey, Account)>), Box<dyn std::error::Error + Send + Sync>> {
+        let slot = self.rpc_client.get_slot().await?;
+        let mut token_accounts = Vec::new();
+        for (program, account_filter) in [
+            // SPL Token accounts are always 165 bytes
+            (
+                TOKEN_PROGRAM,
+                RpcFilterType::DataSize(TOKEN_ACCOUNT_LEN as u64),
+            ),
+            // Token-2022 accounts with extensions carry their account type
+            // right after the base layout
+            (
+                TOKEN_2022_PROGRAM,
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    TOKEN_ACCOUNT_LEN,
+                    vec![ACCOUNT_TYPE_ACCOUNT],
+                )),
+            ),
+        ] {
+            token_accounts.extend(
+                self.rpc_client
+                    .get_program_accounts_with_config(
+                        &Pubkey::from_str_const(program),
+                        RpcProgramAccountsConfig {
+                            filters: Some(vec![
+                                account_filter,
+                                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                                    0,
+                                    mint.to_bytes().to_vec(),
+                                )),
+                            ]),
+                            ..Default::default()
+                        },
+                    )
+                    .await?,
+            );
+        }
+        Ok((slot, token_accounts))
+    }
+
+    /// Applies a token account at `slot`, ignoring anything older than what
+    /// is already known for it.
+    fn apply(&self, address: Pubkey, data: &[u8], slot: u64) {
+        let Some((mint, owner, amount)) = parse_token_account(data) else {
+            return;
+        };
+        let mut tables = self.tables.write().unwrap_or_else(|e| e.into_inner());
+        let Some(table) = tables.get_mut(&mint) else {
+            return;
+        };
+        if table
+            .accounts
+            .get(&address)
+            .is_some_and(|known| known.slot > slot)
+        {
+            return;
+        }
+        table.accounts.insert(
+            address,
+            TokenAccount {
+                owner,
+                amount,
+                slot,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    use super::*;
+    use crate::utils::stub_server::{StubResponse, StubServer};
+
+    fn table(bonding_curve: Pubkey, creator: Pubkey, holdings: &[(Pubkey, u64)]) -> HolderTable {
+        HolderTable {
+            bonding_curve,
+            creator,
+            loaded: true,
+            load_failed: false,
+            accounts: holdings
+                .iter()
+                .map(|(owner, amount)| {
+                    (
+                        Pubkey::new_unique(),
+                        TokenAccount {
+                            owner: *owner,
+                            amount: *amount,
+                            slot: 1,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn leaves_out_the_curve_and_empty_accounts() {
+        let (curve, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let holder = Pubkey::new_unique();
+        let stats = table(
+            curve,
+            creator,
+            &[
+                (curve, 800),
+                (creator, 100),
+                (holder, 50),
+                (Pubkey::new_unique(), 0),
+            ],
+        )
+        .stats(1_000);
+
+        assert_eq!(stats.holder_count, 2);
+        assert_eq!(stats.top10_pct, 15.0);
+        assert_eq!(stats.creator_pct, 10.0);
+    }
+
+    #[test]
+    fn groups_accounts_by_owner() {
+        let (curve, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let stats = table(curve, creator, &[(creator, 100), (creator, 150)]).stats(1_000);
+
+        assert_eq!(stats.holder_count, 1);
+        assert_eq!(stats.creator_pct, 25.0);
+    }
+
+    #[test]
+    fn counts_only_the_ten_largest_holders() {
+        let (curve, creator) = (Pubkey::new_unique(), Pubkey::new_unique());
+        // Eleven holders of 10 to 110 tokens
+        let holdings: Vec<(Pubkey, u64)> =
+            (1..=11).map(|i| (Pubkey::new_unique(), i * 10)).collect();
+        let stats = table(curve, creator, &holdings).stats(1_000);
+
+        assert_eq!(stats.holder_count, 11);
+        assert_eq!(stats.top10_pct, 65.0);
+        assert_eq!(stats.creator_pct, 0.0);
+        assert_eq!(table(curve, creator, &holdings).stats(0).top10_pct, 0.0);
+    }
+
+    fn token() -> TokenInfo {
+        TokenInfo::new(
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+            "Pepe".to_string(),
+            "PEPE".to_string(),
+            "ipfs://doc".to_string(),
+            Pubkey::new_unique().to_string(),
+            1,
+        )
+    }
+
+    fn tracker(server: &StubServer) -> HolderTracker {
+        HolderTracker::new(
+            HolderConfig {
+                enabled: true,
+                max_top10_pct: Some(50.0),
+                max_creator_pct: None,
+                min_holders: None,
+            },
+            Arc::new(RpcClient::new(server.url.clone())),
+        )
+    }
+
+    /// `getProgramAccounts` answer with one token account of `mint`.
+    fn token_accounts(mint: Pubkey, owner: Pubkey, amount: u64) -> StubResponse {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        StubResponse::json(format!(
+            r#"{{"jsonrpc": "2.0", "id": 1, "result": [{{
+                "pubkey": "{}",
+                "account": {{
+                    "data": ["{}", "base64"],
+                    "executable": false,
+                    "lamports": 2039280,
+                    "owner": "{}",
+                    "rentEpoch": 0,
+                    "space": {}
+                }}
+            }}]}}"#,
+            Pubkey::new_unique(),
+            STANDARD.encode(&data),
+            TOKEN_PROGRAM,
+            data.len()
+        ))
+    }
+
+    #[tokio::test]
+    async fn loads_holders_over_rpc() {
+        let token = token();
+        let mint = Pubkey::from_str(&token.mint).unwrap();
+        let server = StubServer::start(vec![
+            (
+                "/#getSlot",
+                StubResponse::json(r#"{"jsonrpc": "2.0", "id": 1, "result": 7}"#),
+            ),
+            (
+                "/#getProgramAccounts",
+                token_accounts(mint, Pubkey::new_unique(), 300),
+            ),
+        ])
+        .await;
+        let tracker = tracker(&server);
+        tracker.track(&token);
+        assert!(matches!(
+            tracker.verdict(&token.mint, 1_000),
+            Verdict::Pending
+        ));
+
+        tracker.load(mint).await;
+        // Both token programs answer the same account, stored once
+        let stats = tracker.stats(&token.mint, 1_000).expect("loaded");
+        assert_eq!(stats.holder_count, 1);
+        assert_eq!(stats.top10_pct, 30.0);
+        assert!(matches!(tracker.verdict(&token.mint, 1_000), Verdict::Pass));
+        assert!(matches!(
+            tracker.verdict(&token.mint, 500),
+            Verdict::Pending
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_tokens_whose_holders_cannot_be_loaded() {
+        let server = StubServer::start(vec![(
+            "/",
+            StubResponse::status(StatusCode::INTERNAL_SERVER_ERROR),
+        )])
+        .await;
+        let tracker = tracker(&server);
+        let token = token();
+        tracker.track(&token);
+
+        tracker.load(Pubkey::from_str(&token.mint).unwrap()).await;
+        assert_eq!(server.hits("/"), LOAD_ATTEMPTS as usize);
+        assert!(tracker.stats(&token.mint, 1_000).is_none());
+        match tracker.verdict(&token.mint, 1_000) {
+            Verdict::Reject(violations) => assert_eq!(violations, ["holder load failed"]),
+            other => panic!("unexpected verdict: {:?}", other),
+        }
+    }
+}
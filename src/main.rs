@@ -1,24 +1,83 @@
-use std::collections::HashSet;
 use std::env;
-use std::sync::{Arc, Mutex};
-use std::vec::Vec;
+use std::process::ExitCode;
+use std::sync::Arc;
 
 use serde_json::Value;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, timeout};
+use tracing::{error, info, warn};
 
-use monitors::{monitor_account, monitor_transaction};
-use types::TokenInfo;
+use bundles::{BundleConfig, BundleDetector};
+use content::{ContentConfig, ContentFilter};
+use execute_ixs::blockhash::{BlockhashCache, run_blockhash_refresher};
+use execute_ixs::template::BuyTemplates;
+use holders::{HolderConfig, HolderTracker};
+use monitors::monitor_account::TokenPrep;
+use monitors::{monitor_account, monitor_global, monitor_transaction};
+use pipeline::controls::RuntimeControls;
+use pipeline::executor::ExecutionContext;
+use pipeline::snapshot::PipelineSnapshot;
+use pipeline::strategy::Gates;
+use pipeline::velocity::VelocityConfig;
+use pipeline::{EventBus, Shutdown, executor, reporter, shutdown, strategy};
+use risk::{RiskConfig, RiskGuard};
+use safety::{MintSafety, SafetyConfig};
 use utils::config::Config;
+use utils::constants::{DEFAULT_STORE_PATH, EVENT_BUS_CAPACITY, STAGE_CHANNEL_CAPACITY};
+use wallet::WalletPool;
 
+mod bundles;
+mod content;
+mod control;
 mod execute_ixs;
+mod holders;
+mod metrics;
 mod monitors;
+mod notify;
 mod parser;
+mod pipeline;
+mod risk;
+mod safety;
+mod signer;
+mod store;
+mod tui;
 mod types;
 mod utils;
+mod wallet;
+
+type StageHandle = JoinHandle<Result<(), Box<dyn std::error::Error + Send + Sync>>>;
+
+// Exit codes: a stage failed, or in-flight work did not finish in time
+const EXIT_STAGE_FAILED: u8 = 1;
+const EXIT_SHUTDOWN_TIMEOUT: u8 = 2;
+// Conventional code for a process terminated by a second Ctrl-C
+const EXIT_FORCED: i32 = 130;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+async fn main() -> Result<ExitCode, Box<dyn std::error::Error + Send + Sync>> {
     dotenv::from_path(".env").ok();
 
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("query") {
+        let path = env::var("STORE_PATH").unwrap_or_else(|_| DEFAULT_STORE_PATH.to_string());
+        if let Err(e) = store::query::run(&path, &args[1..]) {
+            eprintln!("{}", e);
+            return Ok(ExitCode::from(EXIT_STAGE_FAILED));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    if args.first().map(String::as_str) == Some("keystore") {
+        if let Err(e) = signer::keystore::run(&args[1..]) {
+            eprintln!("{}", e);
+            return Ok(ExitCode::from(EXIT_STAGE_FAILED));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    utils::logging::init_logging()?;
+
     let coingecko_endpoint = env::var("COINGECKO_URL").expect("COINGECKO_URL must be set");
 
     let coingecko_resp = reqwest::get(coingecko_endpoint).await?.text().await?;
@@ -28,20 +87,274 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let config = Config::from_env()?;
     config.print_info(coingecko_sol_usd_price);
 
-    let current_batch: Arc<Mutex<Vec<TokenInfo>>> = Arc::new(Mutex::new(Vec::new()));
-    let processed_tokens: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+    if let Some(addr) = config.metrics_addr.clone() {
+        tokio::spawn(async move {
+            if let Err(e) = metrics::server::serve_metrics(addr).await {
+                warn!(error = %e, "⚠️ Metrics server stopped");
+            }
+        });
+    }
 
-    println!("🔍 Starting account monitoring...");
-    // Spawn account monitoring task
-    let account_monitor = tokio::spawn(monitor_account::monitor_batches(
-        current_batch.clone(),
+    let shutdown = Shutdown::new();
+    {
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            let signal = shutdown::wait_for_signal().await;
+            info!(signal, "🛑 Shutting down (repeat to force)...");
+            shutdown.trigger();
+
+            shutdown::wait_for_signal().await;
+            warn!("🛑 Forced exit");
+            std::process::exit(EXIT_FORCED);
+        });
+    }
+
+    // ingest -> tracker -> strategy -> executor, every stage also publishes to the bus
+    let bus = EventBus::new(EVENT_BUS_CAPACITY);
+    let (token_tx, token_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+    let (curve_tx, curve_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+    let (order_tx, order_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+    let controls = Arc::new(RuntimeControls::new(&config));
+    // One pooled RPC client shared by the executor, wallet monitor and control API
+    let rpc_client = Arc::new(RpcClient::new(config.helius_rpc_url.clone()));
+    let risk = Arc::new(RiskGuard::new(RiskConfig::from_env()?));
+    // Keys are loaded once here, a bad key stops startup with a clear error
+    let signers = config
+        .buyer_keypairs
+        .iter()
+        .map(|spec| signer::load_signer(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    let wallets = Arc::new(WalletPool::new(
+        signers,
+        config.wallet_routing,
+        RiskConfig::wallet_from_env()?,
+    ));
+    info!(wallets = wallets.len(), routing = ?config.wallet_routing, "👛 Wallet pool ready");
+    let templates = Arc::new(BuyTemplates::new(
+        wallets.iter().map(|pooled| pooled.wallet.owner()).collect(),
+        rpc_client.clone(),
+    ));
+    let blockhash = Arc::new(BlockhashCache::default());
+    let safety = Arc::new(MintSafety::new(
+        SafetyConfig::from_env()?,
+        rpc_client.clone(),
+    ));
+    let content = Arc::new(ContentFilter::new(ContentConfig::from_env()?));
+    let holders = Arc::new(HolderTracker::new(
+        HolderConfig::from_env()?,
+        rpc_client.clone(),
+    ));
+    let bundles = Arc::new(BundleDetector::new(
+        BundleConfig::from_env()?,
+        rpc_client.clone(),
+    ));
+
+    let reporter = tokio::spawn(reporter::run_reporter(bus.subscribe()));
+    let new_snapshot = || {
+        PipelineSnapshot::new(
+            config.min_market_cap_sol(coingecko_sol_usd_price),
+            config.market_cap_metric,
+            coingecko_sol_usd_price,
+            safety.clone(),
+        )
+    };
+    let dashboard = config.tui.then(|| {
+        tokio::spawn(tui::run_dashboard(
+            bus.subscribe(),
+            new_snapshot(),
+            shutdown.clone(),
+        ))
+    });
+    let recorder = match &config.store_path {
+        Some(path) => {
+            let conn = store::open(path)?;
+            info!(%path, "🗄️ Recording to store");
+            let events = bus.subscribe();
+            let curve_sample_ms = config.store_curve_sample_ms;
+            Some(tokio::task::spawn_blocking(move || {
+                store::run_recorder(events, conn, curve_sample_ms)
+            }))
+        }
+        None => None,
+    };
+    let notifier = notify::NotifyConfig::from_env()?
+        .map(|notify_config| tokio::spawn(notify::run_notifier(bus.subscribe(), notify_config)));
+
+    if let Some(addr) = config.control_api_addr.clone() {
+        let state = control::ControlState::new(
+            controls.clone(),
+            risk.clone(),
+            wallets.clone(),
+            order_tx.clone(),
+            rpc_client.clone(),
+            new_snapshot(),
+            bus.clone(),
+        );
+        let control_api =
+            control::serve_control_api(addr, state, bus.subscribe(), shutdown.clone());
+        tokio::spawn(async move {
+            if let Err(e) = control_api.await {
+                warn!(error = %e, "⚠️ Control API stopped");
+            }
+        });
+    }
+
+    tokio::spawn(wallet::run_wallet_monitor(
+        wallets.iter().map(|pooled| pooled.wallet.clone()).collect(),
+        rpc_client.clone(),
+        config.clone(),
+        bus.clone(),
+        shutdown.clone(),
+    ));
+
+    tokio::spawn(monitor_global::monitor_global(
+        templates.clone(),
+        rpc_client.clone(),
         config.clone(),
+        bus.clone(),
+        shutdown.clone(),
+    ));
+    tokio::spawn(run_blockhash_refresher(
+        blockhash.clone(),
+        rpc_client.clone(),
+        shutdown.clone(),
+    ));
+
+    let context = Arc::new(ExecutionContext {
+        wallets,
+        rpc_client,
+        templates: templates.clone(),
+        blockhash,
+    });
+    let executor: StageHandle = tokio::spawn(executor::run_executor(
+        order_rx,
+        bus.clone(),
+        config.clone(),
+        controls.clone(),
+        risk,
+        context,
+        shutdown.clone(),
+    ));
+
+    let strategy: StageHandle = tokio::spawn(strategy::run_strategy(
+        curve_rx,
+        order_tx,
+        bus.clone(),
+        controls,
+        Gates {
+            safety: safety.clone(),
+            content: content.clone(),
+            holders: holders.clone(),
+            bundles: bundles.clone(),
+            velocity: VelocityConfig::from_env()?,
+        },
+        config.market_cap_metric,
         coingecko_sol_usd_price,
     ));
 
-    // Start transaction monitoring (blocks on main thread)
-    monitor_transaction::monitor_transactions(current_batch, processed_tokens, config).await?;
+    info!("🔍 Starting account monitoring...");
+    // Spawn account monitoring task
+    let account_monitor: StageHandle = tokio::spawn(monitor_account::monitor_batches(
+        token_rx,
+        curve_tx,
+        TokenPrep {
+            templates,
+            safety,
+            content,
+            holders,
+            bundles: bundles.clone(),
+        },
+        bus.clone(),
+        config.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start transaction monitoring (runs until shutdown on main thread)
+    let mut exit_code = ExitCode::SUCCESS;
+    if let Err(e) = monitor_transaction::monitor_transactions(
+        token_tx,
+        bundles,
+        bus,
+        config.clone(),
+        shutdown.clone(),
+    )
+    .await
+    {
+        error!(error = %e, "❌ Transaction monitoring failed");
+        exit_code = ExitCode::from(EXIT_STAGE_FAILED);
+    }
+    // Ingest is done either way, let the rest of the pipeline drain
+    shutdown.trigger();
+
+    // Hand the terminal back before anything else is printed
+    if let Some(dashboard) = dashboard
+        && let Ok(Err(e)) = dashboard.await
+    {
+        warn!(error = %e, "⚠️ Dashboard failed");
+    }
+
+    let drain = async {
+        let mut all_ok = true;
+        for (name, stage) in [
+            ("account monitor", account_monitor),
+            ("strategy", strategy),
+            ("executor", executor),
+        ] {
+            all_ok &= stage_finished(name, stage.await);
+        }
+        all_ok
+    };
+
+    match timeout(Duration::from_secs(config.shutdown_timeout_secs), drain).await {
+        Ok(true) => {}
+        Ok(false) => exit_code = ExitCode::from(EXIT_STAGE_FAILED),
+        Err(_) => {
+            warn!(
+                timeout_secs = config.shutdown_timeout_secs,
+                "⚠️ Pipeline did not drain in time, abandoning in-flight work"
+            );
+            exit_code = ExitCode::from(EXIT_SHUTDOWN_TIMEOUT);
+        }
+    }
+
+    // The reporter finishes once every stage has dropped its bus handle
+    match timeout(Duration::from_secs(config.shutdown_timeout_secs), reporter).await {
+        Ok(Ok(summary)) => summary.print(),
+        _ => warn!("⚠️ Reporter did not finish, no summary available"),
+    }
+    // The recorder also stops once the bus closes, after writing the last events
+    if let Some(recorder) = recorder
+        && timeout(Duration::from_secs(config.shutdown_timeout_secs), recorder)
+            .await
+            .is_err()
+    {
+        warn!("⚠️ Store recorder did not finish writing");
+    }
+    // Give the last notifications a chance to go out
+    if let Some(notifier) = notifier
+        && timeout(Duration::from_secs(config.shutdown_timeout_secs), notifier)
+            .await
+            .is_err()
+    {
+        warn!("⚠️ Notifier did not finish sending");
+    }
+
+    Ok(exit_code)
+}
 
-    account_monitor.await??;
-    Ok(())
+fn stage_finished(
+    name: &str,
+    result: Result<Result<(), Box<dyn std::error::Error + Send + Sync>>, tokio::task::JoinError>,
+) -> bool {
+    match result {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            error!(stage = name, error = %e, "❌ Stage failed");
+            false
+        }
+        Err(e) => {
+            error!(stage = name, error = %e, "❌ Stage task panicked");
+            false
+        }
+    }
 }
@@ -0,0 +1,4 @@
+pub mod registry;
+pub mod server;
+
+pub use registry::METRICS;
@@ -0,0 +1,126 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    exponential_buckets,
+};
+
+/// Process-wide metrics, exported on `/metrics` by the metrics server.
+pub static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub struct Metrics {
+    pub registry: Registry,
+    pub creates_seen: IntCounter,
+    pub creates_parsed: IntCounter,
+    pub creates_failed: IntCounter,
+    pub processed_tokens: IntGauge,
+    pub batch_size: Histogram,
+    pub account_updates: IntCounter,
+    pub signals: IntCounter,
+    /// Labelled by stage: built, simulated, sent, landed, failed, vetoed
+    pub transactions: IntCounterVec,
+    /// Labelled by stream name
+    pub stream_reconnects: IntCounterVec,
+    pub create_to_signal_seconds: Histogram,
+    pub create_to_signal_slots: Histogram,
+    pub signal_to_signed_seconds: Histogram,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new_custom(Some("pump_sniper".to_string()), None)
+            .expect("valid metrics prefix");
+
+        let metrics = Self {
+            creates_seen: IntCounter::new("creates_seen_total", "CREATE instructions seen")
+                .unwrap(),
+            creates_parsed: IntCounter::new(
+                "creates_parsed_total",
+                "CREATE instructions parsed successfully",
+            )
+            .unwrap(),
+            creates_failed: IntCounter::new(
+                "creates_failed_total",
+                "CREATE instructions that failed to parse",
+            )
+            .unwrap(),
+            processed_tokens: IntGauge::new(
+                "processed_tokens",
+                "Mints currently held in the de-duplication set",
+            )
+            .unwrap(),
+            batch_size: Histogram::with_opts(
+                HistogramOpts::new("batch_size", "Tokens per collection batch")
+                    .buckets(exponential_buckets(1.0, 2.0, 10).unwrap()),
+            )
+            .unwrap(),
+            account_updates: IntCounter::new(
+                "account_updates_total",
+                "Bonding curve account updates received",
+            )
+            .unwrap(),
+            signals: IntCounter::new("signals_total", "Tokens that passed eligibility").unwrap(),
+            transactions: IntCounterVec::new(
+                Opts::new("transactions_total", "Transactions by stage"),
+                &["stage"],
+            )
+            .unwrap(),
+            stream_reconnects: IntCounterVec::new(
+                Opts::new("stream_reconnects_total", "Laserstream reconnect attempts"),
+                &["stream"],
+            )
+            .unwrap(),
+            create_to_signal_seconds: Histogram::with_opts(
+                HistogramOpts::new(
+                    "create_to_signal_seconds",
+                    "Time from seeing the CREATE to emitting a signal",
+                )
+                .buckets(exponential_buckets(0.5, 2.0, 10).unwrap()),
+            )
+            .unwrap(),
+            create_to_signal_slots: Histogram::with_opts(
+                HistogramOpts::new(
+                    "create_to_signal_slots",
+                    "Slots between the CREATE and the curve update that triggered a signal",
+                )
+                .buckets(exponential_buckets(1.0, 2.0, 10).unwrap()),
+            )
+            .unwrap(),
+            signal_to_signed_seconds: Histogram::with_opts(
+                HistogramOpts::new(
+                    "signal_to_signed_seconds",
+                    "Time from signal to a signed buy transaction",
+                )
+                .buckets(exponential_buckets(0.0001, 4.0, 10).unwrap()),
+            )
+            .unwrap(),
+            registry,
+        };
+
+        metrics.register();
+        metrics
+    }
+
+    fn register(&self) {
+        let collectors: [Box<dyn prometheus::core::Collector>; 12] = [
+            Box::new(self.creates_seen.clone()),
+            Box::new(self.creates_parsed.clone()),
+            Box::new(self.creates_failed.clone()),
+            Box::new(self.processed_tokens.clone()),
+            Box::new(self.batch_size.clone()),
+            Box::new(self.account_updates.clone()),
+            Box::new(self.signals.clone()),
+            Box::new(self.transactions.clone()),
+            Box::new(self.stream_reconnects.clone()),
+            Box::new(self.create_to_signal_seconds.clone()),
+            Box::new(self.create_to_signal_slots.clone()),
+            Box::new(self.signal_to_signed_seconds.clone()),
+        ];
+        for collector in collectors {
+            // Names are unique and fixed, so this only fails on a programming error
+            self.registry
+                .register(collector)
+                .expect("metric registered once");
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use axum::{Router, http::header, response::IntoResponse, routing::get};
+use prometheus::{Encoder, TextEncoder};
+
+use crate::metrics::METRICS;
+
+/// Serves the Prometheus text format on `GET /metrics` until the process exits.
+pub async fn serve_metrics(addr: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let app = Router::new().route("/metrics", get(metrics_handler));
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    tracing::info!("📈 Metrics available at http://{}/metrics", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn metrics_handler() -> impl IntoResponse {
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(e) = encoder.encode(&METRICS.registry.gather(), &mut body) {
+        tracing::warn!(error = %e, "⚠️ Failed to encode metrics");
+    }
+    (
+        [(header::CONTENT_TYPE, encoder.format_type().to_string())],
+        body,
+    )
+}
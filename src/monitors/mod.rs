@@ -1,2 +1,4 @@
 pub mod monitor_account;
+pub mod monitor_global;
 pub mod monitor_transaction;
+pub mod supervisor;
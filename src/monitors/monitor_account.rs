@@ -1,66 +1,176 @@
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 use bs58;
-use futures_util::StreamExt;
-use helius_laserstream::{
-    LaserstreamConfig,
-    grpc::{CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts},
-    subscribe,
-};
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{pubkey::Pubkey, signature::Keypair};
-use spl_associated_token_account::get_associated_token_address;
-use tokio::time::{Duration, sleep};
-
-use crate::execute_ixs::buy;
+use helius_laserstream::grpc::{CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio::time::{Duration, Instant, sleep_until};
+use tracing::{debug, info, warn};
+
+use crate::bundles::BundleDetector;
+use crate::content::ContentFilter;
+use crate::execute_ixs::template::BuyTemplates;
+use crate::holders::HolderTracker;
+use crate::metrics::METRICS;
+use crate::monitors::supervisor::spawn_supervised_stream;
+use crate::safety::MintSafety;
+
+use crate::pipeline::{CurveUpdate, EventBus, Shutdown, SniperEvent, TrackerMessage};
+use crate::types::{BondingCurve, TokenInfo};
 use crate::utils::config::Config;
-use crate::{
-    types::{BondingCurve, TokenInfo},
-    utils::helper_functions::calculate_market_cap,
-};
+use crate::utils::constants::PUMP_PROGRAM;
+
+/// Per-token state prepared when a token joins a batch and dropped when the
+/// batch ends: the buy template, the mint safety report and the content
+/// verdict. Holder tables are filled while the batch is monitored; launches
+/// registered by the transaction monitor are dropped with the rest.
+#[derive(Clone)]
+pub struct TokenPrep {
+    pub templates: Arc<BuyTemplates>,
+    pub safety: Arc<MintSafety>,
+    pub content: Arc<ContentFilter>,
+    pub holders: Arc<HolderTracker>,
+    pub bundles: Arc<BundleDetector>,
+}
+
+impl TokenPrep {
+    pub async fn prepare(&self, token: &TokenInfo) {
+        tokio::join!(
+            self.templates.prepare(token),
+            self.safety.inspect(token),
+            self.content.screen(token)
+        );
+    }
+
+    pub fn remove(&self, mints: &[String]) {
+        self.templates.remove(mints);
+        self.safety.remove(mints);
+        self.content.remove(mints);
+        self.holders.remove(mints);
+        self.bundles.remove(mints);
+    }
+}
 
+/// Tracker stage: collects new tokens into batches of `collection_window_secs`
+/// and watches each batch's bonding curves for `monitoring_window_secs`,
+/// forwarding every curve update to the strategy. A [`TokenPrep`] is run for
+/// each token as it joins a batch and dropped when the batch ends, after any
+/// preparation still running for it is stopped. On
+/// shutdown the running batches stop early and the function returns once
+/// they have finished.
 pub async fn monitor_batches(
-    current_batch: Arc<Mutex<Vec<TokenInfo>>>,
+    mut new_tokens: mpsc::Receiver<TokenInfo>,
+    curve_updates: mpsc::Sender<TrackerMessage>,
+    prep: TokenPrep,
+    bus: EventBus,
     config: Config,
-    coingecko_sol_usd_price: f64,
+    shutdown: Shutdown,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    loop {
-        sleep(Duration::from_secs(config.collection_window_secs)).await;
+    let window = Duration::from_secs(config.collection_window_secs);
+    let mut current_batch: Vec<TokenInfo> = Vec::new();
+    // Preparation of the current batch's tokens, handed to the batch with it
+    let mut preparing = JoinSet::new();
+    let mut window_end = Instant::now() + window;
+    let mut batch_tasks = JoinSet::new();
 
-        let batch = {
-            let mut current = current_batch.lock().unwrap();
-            current.drain(..).collect::<Vec<_>>()
-        };
+    loop {
+        tokio::select! {
+            token = new_tokens.recv() => match token {
+                Some(token) => {
+                    prep.content.index(&token);
+                    // These go out over the network, keep them off the collection loop
+                    let prep = prep.clone();
+                    let prepared = token.clone();
+                    preparing.spawn(async move { prep.prepare(&prepared).await });
+                    current_batch.push(token);
+                }
+                // Ingest stopped, nothing more will arrive
+                None => break,
+            },
+            _ = sleep_until(window_end) => {
+                window_end = Instant::now() + window;
+                let batch = std::mem::take(&mut current_batch);
+                let mut batch_preparing = std::mem::take(&mut preparing);
+
+                // Reap batches whose monitoring window already ended
+                while batch_tasks.try_join_next().is_some() {}
+
+                if batch.is_empty() {
+                    info!(
+                        "📦 {}-second window ended. No new tokens collected.",
+                        config.collection_window_secs
+                    );
+                    continue;
+                }
 
-        if batch.is_empty() {
-            println!(
-                "📦 {}-second window ended. No new tokens collected.\n",
-                config.collection_window_secs
-            );
-            continue;
+                METRICS.batch_size.observe(batch.len() as f64);
+                info!(
+                    batch_size = batch.len(),
+                    "📦 {}-second collection window ended. Starting {}-second monitoring period...",
+                    config.collection_window_secs,
+                    config.monitoring_window_secs
+                );
+
+                bus.publish(SniperEvent::BatchStarted(batch.clone()));
+
+                // Monitor in the background so collection of the next batch continues
+                let curve_updates = curve_updates.clone();
+                let prep = prep.clone();
+                let bus = bus.clone();
+                let config = config.clone();
+                let shutdown = shutdown.clone();
+                batch_tasks.spawn(async move {
+                    let mints: Vec<String> = batch.iter().map(|token| token.mint.clone()).collect();
+                    let holders = {
+                        let holders = prep.holders.clone();
+                        let batch = batch.clone();
+                        let config = config.clone();
+                        let bus = bus.clone();
+                        tokio::spawn(async move { holders.watch(&batch, &config, bus).await })
+                    };
+                    if let Err(e) =
+                        monitor_batch(batch, &curve_updates, &prep.templates, &bus, &config, &shutdown)
+                            .await
+                    {
+                        warn!(error = %e, "⚠️ Error monitoring batch");
+                    }
+                    holders.abort();
+                    // A late fetch must not store anything after the removal
+                    batch_preparing.shutdown().await;
+                    prep.remove(&mints);
+                    // Nothing to tell a strategy that already stopped
+                    let _ = curve_updates
+                        .send(TrackerMessage::BatchEnded(mints.clone()))
+                        .await;
+                    bus.publish(SniperEvent::BatchEnded(mints));
+                });
+            }
         }
+    }
 
-        println!(
-            "\n📦 {}-second collection window ended. Collected {} tokens.",
-            config.collection_window_secs,
-            batch.len()
+    if !current_batch.is_empty() {
+        let mints: Vec<String> = current_batch.iter().map(|t| t.mint.clone()).collect();
+        preparing.shutdown().await;
+        prep.remove(&mints);
+        info!(
+            dropped = current_batch.len(),
+            "📦 Dropping collected tokens that were never monitored"
         );
-        println!(
-            "🔍 Starting {}-second monitoring period with Laserstream...\n",
-            config.monitoring_window_secs
-        );
-
-        if let Err(e) = monitor_batch(batch, &config, coingecko_sol_usd_price).await {
-            eprintln!("⚠️ Error monitoring batch: {}", e);
-        }
     }
+    while batch_tasks.join_next().await.is_some() {}
+
+    Ok(())
 }
 
 async fn monitor_batch(
     batch: Vec<TokenInfo>,
+    curve_updates: &mpsc::Sender<TrackerMessage>,
+    templates: &BuyTemplates,
+    bus: &EventBus,
     config: &Config,
-    coingecko_sol_usd_price: f64,
+    shutdown: &Shutdown,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let token_map: HashMap<String, TokenInfo> = batch
         .iter()
@@ -68,12 +178,10 @@ async fn monitor_batch(
         .collect();
 
     let bonding_curves: Vec<String> = batch.iter().map(|t| t.bonding_curve.clone()).collect();
-    println!("📋 Subscribing to {} bonding curves:", bonding_curves.len());
-    for (idx, curve) in bonding_curves.iter().take(10).enumerate() {
-        println!("{}: {}", idx + 1, curve);
-    }
-    if bonding_curves.len() > 10 {
-        println!("   ... and {} more", bonding_curves.len() - 10);
+    for token in &batch {
+        token
+            .span()
+            .in_scope(|| debug!(name = %token.name, "📋 Added to monitoring batch"));
     }
 
     // Build a single subscription entry with all bonding curves
@@ -81,7 +189,7 @@ async fn monitor_batch(
         "bonding_curves".to_string(),
         SubscribeRequestFilterAccounts {
             account: bonding_curves,
-            owner: vec!["6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P".to_string()],
+            owner: vec![PUMP_PROGRAM.to_string()],
             ..Default::default()
         },
     )]);
@@ -92,25 +200,31 @@ async fn monitor_batch(
         ..Default::default()
     };
 
-    let laserstream_config =
-        LaserstreamConfig::new(config.laserstream_endpoint.clone(), config.api_key.clone());
-
-    println!(
+    info!(
         "🔌 Subscribing to {} bonding curve accounts...",
         batch.len()
     );
-    let (account_stream, _account_handle) = subscribe(laserstream_config, account_request);
-    tokio::pin!(account_stream);
+    // Dropping the receiver when the window ends also stops the supervisor
+    let mut account_stream =
+        spawn_supervised_stream("bonding_curves", account_request, config, bus.clone());
 
     let batch_start = std::time::Instant::now();
-    let mut found_tokens: HashSet<String> = HashSet::new();
 
     loop {
         let elapsed = batch_start.elapsed().as_secs();
 
+        if shutdown.is_triggered() {
+            info!(
+                elapsed_secs = elapsed,
+                "🛑 Stopping monitoring of {} tokens",
+                batch.len()
+            );
+            break;
+        }
+
         if elapsed >= config.monitoring_window_secs {
-            println!(
-                "⏱️  {}-second monitoring period ended. Checked {} tokens.\n",
+            info!(
+                "⏱️  {}-second monitoring period ended. Checked {} tokens.",
                 config.monitoring_window_secs,
                 batch.len()
             );
@@ -119,24 +233,28 @@ async fn monitor_batch(
 
         let timeout_duration = Duration::from_secs(1);
 
-        match tokio::time::timeout(timeout_duration, account_stream.next()).await {
-            Ok(Some(Ok(update))) => {
-                if let Err(e) = handle_account_update(
-                    update,
-                    &token_map,
-                    &mut found_tokens,
-                    elapsed,
-                    config,
-                    coingecko_sol_usd_price,
-                ) {
-                    eprintln!("⚠️ Error handling account update: {}", e);
+        match tokio::time::timeout(timeout_duration, account_stream.recv()).await {
+            Ok(Some(update)) => match handle_account_update(update, &token_map, elapsed) {
+                Ok(Some(curve_update)) => {
+                    METRICS.account_updates.inc();
+                    templates.check_creator(
+                        &curve_update.token.mint,
+                        Pubkey::new_from_array(curve_update.curve.creator),
+                    );
+                    bus.publish(SniperEvent::CurveUpdated(curve_update.clone()));
+                    if curve_updates
+                        .send(TrackerMessage::CurveUpdate(curve_update))
+                        .await
+                        .is_err()
+                    {
+                        return Err("Strategy stopped".into());
+                    }
                 }
-            }
-            Ok(Some(Err(e))) => {
-                eprintln!("⚠️ Account stream error: {:?}", e);
-            }
+                Ok(None) => {}
+                Err(e) => warn!(error = %e, "⚠️ Error handling account update"),
+            },
             Ok(None) => {
-                println!("⚠️ Account stream ended unexpectedly");
+                warn!("⚠️ Account stream supervisor stopped unexpectedly");
                 break;
             }
             Err(_) => {
@@ -151,108 +269,25 @@ async fn monitor_batch(
 fn handle_account_update(
     update: helius_laserstream::grpc::SubscribeUpdate,
     token_map: &HashMap<String, TokenInfo>,
-    found_tokens: &mut HashSet<String>,
     elapsed: u64,
-    config: &Config,
-    coingecko_sol_usd_price: f64,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    if let Some(update_oneof) = &update.update_oneof {
-        if let helius_laserstream::grpc::subscribe_update::UpdateOneof::Account(account_update) =
-            update_oneof
-        {
-            if let Some(account) = &account_update.account {
-                let account_pubkey = bs58::encode(&account.pubkey).into_string();
-
-                if let Some(token) = token_map.get(&account_pubkey) {
-                    // Skip if we've already found this token eligible
-                    if found_tokens.contains(&token.mint) {
-                        return Ok(());
-                    }
-
-                    let curve = BondingCurve::from_account_data(&account.data)?;
-
-                    let market_cap =
-                        calculate_market_cap(curve.virtual_sol_reserves, coingecko_sol_usd_price);
-
-                    println!(
-                        "📊 Update for {} ({}) at {}s - Market Cap: {:.2} SOL, Market Cap USD: ${:.2}",
-                        token.name, token.symbol, elapsed, market_cap.0, market_cap.1
-                    );
-
-                    if market_cap.0 >= config.min_market_cap_sol(coingecko_sol_usd_price) {
-                        println!(
-                            "✅ ELIGIBLE: {} ({}) - Market Cap SOL: {:.2} SOL (${:.0})",
-                            token.name, token.symbol, market_cap.0, market_cap.1
-                        );
-                        println!("   Mint: {}", token.mint);
-                        println!("   Bonding Curve: {}", token.bonding_curve);
-                        println!("   Creator: {}", token.creator);
-                        println!();
-
-                        // Build buy transaction
-                        println!("\n🔨 Building buy transaction...");
-
-                        // Calculate associated bonding curve address
-                        let mint_pubkey = Pubkey::from_str_const(&token.mint);
-
-                        let bonding_curve_pubkey = Pubkey::from_str_const(&token.bonding_curve);
-
-                        let associated_bonding_curve =
-                            get_associated_token_address(&bonding_curve_pubkey, &mint_pubkey);
-
-                        let keypair = Keypair::from_base58_string(&config.buyer_keypair);
-
-                        let buy_params = buy::BuyParams {
-                            mint: token.mint.clone(),
-                            bonding_curve: token.bonding_curve.clone(),
-                            associated_bonding_curve: associated_bonding_curve.to_string(),
-                            creator: bs58::encode(curve.creator).into_string(),
-                            amount_sol: config.buy_amount_lamports as f64 / 1_000_000_000.0,
-                            slippage_bps: config.slippage_bps,
-                            buyer_keypair: keypair,
-                        };
-
-                        let rpc_client = RpcClient::new(config.helius_rpc_url.clone());
-
-                        match buy::build_buy_transaction(
-                            buy_params,
-                            &rpc_client,
-                            curve.virtual_sol_reserves,
-                            curve.virtual_token_reserves,
-                        ) {
-                            Ok(buy_tx) => {
-                                println!("   ✅ Buy transaction built!");
-                                println!(
-                                    "   📝 Estimated tokens to receive: {}",
-                                    buy_tx.estimated_tokens
-                                );
-                                println!(
-                                    "   🏦 Your token account: {}",
-                                    buy_tx.buyer_token_account
-                                );
-
-                                // Optionally simulate
-                                if let Err(e) =
-                                    buy::simulate_buy_transaction(&buy_tx.transaction, &rpc_client)
-                                {
-                                    eprintln!("   ⚠️ Simulation warning: {}", e);
-                                }
-
-                                println!("   💾 Transaction ready (not executed)");
-                            }
-                            Err(e) => {
-                                eprintln!("   ❌ Failed to build transaction: {}", e);
-                            }
-                        }
-
-                        println!();
-
-                        found_tokens.insert(token.mint.clone());
-                    }
-                }
-            }
+) -> Result<Option<CurveUpdate>, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(helius_laserstream::grpc::subscribe_update::UpdateOneof::Account(account_update)) =
+        &update.update_oneof
+        && let Some(account) = &account_update.account
+    {
+        let account_pubkey = bs58::encode(&account.pubkey).into_string();
+
+        if let Some(token) = token_map.get(&account_pubkey) {
+            let curve = BondingCurve::from_account_data(&account.data)?;
+
+            return Ok(Some(CurveUpdate {
+                token: token.clone(),
+                curve,
+                slot: account_update.slot,
+                elapsed_secs: elapsed,
+            }));
         }
     }
 
-    Ok(())
+    Ok(None)
 }
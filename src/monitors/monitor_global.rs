@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use helius_laserstream::grpc::{
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+    subscribe_update::UpdateOneof,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use tracing::{info, warn};
+
+use crate::execute_ixs::accounts::{self, PUMP_GLOBAL};
+use crate::execute_ixs::template::BuyTemplates;
+use crate::monitors::supervisor::spawn_supervised_stream;
+use crate::pipeline::{EventBus, Shutdown};
+use crate::utils::config::Config;
+
+/// Watches the Pump.fun global account and keeps the buy templates' fee
+/// recipient current. Runs until shutdown.
+pub async fn monitor_global(
+    templates: Arc<BuyTemplates>,
+    rpc_client: Arc<RpcClient>,
+    config: Config,
+    bus: EventBus,
+    shutdown: Shutdown,
+) {
+    match accounts::fetch_fee_recipient(&rpc_client).await {
+        Ok(fee_recipient) => {
+            info!(%fee_recipient, "🏦 Fee recipient loaded");
+            templates.set_fee_recipient(fee_recipient);
+        }
+        Err(e) => {
+            warn!(error = %e, "⚠️ Fee recipient lookup failed, waiting for stream updates")
+        }
+    }
+
+    let request = SubscribeRequest {
+        accounts: HashMap::from([(
+            "global".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: vec![PUMP_GLOBAL.to_string()],
+                ..Default::default()
+            },
+        )]),
+        commitment: Some(CommitmentLevel::Confirmed.into()),
+        ..Default::default()
+    };
+    let mut updates = spawn_supervised_stream("global", request, &config, bus);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.wait() => break,
+            update = updates.recv() => match update {
+                Some(update) => {
+                    if let Some(UpdateOneof::Account(account_update)) = update.update_oneof
+                        && let Some(account) = account_update.account
+                    {
+                        match accounts::parse_fee_recipient(&account.data) {
+                            Ok(fee_recipient) => templates.set_fee_recipient(fee_recipient),
+                            Err(e) => warn!(error = %e, "⚠️ Error parsing global account"),
+                        }
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+}
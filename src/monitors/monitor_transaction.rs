@@ -1,26 +1,38 @@
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 
 use bs58;
-use futures_util::StreamExt;
-use helius_laserstream::{
-    LaserstreamConfig,
-    grpc::{CommitmentLevel, SubscribeRequest, SubscribeRequestFilterTransactions},
-    subscribe,
+use helius_laserstream::grpc::{
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterTransactions,
 };
+use tokio::sync::mpsc;
+use tokio::time::{Duration, interval};
+use tracing::{info, warn};
 
+use crate::bundles::BundleDetector;
+use crate::metrics::METRICS;
+use crate::monitors::supervisor::spawn_supervised_stream;
 use crate::parser::parse_create_instruction;
+use crate::pipeline::{EventBus, Shutdown, SniperEvent};
 use crate::types::TokenInfo;
 use crate::utils::config::Config;
-use crate::utils::constants::{CREATE_DISCRIMINATOR, PUMP_PROGRAM};
+use crate::utils::constants::{
+    CREATE_DISCRIMINATOR, DEDUP_MAINTENANCE_INTERVAL_SECS, PUMP_PROGRAM,
+};
+use crate::utils::dedup::ExpiringSet;
 
+/// Ingest stage: watches pump.fun transactions for CREATE instructions and
+/// forwards every new token to the tracker. Every transaction is also handed
+/// to the bundle detector to catch the early buys of new launches. Returns
+/// once shutdown is triggered, which closes the tracker's input.
 pub async fn monitor_transactions(
-    current_batch: Arc<Mutex<Vec<TokenInfo>>>,
-    processed_tokens: Arc<Mutex<HashSet<String>>>,
+    new_tokens: mpsc::Sender<TokenInfo>,
+    bundles: Arc<BundleDetector>,
+    bus: EventBus,
     config: Config,
+    shutdown: Shutdown,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let laserstream_config = LaserstreamConfig::new(config.laserstream_endpoint, config.api_key);
-
     let request = SubscribeRequest {
         transactions: HashMap::from([(
             "pump-txs".to_string(),
@@ -35,57 +47,94 @@ pub async fn monitor_transactions(
         ..Default::default()
     };
 
-    println!("🔌 Connecting to transaction stream...");
-    let (stream, _handle) = subscribe(laserstream_config, request);
-    futures::pin_mut!(stream);
-
-    while let Some(result) = stream.next().await {
-        match result {
-            Ok(update) => {
-                if let Some(txs) = &update.update_oneof {
-                    if let helius_laserstream::grpc::subscribe_update::UpdateOneof::Transaction(
-                        tx,
-                    ) = txs
-                    {
-                        if let Some(info) = &tx.transaction {
-                            if let Some(transaction) = &info.transaction {
-                                if let Some(message) = &transaction.message {
-                                    for ix in &message.instructions {
-                                        if ix.data.starts_with(&CREATE_DISCRIMINATOR) {
-                                            if let Err(e) = handle_create_instruction(
-                                                &ix.data,
-                                                &message.account_keys,
-                                                &current_batch,
-                                                &processed_tokens,
-                                            ) {
-                                                eprintln!(
-                                                    "⚠️ Failed to handle CREATE instruction: {}",
-                                                    e
-                                                );
-                                            }
-                                            break;
-                                        }
-                                    }
+    info!("🔌 Connecting to transaction stream...");
+    let mut stream = spawn_supervised_stream("transactions", request, &config, bus.clone());
+
+    let mut processed_tokens = load_processed_tokens(&config);
+    let mut maintenance = interval(Duration::from_secs(DEDUP_MAINTENANCE_INTERVAL_SECS));
+
+    loop {
+        let update = tokio::select! {
+            _ = shutdown.wait() => {
+                info!("🛑 Stopping transaction ingestion");
+                break;
+            }
+            _ = maintenance.tick() => {
+                processed_tokens.prune();
+                METRICS.processed_tokens.set(processed_tokens.len() as i64);
+                info!(
+                    tracked = processed_tokens.len(),
+                    evicted = processed_tokens.evicted(),
+                    "🧹 Processed tokens pruned"
+                );
+                save_processed_tokens(&mut processed_tokens, &config);
+                continue;
+            }
+            update = stream.recv() => match update {
+                Some(update) => update,
+                None => break,
+            },
+        };
+
+        if let Some(helius_laserstream::grpc::subscribe_update::UpdateOneof::Transaction(tx)) =
+            &update.update_oneof
+            && let Some(info) = &tx.transaction
+        {
+            if let Some(message) = info
+                .transaction
+                .as_ref()
+                .and_then(|transaction| transaction.message.as_ref())
+            {
+                for ix in &message.instructions {
+                    if ix.data.starts_with(&CREATE_DISCRIMINATOR) {
+                        METRICS.creates_seen.inc();
+                        let result = handle_create_instruction(
+                            &ix.data,
+                            &message.account_keys,
+                            tx.slot,
+                            &mut processed_tokens,
+                        );
+                        METRICS.processed_tokens.set(processed_tokens.len() as i64);
+                        match result {
+                            Ok(Some(token_info)) => {
+                                METRICS.creates_parsed.inc();
+                                bundles.launch(&token_info);
+                                bus.publish(SniperEvent::TokenCreated(token_info.clone()));
+                                if new_tokens.send(token_info).await.is_err() {
+                                    return Err("Tracker stopped".into());
                                 }
                             }
+                            Ok(None) => METRICS.creates_parsed.inc(),
+                            Err(e) => {
+                                METRICS.creates_failed.inc();
+                                warn!(
+                                    error = %e,
+                                    slot = tx.slot,
+                                    "⚠️ Failed to handle CREATE instruction"
+                                );
+                            }
                         }
+                        break;
                     }
                 }
             }
-            Err(e) => eprintln!("⚠️ Transaction Stream Error: {:?}", e),
+            // After the create, so a buy in the same transaction counts
+            bundles.observe(tx.slot, info);
         }
     }
 
+    save_processed_tokens(&mut processed_tokens, &config);
+
     Ok(())
 }
 
 fn handle_create_instruction(
     data: &[u8],
     account_keys: &[Vec<u8>],
-    current_batch: &Arc<Mutex<Vec<TokenInfo>>>,
-    processed_tokens: &Arc<Mutex<HashSet<String>>>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let (name, symbol) = parse_create_instruction(data)?;
+    slot: u64,
+    processed_tokens: &mut ExpiringSet,
+) -> Result<Option<TokenInfo>, Box<dyn std::error::Error + Send + Sync>> {
+    let (name, symbol, uri) = parse_create_instruction(data)?;
 
     if account_keys.len() < 3 {
         return Err("Not enough account keys".into());
@@ -96,18 +145,43 @@ fn handle_create_instruction(
     let creator = bs58::encode(&account_keys[0]).into_string();
 
     // Check if already processed
-    let mut processed = processed_tokens.lock().unwrap();
-    if processed.contains(&mint) {
-        return Ok(());
+    if !processed_tokens.insert(&mint) {
+        return Ok(None);
     }
-    processed.insert(mint.clone());
-    drop(processed);
 
-    let token_info = TokenInfo::new(mint, bonding_curve, name, symbol, creator);
-    token_info.print_creation();
+    Ok(Some(TokenInfo::new(
+        mint,
+        bonding_curve,
+        name,
+        symbol,
+        uri,
+        creator,
+        slot,
+    )))
+}
 
-    let mut batch = current_batch.lock().unwrap();
-    batch.push(token_info);
+fn load_processed_tokens(config: &Config) -> ExpiringSet {
+    let ttl = Duration::from_secs(config.dedup_ttl_secs);
+    let Some(path) = &config.dedup_state_path else {
+        return ExpiringSet::new(ttl, config.dedup_capacity);
+    };
 
-    Ok(())
+    match ExpiringSet::load(Path::new(path), ttl, config.dedup_capacity) {
+        Ok(set) => {
+            info!(restored = set.len(), %path, "🧹 Restored processed tokens");
+            set
+        }
+        Err(e) => {
+            warn!(error = %e, %path, "⚠️ Failed to load processed tokens");
+            ExpiringSet::new(ttl, config.dedup_capacity)
+        }
+    }
+}
+
+fn save_processed_tokens(processed_tokens: &mut ExpiringSet, config: &Config) {
+    if let Some(path) = &config.dedup_state_path
+        && let Err(e) = processed_tokens.save(Path::new(path))
+    {
+        warn!(error = %e, %path, "⚠️ Failed to save processed tokens");
+    }
 }
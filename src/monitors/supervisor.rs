@@ -0,0 +1,233 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use futures_util::StreamExt;
+use helius_laserstream::{
+    LaserstreamConfig,
+    grpc::{SubscribeRequest, SubscribeUpdate, subscribe_update::UpdateOneof},
+    subscribe,
+};
+use tokio::sync::mpsc;
+use tokio::time::{Duration, sleep};
+
+use crate::metrics::METRICS;
+use crate::pipeline::{EventBus, SniperEvent, StreamHealth, StreamStatus};
+use crate::utils::config::Config;
+use crate::utils::constants::STAGE_CHANNEL_CAPACITY;
+
+/// Subscribes to Laserstream with `request` and keeps the subscription alive
+/// until the returned receiver is dropped. When the stream errors or ends it
+/// is re-opened with jittered exponential backoff, resuming from the last seen slot if
+/// `stream_replay` is enabled. Connection changes are published on the bus.
+pub fn spawn_supervised_stream(
+    stream_name: &'static str,
+    request: SubscribeRequest,
+    config: &Config,
+    bus: EventBus,
+) -> mpsc::Receiver<SubscribeUpdate> {
+    let (updates_tx, updates_rx) = mpsc::channel(STAGE_CHANNEL_CAPACITY);
+    tokio::spawn(supervise(
+        stream_name,
+        request,
+        config.clone(),
+        bus,
+        updates_tx,
+    ));
+    updates_rx
+}
+
+async fn supervise(
+    stream_name: &'static str,
+    request: SubscribeRequest,
+    config: Config,
+    bus: EventBus,
+    updates: mpsc::Sender<SubscribeUpdate>,
+) {
+    let mut attempt: u32 = 0;
+    let mut last_slot: Option<u64> = None;
+
+    let publish = |status: StreamStatus, last_slot: Option<u64>| {
+        bus.publish(SniperEvent::StreamHealth(StreamHealth {
+            stream: stream_name,
+            status,
+            last_slot,
+        }));
+    };
+
+    loop {
+        let mut request = request.clone();
+        if config.stream_replay
+            && let Some(slot) = last_slot
+        {
+            request.from_slot = Some(slot);
+        }
+
+        let laserstream_config =
+            LaserstreamConfig::new(config.laserstream_endpoint.clone(), config.api_key.clone());
+        let (stream, _handle) = subscribe(laserstream_config, request);
+        tokio::pin!(stream);
+
+        let mut connected = false;
+
+        let reason = loop {
+            tokio::select! {
+                // Consumer is gone, nothing left to supervise
+                _ = updates.closed() => return,
+                next = stream.next() => match next {
+                    Some(Ok(update)) => {
+                        if !connected {
+                            connected = true;
+                            attempt = 0;
+                            publish(StreamStatus::Connected, last_slot);
+                        }
+                        last_slot = advance_slot(last_slot, &update);
+                        if updates.send(update).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Err(e)) => break format!("{:?}", e),
+                    None => break "stream ended".to_string(),
+                },
+            }
+        };
+
+        publish(StreamStatus::Disconnected { reason }, last_slot);
+
+        attempt = attempt.saturating_add(1);
+        METRICS
+            .stream_reconnects
+            .with_label_values(&[stream_name])
+            .inc();
+        let delay = backoff_delay(
+            attempt,
+            config.reconnect_initial_backoff_ms,
+            config.reconnect_max_backoff_ms,
+            random_fraction(),
+        );
+        publish(
+            StreamStatus::Reconnecting {
+                attempt,
+                delay_ms: delay.as_millis() as u64,
+            },
+            last_slot,
+        );
+
+        tokio::select! {
+            _ = updates.closed() => return,
+            _ = sleep(delay) => {}
+        }
+    }
+}
+
+/// Doubles the delay for every failed attempt, capped at `max_ms`, then
+/// takes off up to half of it by `jitter` (in `[0, 1)`) so streams dropped
+/// together don't reconnect in lockstep.
+fn backoff_delay(attempt: u32, initial_ms: u64, max_ms: u64, jitter: f64) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    let capped_ms = initial_ms.saturating_mul(factor).min(max_ms);
+    Duration::from_millis(capped_ms - (capped_ms as f64 * jitter.clamp(0.0, 1.0) / 2.0) as u64)
+}
+
+/// Uniform in `[0, 1)`, from the randomly seeded std hasher.
+fn random_fraction() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// The highest slot seen so far, never moving back for a late update.
+fn advance_slot(last_slot: Option<u64>, update: &SubscribeUpdate) -> Option<u64> {
+    match update_slot(update) {
+        Some(slot) => Some(last_slot.map_or(slot, |last| last.max(slot))),
+        None => last_slot,
+    }
+}
+
+fn update_slot(update: &SubscribeUpdate) -> Option<u64> {
+    match update.update_oneof.as_ref()? {
+        UpdateOneof::Account(account) => Some(account.slot),
+        UpdateOneof::Transaction(tx) => Some(tx.slot),
+        UpdateOneof::Slot(slot) => Some(slot.slot),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use helius_laserstream::grpc::{
+        SubscribeUpdateAccount, SubscribeUpdatePing, SubscribeUpdateSlot,
+        SubscribeUpdateTransaction,
+    };
+
+    use super::*;
+
+    fn update(update: UpdateOneof) -> SubscribeUpdate {
+        SubscribeUpdate {
+            update_oneof: Some(update),
+            ..Default::default()
+        }
+    }
+
+    fn slot_update(slot: u64) -> SubscribeUpdate {
+        update(UpdateOneof::Slot(SubscribeUpdateSlot {
+            slot,
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn doubles_the_delay_up_to_the_cap() {
+        let delays: Vec<u64> = (1..=6)
+            .map(|attempt| backoff_delay(attempt, 500, 5_000, 0.0).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, [500, 1_000, 2_000, 4_000, 5_000, 5_000]);
+        // Far past the cap without overflowing
+        assert_eq!(backoff_delay(u32::MAX, 500, 5_000, 0.0).as_millis(), 5_000);
+    }
+
+    #[test]
+    fn jitter_takes_off_at_most_half() {
+        assert_eq!(backoff_delay(3, 500, 30_000, 0.5).as_millis(), 1_500);
+        assert_eq!(backoff_delay(3, 500, 30_000, 0.999_999).as_millis(), 1_001);
+        assert_eq!(backoff_delay(3, 500, 30_000, 7.0).as_millis(), 1_000);
+
+        for _ in 0..100 {
+            let jitter = random_fraction();
+            assert!((0.0..1.0).contains(&jitter));
+            let delay = backoff_delay(10, 500, 30_000, jitter).as_millis();
+            assert!((15_000..=30_000).contains(&delay), "{}", delay);
+        }
+    }
+
+    #[test]
+    fn reads_the_slot_of_each_update_kind() {
+        let account = update(UpdateOneof::Account(SubscribeUpdateAccount {
+            slot: 5,
+            ..Default::default()
+        }));
+        let transaction = update(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            slot: 6,
+            ..Default::default()
+        }));
+        assert_eq!(update_slot(&account), Some(5));
+        assert_eq!(update_slot(&transaction), Some(6));
+        assert_eq!(update_slot(&slot_update(7)), Some(7));
+        assert_eq!(
+            update_slot(&update(UpdateOneof::Ping(SubscribeUpdatePing {}))),
+            None
+        );
+        assert_eq!(update_slot(&SubscribeUpdate::default()), None);
+    }
+
+    #[test]
+    fn keeps_the_highest_slot_seen() {
+        let mut last_slot = None;
+        for slot in [10, 12, 11] {
+            last_slot = advance_slot(last_slot, &slot_update(slot));
+        }
+        assert_eq!(last_slot, Some(12));
+
+        let ping = update(UpdateOneof::Ping(SubscribeUpdatePing {}));
+        assert_eq!(advance_slot(last_slot, &ping), Some(12));
+        assert_eq!(advance_slot(None, &ping), None);
+    }
+}
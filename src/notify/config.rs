@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::env;
+
+use crate::notify::sinks::Sink;
+use crate::notify::template::{
+    DEFAULT_FAILURE_TEMPLATE, DEFAULT_FILL_TEMPLATE, DEFAULT_HEALTH_TEMPLATE,
+    DEFAULT_SIGNAL_TEMPLATE,
+};
+
+/// Event types a notification can be routed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotifyKind {
+    Signal,
+    Fill,
+    Failure,
+    Health,
+}
+
+impl NotifyKind {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "signal" => Some(NotifyKind::Signal),
+            "fill" => Some(NotifyKind::Fill),
+            "failure" => Some(NotifyKind::Failure),
+            "health" => Some(NotifyKind::Health),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NotifyConfig {
+    pub sinks: Vec<Sink>,
+    /// Sink names to deliver to, per event type
+    pub routes: HashMap<NotifyKind, Vec<String>>,
+    pub templates: HashMap<NotifyKind, String>,
+    pub rate_limit_per_min: u32,
+}
+
+impl NotifyConfig {
+    /// Reads the notifier settings. Returns `None` when no sink is configured.
+    pub fn from_env() -> Result<Option<Self>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut sinks = Vec::new();
+
+        if let Some(url) = non_empty_var("NOTIFY_WEBHOOK_URL") {
+            sinks.push(Sink::Webhook { url });
+        }
+        if let Some(bot_token) = non_empty_var("NOTIFY_TELEGRAM_BOT_TOKEN") {
+            let chat_id = non_empty_var("NOTIFY_TELEGRAM_CHAT_ID")
+                .ok_or("NOTIFY_TELEGRAM_CHAT_ID must be set with NOTIFY_TELEGRAM_BOT_TOKEN")?;
+            sinks.push(Sink::Telegram {
+                api_base: non_empty_var("NOTIFY_TELEGRAM_API_BASE")
+                    .unwrap_or_else(|| "https://api.telegram.org".to_string()),
+                bot_token,
+                chat_id,
+            });
+        }
+        if let Some(url) = non_empty_var("NOTIFY_DISCORD_WEBHOOK_URL") {
+            sinks.push(Sink::Discord { url });
+        }
+
+        if sinks.is_empty() {
+            return Ok(None);
+        }
+
+        let routes = parse_routes(
+            &non_empty_var("NOTIFY_ROUTES")
+                .unwrap_or_else(|| "signal=all;fill=all;failure=all".to_string()),
+            &sinks,
+        )?;
+
+        let templates = HashMap::from([
+            (
+                NotifyKind::Signal,
+                non_empty_var("NOTIFY_TEMPLATE_SIGNAL")
+                    .unwrap_or_else(|| DEFAULT_SIGNAL_TEMPLATE.to_string()),
+            ),
+            (
+                NotifyKind::Fill,
+                non_empty_var("NOTIFY_TEMPLATE_FILL")
+                    .unwrap_or_else(|| DEFAULT_FILL_TEMPLATE.to_string()),
+            ),
+            (
+                NotifyKind::Failure,
+                non_empty_var("NOTIFY_TEMPLATE_FAILURE")
+                    .unwrap_or_else(|| DEFAULT_FAILURE_TEMPLATE.to_string()),
+            ),
+            (
+                NotifyKind::Health,
+                non_empty_var("NOTIFY_TEMPLATE_HEALTH")
+                    .unwrap_or_else(|| DEFAULT_HEALTH_TEMPLATE.to_string()),
+            ),
+        ]);
+
+        Ok(Some(NotifyConfig {
+            sinks,
+            routes,
+            templates,
+            rate_limit_per_min: env::var("NOTIFY_RATE_LIMIT_PER_MIN")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()?,
+        }))
+    }
+}
+
+fn non_empty_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Parses `kind=sink,sink;kind=all` into a routing table. `all` expands to
+/// every configured sink.
+fn parse_routes(
+    spec: &str,
+    sinks: &[Sink],
+) -> Result<HashMap<NotifyKind, Vec<String>>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut routes = HashMap::new();
+
+    for route in spec.split(';').filter(|route| !route.trim().is_empty()) {
+        let (kind, targets) = route
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid NOTIFY_ROUTES entry: {}", route))?;
+        let kind =
+            NotifyKind::parse(kind).ok_or_else(|| format!("Unknown notify event: {}", kind))?;
+
+        let mut names = Vec::new();
+        for target in targets.split(',').map(str::trim) {
+            if target == "all" {
+                names.extend(sinks.iter().map(|sink| sink.name().to_string()));
+            } else if sinks.iter().any(|sink| sink.name() == target) {
+                names.push(target.to_string());
+            } else {
+                return Err(format!("NOTIFY_ROUTES targets unconfigured sink: {}", target).into());
+            }
+        }
+        routes.insert(kind, names);
+    }
+
+    Ok(routes)
+}
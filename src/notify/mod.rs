@@ -0,0 +1,8 @@
+pub mod config;
+pub mod notifier;
+pub mod rate_limit;
+pub mod sinks;
+pub mod template;
+
+pub use config::NotifyConfig;
+pub use notifier::run_notifier;
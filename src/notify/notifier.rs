@@ -0,0 +1,442 @@
+use std::collections::HashMap;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::Duration;
+use tracing::{debug, warn};
+
+use crate::notify::config::{NotifyConfig, NotifyKind};
+use crate::notify::rate_limit::RateLimiter;
+use crate::notify::template::render;
+use crate::pipeline::{OrderSide, SniperEvent, StreamStatus};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Posts notifications for signals, fills, failures and stream outages to
+/// the configured sinks. Runs until the event bus closes.
+pub async fn run_notifier(mut events: broadcast::Receiver<SniperEvent>, config: NotifyConfig) {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    let mut limiters: HashMap<&'static str, RateLimiter> = config
+        .sinks
+        .iter()
+        .map(|sink| {
+            (
+                sink.name(),
+                RateLimiter::per_minute(config.rate_limit_per_min),
+            )
+        })
+        .collect();
+
+    let mut tracked = Tracked::default();
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(skipped, "⚠️ Notifier lagged behind");
+                continue;
+            }
+            Err(RecvError::Closed) => break,
+        };
+
+        let Some((kind, fields)) = notification_fields(&event, &mut tracked) else {
+            continue;
+        };
+        let Some(targets) = config.routes.get(&kind) else {
+            continue;
+        };
+        let text = render(&config.templates[&kind], &fields);
+
+        for sink in config
+            .sinks
+            .iter()
+            .filter(|sink| targets.iter().any(|name| name == sink.name()))
+        {
+            let limiter = limiters
+                .get_mut(sink.name())
+                .expect("limiter for every sink");
+            if !limiter.try_acquire() {
+                warn!(
+                    sink = sink.name(),
+                    ?kind,
+                    "⚠️ Notification rate limited, dropping"
+                );
+                continue;
+            }
+
+            match sink.send(&client, kind, &text, &fields).await {
+                Ok(()) => debug!(sink = sink.name(), ?kind, "📨 Notification sent"),
+                Err(e) => warn!(sink = sink.name(), error = %e, "⚠️ Failed to send notification"),
+            }
+        }
+    }
+}
+
+/// Bought amount and cost of a mint, to value its sells against.
+#[derive(Debug, Default)]
+struct Holding {
+    tokens: u64,
+    cost_lamports: u64,
+}
+
+/// What the notifier remembers between events.
+#[derive(Debug, Default)]
+struct Tracked {
+    /// Name and symbol of signalled tokens, so fills and failures can
+    /// mention them. Dropped when their batch ends unless still held, and
+    /// once a held token is sold out.
+    token_names: HashMap<String, (String, String)>,
+    holdings: HashMap<String, Holding>,
+}
+
+impl Tracked {
+    fn name(&self, mint: &str) -> (String, String) {
+        self.token_names.get(mint).cloned().unwrap_or_default()
+    }
+
+    /// Records a fill. Returns the profit of a sell against the cost of the
+    /// tokens it sold, `None` without a recorded buy.
+    fn record_fill(
+        &mut self,
+        side: OrderSide,
+        mint: &str,
+        tokens: u64,
+        lamports: u64,
+    ) -> Option<i64> {
+        match side {
+            OrderSide::Buy => {
+                let holding = self.holdings.entry(mint.to_string()).or_default();
+                holding.tokens += tokens;
+                holding.cost_lamports += lamports;
+                None
+            }
+            OrderSide::Sell => {
+                let holding = self.holdings.get_mut(mint)?;
+                if holding.tokens == 0 {
+                    return None;
+                }
+                let sold = tokens.min(holding.tokens);
+                let cost =
+                    (holding.cost_lamports as u128 * sold as u128 / holding.tokens as u128) as u64;
+                holding.tokens -= sold;
+                holding.cost_lamports -= cost;
+                if holding.tokens == 0 {
+                    self.holdings.remove(mint);
+                }
+                Some(lamports as i64 - cost as i64)
+            }
+        }
+    }
+
+    fn end_batch(&mut self, mints: &[String]) {
+        for mint in mints {
+            if !self.holdings.contains_key(mint) {
+                self.token_names.remove(mint);
+            }
+        }
+    }
+}
+
+fn notification_fields(
+    event: &SniperEvent,
+    tracked: &mut Tracked,
+) -> Option<(NotifyKind, Vec<(&'static str, String)>)> {
+    match event {
+        SniperEvent::BatchEnded(mints) => {
+            tracked.end_batch(mints);
+            None
+        }
+        SniperEvent::Signal(signal) => {
+            let token = &signal.token;
+            tracked.token_names.insert(
+                token.mint.clone(),
+                (token.name.clone(), token.symbol.clone()),
+            );
+            Some((
+                NotifyKind::Signal,
+                vec![
+                    ("name", token.name.clone()),
+                    ("symbol", token.symbol.clone()),
+                    ("mint", token.mint.clone()),
+                    ("mint_link", mint_link(&token.mint)),
+                    ("market_cap_sol", format!("{:.2}", signal.market_cap_sol)),
+                    ("market_cap_usd", format!("{:.0}", signal.market_cap_usd)),
+                ],
+            ))
+        }
+        SniperEvent::OrderFilled(fill) => {
+            let (name, symbol) = tracked.name(&fill.mint);
+            let pnl = tracked.record_fill(
+                fill.side,
+                &fill.mint,
+                fill.token_amount,
+                fill.amount_lamports,
+            );
+            let mut fields = vec![
+                ("side", fill.side.to_string()),
+                ("wallet", fill.wallet.clone()),
+                ("name", name),
+                ("symbol", symbol),
+                ("mint", fill.mint.clone()),
+                ("mint_link", mint_link(&fill.mint)),
+                ("signature", fill.signature.clone()),
+                ("tx_link", tx_link(&fill.signature)),
+                (
+                    "amount_sol",
+                    format!("{}", fill.amount_lamports as f64 / 1_000_000_000.0),
+                ),
+                ("tokens", fill.token_amount.to_string()),
+            ];
+            if fill.side == OrderSide::Sell {
+                let pnl = match pnl {
+                    Some(pnl_lamports) => format!("{:+}", pnl_lamports as f64 / 1_000_000_000.0),
+                    None => "unknown".to_string(),
+                };
+                fields.push(("pnl", pnl));
+                // Fully sold, the name is only kept for tokens still held
+                if !tracked.holdings.contains_key(&fill.mint) {
+                    tracked.token_names.remove(&fill.mint);
+                }
+            }
+            Some((NotifyKind::Fill, fields))
+        }
+        SniperEvent::OrderFailed(failure) => {
+            let (name, symbol) = tracked.name(&failure.mint);
+            Some((
+                NotifyKind::Failure,
+                vec![
+                    ("side", failure.side.to_string()),
+                    ("name", name),
+                    ("symbol", symbol),
+                    ("mint", failure.mint.clone()),
+                    ("mint_link", mint_link(&failure.mint)),
+                    ("reason", failure.reason.clone()),
+                ],
+            ))
+        }
+        SniperEvent::OrderVetoed(veto) => {
+            let (name, symbol) = tracked.name(&veto.mint);
+            Some((
+                NotifyKind::Failure,
+                vec![
+                    ("side", veto.side.to_string()),
+                    ("name", name),
+                    ("symbol", symbol),
+                    ("mint", veto.mint.clone()),
+                    ("mint_link", mint_link(&veto.mint)),
+                    ("reason", format!("vetoed: {}", veto.reason)),
+                ],
+            ))
+        }
+        SniperEvent::StreamHealth(health) => match &health.status {
+            StreamStatus::Disconnected { reason } => Some((
+                NotifyKind::Health,
+                vec![
+                    ("stream", health.stream.to_string()),
+                    ("status", format!("disconnected ({})", reason)),
+                ],
+            )),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn mint_link(mint: &str) -> String {
+    format!("https://pump.fun/coin/{}", mint)
+}
+
+fn tx_link(signature: &str) -> String {
+    format!("https://solscan.io/tx/{}", signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use tokio::sync::broadcast;
+
+    use super::*;
+    use crate::notify::sinks::Sink;
+    use crate::notify::template::{
+        DEFAULT_FAILURE_TEMPLATE, DEFAULT_FILL_TEMPLATE, DEFAULT_HEALTH_TEMPLATE,
+        DEFAULT_SIGNAL_TEMPLATE,
+    };
+    use crate::pipeline::{OrderFailed, OrderFilled, Signal};
+    use crate::types::{BondingCurve, TokenInfo};
+    use crate::utils::stub_server::{StubResponse, StubServer};
+
+    fn signal(mint: &str) -> SniperEvent {
+        SniperEvent::Signal(Signal {
+            token: TokenInfo::new(
+                mint.to_string(),
+                "curve".to_string(),
+                "Pepe".to_string(),
+                "PEPE".to_string(),
+                "ipfs://doc".to_string(),
+                "creator".to_string(),
+                1,
+            ),
+            curve: BondingCurve {
+                virtual_token_reserves: 1_073_000_000_000_000,
+                virtual_sol_reserves: 30_000_000_000,
+                real_token_reserves: 793_100_000_000_000,
+                real_sol_reserves: 0,
+                token_total_supply: 1_000_000_000_000_000,
+                complete: false,
+                creator: [0; 32],
+            },
+            market_cap_sol: 28.0,
+            market_cap_usd: 5_600.0,
+            min_market_cap_sol: 25.0,
+            slot: 2,
+            emitted_at: Instant::now(),
+        })
+    }
+
+    fn fill(side: OrderSide, mint: &str, token_amount: u64, amount_lamports: u64) -> SniperEvent {
+        SniperEvent::OrderFilled(OrderFilled {
+            side,
+            mint: mint.to_string(),
+            wallet: "wallet".to_string(),
+            signature: "sig".to_string(),
+            amount_lamports,
+            token_amount,
+            units_consumed: None,
+        })
+    }
+
+    fn field(fields: &[(&'static str, String)], key: &str) -> Option<String> {
+        fields
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.clone())
+    }
+
+    #[test]
+    fn sells_carry_pnl_against_the_recorded_buy() {
+        let mut tracked = Tracked::default();
+        notification_fields(&signal("mint"), &mut tracked);
+
+        let (_, buy) = notification_fields(
+            &fill(OrderSide::Buy, "mint", 1_000, 1_000_000_000),
+            &mut tracked,
+        )
+        .unwrap();
+        assert_eq!(field(&buy, "pnl"), None);
+
+        // Half the tokens for 0.8 SOL, cost 0.5 SOL
+        let (_, sell) = notification_fields(
+            &fill(OrderSide::Sell, "mint", 500, 800_000_000),
+            &mut tracked,
+        )
+        .unwrap();
+        assert_eq!(field(&sell, "pnl").as_deref(), Some("+0.3"));
+        assert_eq!(field(&sell, "name").as_deref(), Some("Pepe"));
+
+        // The rest for 0.2 SOL
+        let (_, sell) = notification_fields(
+            &fill(OrderSide::Sell, "mint", 500, 200_000_000),
+            &mut tracked,
+        )
+        .unwrap();
+        assert_eq!(field(&sell, "pnl").as_deref(), Some("-0.3"));
+        assert!(tracked.holdings.is_empty());
+        assert!(tracked.token_names.is_empty());
+
+        let (_, sell) =
+            notification_fields(&fill(OrderSide::Sell, "other", 10, 1_000), &mut tracked).unwrap();
+        assert_eq!(field(&sell, "pnl").as_deref(), Some("unknown"));
+    }
+
+    #[test]
+    fn batch_end_drops_names_of_tokens_not_held() {
+        let mut tracked = Tracked::default();
+        notification_fields(&signal("held"), &mut tracked);
+        notification_fields(&signal("skipped"), &mut tracked);
+        notification_fields(&fill(OrderSide::Buy, "held", 1_000, 1_000), &mut tracked);
+
+        let ended = SniperEvent::BatchEnded(vec!["held".to_string(), "skipped".to_string()]);
+        assert!(notification_fields(&ended, &mut tracked).is_none());
+        assert!(tracked.token_names.contains_key("held"));
+        assert!(!tracked.token_names.contains_key("skipped"));
+    }
+
+    #[tokio::test]
+    async fn routes_events_to_their_sinks_within_the_rate_limit() {
+        let server = StubServer::start(vec![
+            ("/hook", StubResponse::json("{}")),
+            (
+                "/botTOKEN/sendMessage",
+                StubResponse::json(r#"{"ok": true}"#),
+            ),
+            ("/discord", StubResponse::json("{}")),
+        ])
+        .await;
+        let config = NotifyConfig {
+            sinks: vec![
+                Sink::Webhook {
+                    url: format!("{}/hook", server.url),
+                },
+                Sink::Telegram {
+                    api_base: server.url.clone(),
+                    bot_token: "TOKEN".to_string(),
+                    chat_id: "42".to_string(),
+                },
+                Sink::Discord {
+                    url: format!("{}/discord", server.url),
+                },
+            ],
+            routes: HashMap::from([
+                (
+                    NotifyKind::Signal,
+                    vec!["webhook".to_string(), "telegram".to_string()],
+                ),
+                (NotifyKind::Fill, vec!["discord".to_string()]),
+            ]),
+            templates: HashMap::from([
+                (NotifyKind::Signal, DEFAULT_SIGNAL_TEMPLATE.to_string()),
+                (NotifyKind::Fill, DEFAULT_FILL_TEMPLATE.to_string()),
+                (NotifyKind::Failure, DEFAULT_FAILURE_TEMPLATE.to_string()),
+                (NotifyKind::Health, DEFAULT_HEALTH_TEMPLATE.to_string()),
+            ]),
+            rate_limit_per_min: 1,
+        };
+
+        let (events, receiver) = broadcast::channel(16);
+        let notifier = tokio::spawn(run_notifier(receiver, config));
+        events.send(signal("mint")).unwrap();
+        // Not routed anywhere
+        events
+            .send(SniperEvent::OrderFailed(OrderFailed {
+                side: OrderSide::Buy,
+                mint: "mint".to_string(),
+                reason: "boom".to_string(),
+            }))
+            .unwrap();
+        events
+            .send(fill(OrderSide::Buy, "mint", 1_000, 1_000))
+            .unwrap();
+        // Over discord's one message a minute
+        events
+            .send(fill(OrderSide::Sell, "mint", 1_000, 2_000))
+            .unwrap();
+        drop(events);
+        notifier.await.unwrap();
+
+        assert_eq!(server.hits("/hook"), 1);
+        assert_eq!(server.hits("/botTOKEN/sendMessage"), 1);
+        assert_eq!(server.hits("/discord"), 1);
+        assert_eq!(server.requests().len(), 3);
+
+        let discord = server
+            .requests()
+            .into_iter()
+            .find(|request| request.path == "/discord")
+            .unwrap();
+        assert!(discord.body.contains("Filled: Pepe (PEPE)"));
+    }
+}
@@ -0,0 +1,153 @@
+use serde_json::json;
+
+use crate::notify::config::NotifyKind;
+
+/// A destination notifications can be posted to.
+#[derive(Debug, Clone)]
+pub enum Sink {
+    /// Generic JSON webhook: `{"event": ..., "text": ..., "fields": {...}}`
+    Webhook {
+        url: String,
+    },
+    /// Telegram Bot API `sendMessage`; `api_base` is overridable for testing
+    Telegram {
+        api_base: String,
+        bot_token: String,
+        chat_id: String,
+    },
+    Discord {
+        url: String,
+    },
+}
+
+impl Sink {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sink::Webhook { .. } => "webhook",
+            Sink::Telegram { .. } => "telegram",
+            Sink::Discord { .. } => "discord",
+        }
+    }
+
+    pub async fn send(
+        &self,
+        client: &reqwest::Client,
+        kind: NotifyKind,
+        text: &str,
+        fields: &[(&str, String)],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let request = match self {
+            Sink::Webhook { url } => {
+                let fields: serde_json::Map<String, serde_json::Value> = fields
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), json!(value)))
+                    .collect();
+                client.post(url).json(&json!({
+                    "event": format!("{:?}", kind).to_lowercase(),
+                    "text": text,
+                    "fields": fields,
+                }))
+            }
+            Sink::Telegram {
+                api_base,
+                bot_token,
+                chat_id,
+            } => client
+                .post(format!("{}/bot{}/sendMessage", api_base, bot_token))
+                .json(&json!({
+                    "chat_id": chat_id,
+                    "text": text,
+                    "disable_web_page_preview": true,
+                })),
+            Sink::Discord { url } => client.post(url).json(&json!({ "content": text })),
+        };
+
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::utils::stub_server::{StubResponse, StubServer};
+
+    const FIELDS: &[(&str, String)] = &[];
+
+    async fn sent_body(sink: Sink, server: &StubServer, path: &str) -> Value {
+        sink.send(
+            &reqwest::Client::new(),
+            NotifyKind::Signal,
+            "hello",
+            &[("mint", "Mint111".to_string())],
+        )
+        .await
+        .unwrap();
+        let request = server
+            .requests()
+            .into_iter()
+            .find(|request| request.path == path)
+            .expect("request on the sink path");
+        serde_json::from_str(&request.body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn posts_webhook_payload() {
+        let server = StubServer::start(vec![("/hook", StubResponse::json("{}"))]).await;
+        let sink = Sink::Webhook {
+            url: format!("{}/hook", server.url),
+        };
+
+        let body = sent_body(sink, &server, "/hook").await;
+        assert_eq!(
+            body,
+            json!({ "event": "signal", "text": "hello", "fields": { "mint": "Mint111" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_telegram_message_under_the_api_base() {
+        let server = StubServer::start(vec![(
+            "/botTOKEN/sendMessage",
+            StubResponse::json(r#"{"ok": true}"#),
+        )])
+        .await;
+        let sink = Sink::Telegram {
+            api_base: server.url.clone(),
+            bot_token: "TOKEN".to_string(),
+            chat_id: "42".to_string(),
+        };
+
+        let body = sent_body(sink, &server, "/botTOKEN/sendMessage").await;
+        assert_eq!(
+            body,
+            json!({ "chat_id": "42", "text": "hello", "disable_web_page_preview": true })
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_discord_content() {
+        let server = StubServer::start(vec![("/discord", StubResponse::json("{}"))]).await;
+        let sink = Sink::Discord {
+            url: format!("{}/discord", server.url),
+        };
+
+        let body = sent_body(sink, &server, "/discord").await;
+        assert_eq!(body, json!({ "content": "hello" }));
+    }
+
+    #[tokio::test]
+    async fn reports_rejected_posts() {
+        let server = StubServer::start(vec![]).await;
+        let sink = Sink::Discord {
+            url: format!("{}/discord", server.url),
+        };
+
+        let sent = sink
+            .send(&reqwest::Client::new(), NotifyKind::Fill, "hello", FIELDS)
+            .await;
+        assert!(sent.is_err());
+    }
+}
@@ -0,0 +1,16 @@
+pub const DEFAULT_SIGNAL_TEMPLATE: &str = "✅ ELIGIBLE: {name} ({symbol})\nMarket cap: {market_cap_sol} SOL (${market_cap_usd})\n{mint_link}";
+pub const DEFAULT_FILL_TEMPLATE: &str =
+    "🎯 Filled: {name} ({symbol})\n{amount_sol} SOL for ~{tokens} tokens\n{tx_link}";
+pub const DEFAULT_FAILURE_TEMPLATE: &str =
+    "❌ Order failed: {name} ({symbol})\n{reason}\n{mint_link}";
+pub const DEFAULT_HEALTH_TEMPLATE: &str = "⚠️ {stream} stream: {status}";
+
+/// Replaces every `{key}` in `template` with its value. Unknown placeholders
+/// are left as-is so a typo shows up in the message instead of vanishing.
+pub fn render(template: &str, fields: &[(&str, String)]) -> String {
+    let mut rendered = template.to_string();
+    for (key, value) in fields {
+        rendered = rendered.replace(&format!("{{{}}}", key), value);
+    }
+    rendered
+}
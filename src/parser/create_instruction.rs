@@ -1,6 +1,6 @@
 pub fn parse_create_instruction(
     data: &[u8],
-) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<(String, String, String), Box<dyn std::error::Error + Send + Sync>> {
     if data.len() < 8 {
         return Err("Data too short".into());
     }
@@ -36,6 +36,22 @@ pub fn parse_create_instruction(
         return Err("Symbol data out of bounds".into());
     }
     let symbol = String::from_utf8_lossy(&data[offset..offset + symbol_len]).to_string();
+    offset += symbol_len;
 
-    Ok((name, symbol))
+    if offset + 4 > data.len() {
+        return Err("Cannot read uri length".into());
+    }
+    let uri_len = u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ]) as usize;
+    offset += 4;
+    if offset + uri_len > data.len() {
+        return Err("Uri data out of bounds".into());
+    }
+    let uri = String::from_utf8_lossy(&data[offset..offset + uri_len]).to_string();
+
+    Ok((name, symbol, uri))
 }
@@ -0,0 +1,28 @@
+use tokio::sync::broadcast;
+
+use crate::pipeline::events::SniperEvent;
+
+/// Fan-out of every pipeline event to any number of observers (reporter,
+/// notifiers, recorders). Stages hand work to each other over bounded mpsc
+/// channels; the bus only mirrors what happened so observers can be added
+/// without touching the stages.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<SniperEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: SniperEvent) {
+        // No subscribers is not an error, the event is just dropped
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SniperEvent> {
+        self.sender.subscribe()
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::utils::config::Config;
+
+/// Trade parameters that can be changed while running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeSettings {
+    pub min_market_cap_usd: f64,
+    pub buy_amount_lamports: u64,
+    pub slippage_bps: u64,
+}
+
+/// Runtime switches shared by the strategy, the executor and the control
+/// API. Starts from `Config` and is only changed through the API.
+pub struct RuntimeControls {
+    paused: AtomicBool,
+    settings: RwLock<TradeSettings>,
+    blocked_mints: RwLock<HashSet<String>>,
+    blocked_creators: RwLock<HashSet<String>>,
+}
+
+impl RuntimeControls {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            paused: AtomicBool::new(false),
+            settings: RwLock::new(TradeSettings {
+                min_market_cap_usd: config.min_market_cap_usd,
+                buy_amount_lamports: config.buy_amount_lamports,
+                slippage_bps: config.slippage_bps,
+            }),
+            blocked_mints: RwLock::new(HashSet::new()),
+            blocked_creators: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub async fn settings(&self) -> TradeSettings {
+        self.settings.read().await.clone()
+    }
+
+    pub async fn update_settings(&self, update: impl FnOnce(&mut TradeSettings)) -> TradeSettings {
+        let mut settings = self.settings.write().await;
+        update(&mut settings);
+        settings.clone()
+    }
+
+    /// True if either the mint or its creator is blocklisted.
+    pub async fn is_blocked(&self, mint: &str, creator: &str) -> bool {
+        self.blocked_mints.read().await.contains(mint)
+            || self.blocked_creators.read().await.contains(creator)
+    }
+
+    pub async fn block_mint(&self, mint: String) {
+        self.blocked_mints.write().await.insert(mint);
+    }
+
+    pub async fn block_creator(&self, creator: String) {
+        self.blocked_creators.write().await.insert(creator);
+    }
+
+    pub async fn unblock_mint(&self, mint: &str) -> bool {
+        self.blocked_mints.write().await.remove(mint)
+    }
+
+    pub async fn unblock_creator(&self, creator: &str) -> bool {
+        self.blocked_creators.write().await.remove(creator)
+    }
+
+    pub async fn blocklists(&self) -> (Vec<String>, Vec<String>) {
+        (
+            self.blocked_mints.read().await.iter().cloned().collect(),
+            self.blocked_creators.read().await.iter().cloned().collect(),
+        )
+    }
+}
@@ -0,0 +1,170 @@
+use crate::pipeline::controls::TradeSettings;
+use crate::types::{BondingCurve, TokenInfo};
+
+/// A bonding curve account update for a token in the monitored batch.
+#[derive(Debug, Clone)]
+pub struct CurveUpdate {
+    pub token: TokenInfo,
+    pub curve: BondingCurve,
+    pub slot: u64,
+    pub elapsed_secs: u64,
+}
+
+/// What the tracker hands the strategy over the stage channel. A batch's
+/// end follows its last curve update, so the strategy can drop the batch's
+/// state without missing or replaying anything.
+// Nearly every message is a curve update, so boxing it would only add an
+// allocation per update
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum TrackerMessage {
+    CurveUpdate(CurveUpdate),
+    BatchEnded(Vec<String>),
+}
+
+/// A token that passed the strategy's eligibility checks.
+#[derive(Debug, Clone)]
+pub struct Signal {
+    pub token: TokenInfo,
+    pub curve: BondingCurve,
+    pub market_cap_sol: f64,
+    pub market_cap_usd: f64,
+    /// Threshold in effect when the signal fired
+    pub min_market_cap_sol: f64,
+    /// Slot of the curve update that triggered the signal
+    pub slot: u64,
+    pub emitted_at: std::time::Instant,
+}
+
+/// Why the strategy skipped a token that reached the market cap threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectKind {
+    Paused,
+    Blocklisted,
+    Safety,
+    Content,
+    Velocity,
+    Holders,
+    Bundled,
+}
+
+impl std::fmt::Display for RejectKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RejectKind::Paused => write!(f, "paused"),
+            RejectKind::Blocklisted => write!(f, "blocklisted"),
+            RejectKind::Safety => write!(f, "safety"),
+            RejectKind::Content => write!(f, "content"),
+            RejectKind::Velocity => write!(f, "velocity"),
+            RejectKind::Holders => write!(f, "holders"),
+            RejectKind::Bundled => write!(f, "bundled"),
+        }
+    }
+}
+
+/// A token that reached the threshold but was skipped by the strategy.
+/// Pause skips are published once per pause, the others are final.
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    pub token: TokenInfo,
+    pub curve: BondingCurve,
+    pub kind: RejectKind,
+    pub violations: Vec<String>,
+    pub market_cap_sol: f64,
+    pub market_cap_usd: f64,
+    pub min_market_cap_sol: f64,
+    /// Slot of the curve update that was rejected
+    pub slot: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl std::fmt::Display for OrderSide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderSide::Buy => write!(f, "buy"),
+            OrderSide::Sell => write!(f, "sell"),
+        }
+    }
+}
+
+/// A transaction that was built and signed for an order.
+#[derive(Debug, Clone)]
+pub struct OrderSubmitted {
+    pub side: OrderSide,
+    pub mint: String,
+    pub wallet: String,
+    pub signature: String,
+    pub token_account: String,
+    /// SOL spent on a buy, expected SOL out on a sell
+    pub amount_lamports: u64,
+    /// Tokens expected on a buy, tokens sold on a sell
+    pub token_amount: u64,
+}
+
+/// An order that completed. Transactions are only simulated for now, so a
+/// successful simulation counts as the fill.
+#[derive(Debug, Clone)]
+pub struct OrderFilled {
+    pub side: OrderSide,
+    pub mint: String,
+    pub wallet: String,
+    pub signature: String,
+    pub amount_lamports: u64,
+    pub token_amount: u64,
+    pub units_consumed: Option<u64>,
+}
+
+/// An order that could not be built or did not go through.
+#[derive(Debug, Clone)]
+pub struct OrderFailed {
+    pub side: OrderSide,
+    pub mint: String,
+    pub reason: String,
+}
+
+/// An order refused by the risk limits before anything was built.
+#[derive(Debug, Clone)]
+pub struct OrderVetoed {
+    pub side: OrderSide,
+    pub mint: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum StreamStatus {
+    Connected,
+    Disconnected { reason: String },
+    Reconnecting { attempt: u32, delay_ms: u64 },
+}
+
+/// Connection state change of a supervised Laserstream subscription.
+#[derive(Debug, Clone)]
+pub struct StreamHealth {
+    pub stream: &'static str,
+    pub status: StreamStatus,
+    pub last_slot: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum SniperEvent {
+    TokenCreated(TokenInfo),
+    /// A collection window closed and these tokens are now being monitored
+    BatchStarted(Vec<TokenInfo>),
+    /// Monitoring ended for these mints
+    BatchEnded(Vec<String>),
+    CurveUpdated(CurveUpdate),
+    Signal(Signal),
+    Rejected(Rejection),
+    OrderSubmitted(OrderSubmitted),
+    OrderFilled(OrderFilled),
+    OrderFailed(OrderFailed),
+    OrderVetoed(OrderVetoed),
+    StreamHealth(StreamHealth),
+    /// Trade settings were changed through the control API
+    SettingsUpdated(TradeSettings),
+}
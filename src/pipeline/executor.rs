@@ -0,0 +1,693 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address;
+use tokio::sync::mpsc;
+use tokio::task::{self, JoinError, JoinSet};
+use tracing::{Instrument, debug, info, warn};
+
+use crate::execute_ixs::blockhash::BlockhashCache;
+use crate::execute_ixs::template::BuyTemplates;
+use crate::execute_ixs::{buy, sell, simulate};
+use crate::metrics::METRICS;
+use crate::pipeline::controls::{RuntimeControls, TradeSettings};
+use crate::pipeline::{
+    EventBus, OrderFailed, OrderFilled, OrderSide, OrderSubmitted, OrderVetoed, Shutdown, Signal,
+    SniperEvent,
+};
+use crate::risk::RiskGuard;
+use crate::signer::SharedSigner;
+use crate::types::BondingCurve;
+use crate::utils::config::Config;
+use crate::wallet::WalletPool;
+use crate::wallet::funding::{self, Funding, FundingRules};
+use crate::wallet::pool::PooledWallet;
+
+/// Manual sell of `token_amount` tokens of a mint at the given curve state.
+#[derive(Debug, Clone)]
+pub struct SellOrder {
+    pub mint: String,
+    pub bonding_curve: String,
+    pub token_amount: u64,
+    pub curve: BondingCurve,
+}
+
+#[derive(Debug, Clone)]
+pub enum ExecutorCommand {
+    Buy(Signal),
+    Sell(SellOrder),
+}
+
+impl ExecutorCommand {
+    fn side(&self) -> OrderSide {
+        match self {
+            ExecutorCommand::Buy(_) => OrderSide::Buy,
+            ExecutorCommand::Sell(_) => OrderSide::Sell,
+        }
+    }
+
+    fn mint(&self) -> &str {
+        match self {
+            ExecutorCommand::Buy(signal) => &signal.token.mint,
+            ExecutorCommand::Sell(order) => &order.mint,
+        }
+    }
+
+    fn span(&self) -> tracing::Span {
+        match self {
+            ExecutorCommand::Buy(signal) => signal.token.span(),
+            ExecutorCommand::Sell(order) => tracing::info_span!("token", mint = %order.mint),
+        }
+    }
+}
+
+/// Shared handles the executor picks wallets and builds orders with.
+pub struct ExecutionContext {
+    pub wallets: Arc<WalletPool>,
+    pub rpc_client: Arc<RpcClient>,
+    pub templates: Arc<BuyTemplates>,
+    pub blockhash: Arc<BlockhashCache>,
+}
+
+/// An order being built, kept until its task finishes.
+struct PendingOrder {
+    wallet_index: usize,
+    side: OrderSide,
+    mint: String,
+    /// Lamports reserved against the risk and wallet limits, buys only
+    reserved_lamports: u64,
+}
+
+/// Builds (and simulates) a transaction for every command received, over the
+/// shared nonblocking RPC client so the stream stages never wait on RPC round
+/// trips. Commands are vetted one at a time, then each order is built in its
+/// own task so a slow build doesn't hold up the next signal. Approved buys
+/// are reserved against the limits while in flight. After shutdown queued
+/// commands are skipped and the orders in flight are allowed to finish before
+/// returning. Orders refused by the risk guard or that no wallet of the pool
+/// can take or fund are published as vetoed without building anything. Buys
+/// of tracked tokens fill in the token's prebuilt template instead of
+/// deriving accounts over RPC.
+pub async fn run_executor(
+    mut commands: mpsc::Receiver<ExecutorCommand>,
+    bus: EventBus,
+    config: Config,
+    controls: Arc<RuntimeControls>,
+    risk: Arc<RiskGuard>,
+    context: Arc<ExecutionContext>,
+    shutdown: Shutdown,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let wallets = &context.wallets;
+    let funding = FundingRules::from_config(&config);
+    let mut orders = JoinSet::new();
+    let mut pending: HashMap<task::Id, PendingOrder> = HashMap::new();
+
+    loop {
+        let command = tokio::select! {
+            command = commands.recv() => match command {
+                Some(command) => command,
+                None => break,
+            },
+            Some(finished) = orders.join_next_with_id() => {
+                finish_order(finished, &mut pending, &bus, &risk, wallets);
+                continue;
+            }
+        };
+
+        if shutdown.is_triggered() {
+            info!(mint = %command.mint(), "🛑 Skipping order, shutting down");
+            continue;
+        }
+
+        let mut settings = controls.settings().await;
+        let side = command.side();
+        let mint = command.mint().to_string();
+
+        let verdict = match side {
+            OrderSide::Buy => risk.check_buy(settings.buy_amount_lamports).and_then(|()| {
+                pick_buy_wallet(
+                    &mint,
+                    &mut settings,
+                    &funding,
+                    &risk,
+                    wallets,
+                    &context.templates,
+                )
+            }),
+            OrderSide::Sell => risk.check_sell().map(|()| wallets.select_for_sell(&mint)),
+        };
+        let wallet_index = match verdict {
+            Ok(index) => index,
+            Err(reason) => {
+                METRICS.transactions.with_label_values(&["vetoed"]).inc();
+                bus.publish(SniperEvent::OrderVetoed(OrderVetoed { side, mint, reason }));
+                continue;
+            }
+        };
+
+        let reserved_lamports = match side {
+            OrderSide::Buy => {
+                risk.reserve_buy(settings.buy_amount_lamports);
+                wallets.reserve_buy(wallet_index, settings.buy_amount_lamports);
+                settings.buy_amount_lamports
+            }
+            OrderSide::Sell => 0,
+        };
+
+        let signer = wallets.get(wallet_index).signer.clone();
+        let context = context.clone();
+        let span = command.span();
+        let handle = orders.spawn(
+            async move {
+                match command {
+                    ExecutorCommand::Buy(signal) => {
+                        execute_signal(signal, &settings, signer, &context).await
+                    }
+                    ExecutorCommand::Sell(order) => {
+                        execute_sell(order, &settings, signer, &context.rpc_client).await
+                    }
+                }
+            }
+            .instrument(span),
+        );
+        pending.insert(
+            handle.id(),
+            PendingOrder {
+                wallet_index,
+                side,
+                mint,
+                reserved_lamports,
+            },
+        );
+    }
+
+    while let Some(finished) = orders.join_next_with_id().await {
+        finish_order(finished, &mut pending, &bus, &risk, wallets);
+    }
+
+    Ok(())
+}
+
+/// Settles the reservation of a finished order and publishes its events.
+fn finish_order(
+    finished: Result<(task::Id, Vec<SniperEvent>), JoinError>,
+    pending: &mut HashMap<task::Id, PendingOrder>,
+    bus: &EventBus,
+    risk: &RiskGuard,
+    wallets: &WalletPool,
+) {
+    let id = match &finished {
+        Ok((id, _)) => *id,
+        Err(e) => e.id(),
+    };
+    let Some(order) = pending.remove(&id) else {
+        return;
+    };
+    let events = finished.map(|(_, events)| events).unwrap_or_else(|e| {
+        vec![SniperEvent::OrderFailed(OrderFailed {
+            side: order.side,
+            mint: order.mint.clone(),
+            reason: format!("executor task panicked: {}", e),
+        })]
+    });
+
+    if order.side == OrderSide::Buy {
+        // Only built buys count toward the window, failed ones are released
+        let built = events.iter().any(|event| {
+            matches!(event, SniperEvent::OrderSubmitted(submitted) if submitted.side == OrderSide::Buy)
+        });
+        risk.finish_buy(order.reserved_lamports, built);
+        wallets.finish_buy(
+            order.wallet_index,
+            &order.mint,
+            order.reserved_lamports,
+            built,
+        );
+    }
+
+    for event in events {
+        if let SniperEvent::OrderFilled(fill) = &event {
+            risk.record_fill(fill);
+            wallets.record_fill(order.wallet_index, fill);
+        }
+        bus.publish(event);
+    }
+}
+
+/// Tries the pool's wallets in routing order and takes the first one within
+/// its own limits that can fund the buy, with the amount it can afford.
+fn pick_buy_wallet(
+    mint: &str,
+    settings: &mut TradeSettings,
+    funding: &FundingRules,
+    risk: &RiskGuard,
+    wallets: &WalletPool,
+    templates: &BuyTemplates,
+) -> Result<usize, String> {
+    let mut refused = Vec::new();
+    for index in wallets.select_for_buy(mint) {
+        let pooled = wallets.get(index);
+        let mut funded = settings.clone();
+        let verdict = pooled
+            .limits
+            .check_buy(settings.buy_amount_lamports)
+            .and_then(|()| fund_buy(mint, &mut funded, funding, risk, pooled, templates));
+        match verdict {
+            Ok(()) => {
+                *settings = funded;
+                return Ok(index);
+            }
+            Err(reason) => refused.push(format!("{}: {}", pooled.wallet.owner(), reason)),
+        }
+    }
+    Err(format!(
+        "no wallet can take the buy ({})",
+        refused.join("; ")
+    ))
+}
+
+/// Applies the minimum-balance limits and the affordability check to a buy,
+/// shrinking its amount when the wallet only covers part of it.
+fn fund_buy(
+    mint: &str,
+    settings: &mut TradeSettings,
+    funding: &FundingRules,
+    risk: &RiskGuard,
+    pooled: &PooledWallet,
+    templates: &BuyTemplates,
+) -> Result<(), String> {
+    let wallet = &pooled.wallet;
+    let Some(balance) = wallet.lamports() else {
+        warn!(%mint, "⚠️ Wallet balance unknown, skipping funding checks");
+        return Ok(());
+    };
+
+    let owner = wallet.owner();
+    // Without a template assume SPL Token, only the rent estimate depends on it
+    let token_account = match templates.get(mint).and_then(|t| t.token_account(&owner)) {
+        Some(token_account) => token_account,
+        None => {
+            let mint = Pubkey::from_str(mint).map_err(|e| format!("invalid mint: {}", e))?;
+            get_associated_token_address(&owner, &mint)
+        }
+    };
+
+    match funding::plan_buy(
+        balance,
+        settings.buy_amount_lamports,
+        !wallet.has_token_account(&token_account),
+        funding.priority_fee_lamports,
+        funding.shrink,
+        funding.min_buy_lamports,
+    ) {
+        Funding::Full => {}
+        Funding::Shrunk { amount_lamports } => {
+            info!(
+                %mint,
+                requested_lamports = settings.buy_amount_lamports,
+                amount_lamports,
+                "👛 Shrinking buy to fit wallet balance"
+            );
+            settings.buy_amount_lamports = amount_lamports;
+        }
+        Funding::Insufficient { reason } => return Err(reason),
+    }
+
+    risk.check_balance(balance, settings.buy_amount_lamports)?;
+    pooled
+        .limits
+        .check_balance(balance, settings.buy_amount_lamports)
+}
+
+async fn execute_signal(
+    signal: Signal,
+    settings: &TradeSettings,
+    signer: SharedSigner,
+    context: &ExecutionContext,
+) -> Vec<SniperEvent> {
+    let token = &signal.token;
+    let rpc_client = context.rpc_client.as_ref();
+    let mut events = Vec::new();
+
+    info!("🔨 Building buy transaction...");
+
+    let buyer = signer.pubkey();
+
+    let buy_params = buy::BuyParams {
+        mint: token.mint.clone(),
+        bonding_curve: token.bonding_curve.clone(),
+        creator: bs58::encode(signal.curve.creator).into_string(),
+        amount_sol: settings.buy_amount_lamports as f64 / 1_000_000_000.0,
+        slippage_bps: settings.slippage_bps,
+        signer,
+    };
+
+    // A template built for another creator is stale, fall back to a full build
+    let template = context
+        .templates
+        .get(&token.mint)
+        .filter(|template| template.creator.to_bytes() == signal.curve.creator);
+    let built = match template {
+        Some(template) => match context.blockhash.get_or_fetch(rpc_client).await {
+            Ok(blockhash) => {
+                template
+                    .build(
+                        &buyer,
+                        &buy_params,
+                        signal.curve.virtual_sol_reserves,
+                        signal.curve.virtual_token_reserves,
+                        blockhash,
+                    )
+                    .await
+            }
+            Err(e) => Err(e),
+        },
+        None => {
+            debug!("No buy template, deriving accounts");
+            buy::build_buy_transaction(
+                buy_params,
+                rpc_client,
+                signal.curve.virtual_sol_reserves,
+                signal.curve.virtual_token_reserves,
+            )
+            .await
+        }
+    };
+
+    match built {
+        Ok(buy_tx) => {
+            METRICS.transactions.with_label_values(&["built"]).inc();
+            METRICS
+                .signal_to_signed_seconds
+                .observe(signal.emitted_at.elapsed().as_secs_f64());
+            info!(
+                estimated_tokens = buy_tx.estimated_tokens,
+                buyer_token_account = %buy_tx.buyer_token_account,
+                "✅ Buy transaction built"
+            );
+
+            let submitted = OrderSubmitted {
+                side: OrderSide::Buy,
+                mint: token.mint.clone(),
+                wallet: buyer.to_string(),
+                signature: buy_tx.transaction.signatures[0].to_string(),
+                token_account: buy_tx.buyer_token_account.clone(),
+                amount_lamports: settings.buy_amount_lamports,
+                token_amount: buy_tx.estimated_tokens,
+            };
+            simulate_order(&buy_tx.transaction, rpc_client, submitted, &mut events).await;
+        }
+        Err(e) => {
+            METRICS.transactions.with_label_values(&["failed"]).inc();
+            warn!(error = %e, "❌ Failed to build transaction");
+            events.push(SniperEvent::OrderFailed(OrderFailed {
+                side: OrderSide::Buy,
+                mint: token.mint.clone(),
+                reason: e.to_string(),
+            }));
+        }
+    }
+
+    events
+}
+
+async fn execute_sell(
+    order: SellOrder,
+    settings: &TradeSettings,
+    signer: SharedSigner,
+    rpc_client: &RpcClient,
+) -> Vec<SniperEvent> {
+    let mut events = Vec::new();
+
+    info!(
+        token_amount = order.token_amount,
+        "🔨 Building sell transaction..."
+    );
+
+    let wallet = signer.pubkey().to_string();
+
+    let sell_params = sell::SellParams {
+        mint: order.mint.clone(),
+        bonding_curve: order.bonding_curve.clone(),
+        creator: bs58::encode(order.curve.creator).into_string(),
+        token_amount: order.token_amount,
+        slippage_bps: settings.slippage_bps,
+        signer,
+    };
+
+    match sell::build_sell_transaction(
+        sell_params,
+        rpc_client,
+        order.curve.virtual_sol_reserves,
+        order.curve.virtual_token_reserves,
+    )
+    .await
+    {
+        Ok(sell_tx) => {
+            METRICS.transactions.with_label_values(&["built"]).inc();
+            let submitted = OrderSubmitted {
+                side: OrderSide::Sell,
+                mint: order.mint.clone(),
+                wallet,
+                signature: sell_tx.transaction.signatures[0].to_string(),
+                token_account: sell_tx.seller_token_account.clone(),
+                amount_lamports: sell_tx.estimated_sol_out,
+                token_amount: order.token_amount,
+            };
+            simulate_order(&sell_tx.transaction, rpc_client, submitted, &mut events).await;
+        }
+        Err(e) => {
+            METRICS.transactions.with_label_values(&["failed"]).inc();
+            warn!(error = %e, "❌ Failed to build sell transaction");
+            events.push(SniperEvent::OrderFailed(OrderFailed {
+                side: OrderSide::Sell,
+                mint: order.mint.clone(),
+                reason: e.to_string(),
+            }));
+        }
+    }
+
+    events
+}
+
+/// Publishes the submitted order, simulates it and records the outcome.
+async fn simulate_order(
+    transaction: &solana_sdk::transaction::Transaction,
+    rpc_client: &RpcClient,
+    submitted: OrderSubmitted,
+    events: &mut Vec<SniperEvent>,
+) {
+    events.push(SniperEvent::OrderSubmitted(submitted.clone()));
+
+    // Optionally simulate
+    match simulate::simulate_transaction(transaction, rpc_client).await {
+        Ok(units_consumed) => {
+            METRICS.transactions.with_label_values(&["simulated"]).inc();
+            events.push(SniperEvent::OrderFilled(OrderFilled {
+                side: submitted.side,
+                mint: submitted.mint,
+                wallet: submitted.wallet,
+                signature: submitted.signature,
+                amount_lamports: submitted.amount_lamports,
+                token_amount: submitted.token_amount,
+                units_consumed,
+            }));
+        }
+        Err(e) => {
+            METRICS.transactions.with_label_values(&["failed"]).inc();
+            warn!(error = %e, "⚠️ Simulation warning");
+            events.push(SniperEvent::OrderFailed(OrderFailed {
+                side: submitted.side,
+                mint: submitted.mint,
+                reason: e.to_string(),
+            }));
+        }
+    }
+
+    info!("💾 Transaction ready (not executed)");
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::signature::Keypair;
+
+    use super::*;
+    use crate::risk::RiskConfig;
+    use crate::wallet::Routing;
+
+    const SOL: u64 = 1_000_000_000;
+
+    fn pool(balances: &[u64]) -> WalletPool {
+        let signers: Vec<SharedSigner> = balances
+            .iter()
+            .map(|_| Arc::new(Keypair::new()) as SharedSigner)
+            .collect();
+        let pool = WalletPool::new(signers, Routing::RoundRobin, RiskConfig::default());
+        for (pooled, balance) in pool.iter().zip(balances) {
+            pooled.wallet.set_lamports(*balance, 1);
+        }
+        pool
+    }
+
+    fn templates() -> BuyTemplates {
+        BuyTemplates::new(
+            Vec::new(),
+            Arc::new(RpcClient::new("http://127.0.0.1:9".to_string())),
+        )
+    }
+
+    fn settings(buy_amount_lamports: u64) -> TradeSettings {
+        TradeSettings {
+            min_market_cap_usd: 0.0,
+            buy_amount_lamports,
+            slippage_bps: 500,
+        }
+    }
+
+    const NO_SHRINK: FundingRules = FundingRules {
+        priority_fee_lamports: 0,
+        shrink: false,
+        min_buy_lamports: 0,
+    };
+
+    #[test]
+    fn funds_the_full_amount_when_the_balance_covers_it() {
+        let wallets = pool(&[10 * SOL]);
+        let risk = RiskGuard::new(RiskConfig::default());
+        let mut settings = settings(SOL / 10);
+
+        let mint = Pubkey::new_unique().to_string();
+        let funded = fund_buy(
+            &mint,
+            &mut settings,
+            &NO_SHRINK,
+            &risk,
+            wallets.get(0),
+            &templates(),
+        );
+        assert_eq!(funded, Ok(()));
+        assert_eq!(settings.buy_amount_lamports, SOL / 10);
+    }
+
+    #[test]
+    fn shrinks_the_buy_to_the_balance() {
+        let balance = funding::buy_cost(0, true, 0) + 51_000_000;
+        let wallets = pool(&[balance]);
+        let risk = RiskGuard::new(RiskConfig::default());
+        let mut settings = settings(SOL / 10);
+        let rules = FundingRules {
+            shrink: true,
+            min_buy_lamports: SOL / 100,
+            ..NO_SHRINK
+        };
+
+        let mint = Pubkey::new_unique().to_string();
+        let funded = fund_buy(
+            &mint,
+            &mut settings,
+            &rules,
+            &risk,
+            wallets.get(0),
+            &templates(),
+        );
+        assert_eq!(funded, Ok(()));
+        assert_eq!(settings.buy_amount_lamports, SOL / 20);
+    }
+
+    #[test]
+    fn refuses_buys_the_balance_cannot_cover() {
+        let wallets = pool(&[SOL / 100]);
+        let risk = RiskGuard::new(RiskConfig::default());
+
+        let mint = Pubkey::new_unique().to_string();
+        let reason = fund_buy(
+            &mint,
+            &mut settings(SOL / 10),
+            &NO_SHRINK,
+            &risk,
+            wallets.get(0),
+            &templates(),
+        )
+        .unwrap_err();
+        assert!(reason.starts_with("insufficient funds"));
+    }
+
+    #[test]
+    fn refuses_buys_dropping_below_the_minimum_balance() {
+        let wallets = pool(&[SOL]);
+        let risk = RiskGuard::new(RiskConfig {
+            min_wallet_balance_lamports: Some(SOL * 95 / 100),
+            ..Default::default()
+        });
+
+        let mint = Pubkey::new_unique().to_string();
+        let funded = fund_buy(
+            &mint,
+            &mut settings(SOL / 10),
+            &NO_SHRINK,
+            &risk,
+            wallets.get(0),
+            &templates(),
+        );
+        assert!(funded.unwrap_err().contains("below minimum"));
+    }
+
+    #[test]
+    fn skips_funding_checks_while_the_balance_is_unknown() {
+        let signers: Vec<SharedSigner> = vec![Arc::new(Keypair::new())];
+        let wallets = WalletPool::new(signers, Routing::RoundRobin, RiskConfig::default());
+        let risk = RiskGuard::new(RiskConfig::default());
+
+        let mint = Pubkey::new_unique().to_string();
+        let funded = fund_buy(
+            &mint,
+            &mut settings(100 * SOL),
+            &NO_SHRINK,
+            &risk,
+            wallets.get(0),
+            &templates(),
+        );
+        assert_eq!(funded, Ok(()));
+    }
+
+    #[test]
+    fn falls_through_to_a_wallet_that_can_fund_the_buy() {
+        let wallets = pool(&[SOL / 100, 10 * SOL]);
+        let risk = RiskGuard::new(RiskConfig::default());
+        let mint = Pubkey::new_unique().to_string();
+
+        let mut settings = settings(SOL / 10);
+        let index = pick_buy_wallet(
+            &mint,
+            &mut settings,
+            &NO_SHRINK,
+            &risk,
+            &wallets,
+            &templates(),
+        );
+        assert_eq!(index, Ok(1));
+        assert_eq!(settings.buy_amount_lamports, SOL / 10);
+    }
+
+    #[test]
+    fn vetoes_when_no_wallet_can_fund_the_buy() {
+        let wallets = pool(&[SOL / 100, SOL / 100]);
+        let risk = RiskGuard::new(RiskConfig::default());
+        let mint = Pubkey::new_unique().to_string();
+
+        let reason = pick_buy_wallet(
+            &mint,
+            &mut settings(SOL / 10),
+            &NO_SHRINK,
+            &risk,
+            &wallets,
+            &templates(),
+        )
+        .unwrap_err();
+        assert!(reason.starts_with("no wallet can take the buy"));
+        assert_eq!(reason.matches("insufficient funds").count(), 2);
+    }
+}
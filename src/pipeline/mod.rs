@@ -0,0 +1,15 @@
+pub mod bus;
+pub mod controls;
+pub mod events;
+pub mod executor;
+pub mod reporter;
+pub mod shutdown;
+pub mod snapshot;
+pub mod strategy;
+pub mod velocity;
+pub mod verdict;
+
+pub use bus::*;
+pub use events::*;
+pub use shutdown::Shutdown;
+pub use verdict::Verdict;
@@ -0,0 +1,179 @@
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{info, warn};
+
+use crate::pipeline::{OrderSide, SniperEvent, StreamStatus};
+
+/// A filled order the sniper is holding.
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub mint: String,
+    pub signature: String,
+    pub amount_lamports: u64,
+    pub tokens: u64,
+}
+
+/// Totals collected over the lifetime of the process, printed on shutdown.
+#[derive(Debug, Default)]
+pub struct RunSummary {
+    pub tokens_tracked: usize,
+    pub curve_updates: usize,
+    pub signals: Vec<String>,
+    pub tokens_rejected: usize,
+    pub orders_submitted: usize,
+    pub orders_filled: usize,
+    pub orders_failed: usize,
+    pub orders_vetoed: usize,
+    pub positions: Vec<Position>,
+}
+
+impl RunSummary {
+    fn record(&mut self, event: &SniperEvent) {
+        match event {
+            SniperEvent::TokenCreated(_) => self.tokens_tracked += 1,
+            SniperEvent::CurveUpdated(_) => self.curve_updates += 1,
+            SniperEvent::Signal(signal) => self.signals.push(format!(
+                "{} ({}) {}",
+                signal.token.name, signal.token.symbol, signal.token.mint
+            )),
+            SniperEvent::OrderSubmitted(_) => self.orders_submitted += 1,
+            SniperEvent::OrderFilled(fill) => {
+                self.orders_filled += 1;
+                match fill.side {
+                    OrderSide::Buy => self.positions.push(Position {
+                        mint: fill.mint.clone(),
+                        signature: fill.signature.clone(),
+                        amount_lamports: fill.amount_lamports,
+                        tokens: fill.token_amount,
+                    }),
+                    OrderSide::Sell => {
+                        if let Some(position) =
+                            self.positions.iter_mut().find(|p| p.mint == fill.mint)
+                        {
+                            position.tokens = position.tokens.saturating_sub(fill.token_amount);
+                        }
+                        self.positions.retain(|p| p.tokens > 0);
+                    }
+                }
+            }
+            SniperEvent::OrderFailed(_) => self.orders_failed += 1,
+            SniperEvent::OrderVetoed(_) => self.orders_vetoed += 1,
+            SniperEvent::Rejected(_) => self.tokens_rejected += 1,
+            SniperEvent::BatchStarted(_)
+            | SniperEvent::BatchEnded(_)
+            | SniperEvent::StreamHealth(_)
+            | SniperEvent::SettingsUpdated(_) => {}
+        }
+    }
+
+    pub fn print(&self) {
+        info!(
+            tokens_tracked = self.tokens_tracked,
+            curve_updates = self.curve_updates,
+            signals = self.signals.len(),
+            tokens_rejected = self.tokens_rejected,
+            orders_submitted = self.orders_submitted,
+            orders_filled = self.orders_filled,
+            orders_failed = self.orders_failed,
+            orders_vetoed = self.orders_vetoed,
+            "📋 Session summary"
+        );
+        for signal in &self.signals {
+            info!("   Signal: {}", signal);
+        }
+        for position in &self.positions {
+            info!(
+                mint = %position.mint,
+                tokens = position.tokens,
+                amount_lamports = position.amount_lamports,
+                signature = %position.signature,
+                "   Open position"
+            );
+        }
+    }
+}
+
+/// Logs pipeline events. Returns the session summary once
+/// every publisher has gone away.
+pub async fn run_reporter(mut events: broadcast::Receiver<SniperEvent>) -> RunSummary {
+    let mut summary = RunSummary::default();
+
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                summary.record(&event);
+                report(&event);
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(skipped, "⚠️ Reporter lagged behind");
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+
+    summary
+}
+
+fn report(event: &SniperEvent) {
+    match event {
+        SniperEvent::TokenCreated(token) => token.span().in_scope(|| token.log_creation()),
+        SniperEvent::Signal(signal) => {
+            let token = &signal.token;
+            token.span().in_scope(|| {
+                info!(
+                    name = %token.name,
+                    creator = %token.creator,
+                    market_cap_sol = signal.market_cap_sol,
+                    market_cap_usd = signal.market_cap_usd,
+                    "✅ ELIGIBLE - Market Cap: {:.2} SOL (${:.0})",
+                    signal.market_cap_sol,
+                    signal.market_cap_usd
+                )
+            });
+        }
+        SniperEvent::OrderSubmitted(order) => {
+            info!(side = %order.side, mint = %order.mint, wallet = %order.wallet, signature = %order.signature, token_account = %order.token_account, "📤 Order built");
+        }
+        SniperEvent::OrderFilled(fill) => {
+            info!(
+                side = %fill.side,
+                mint = %fill.mint,
+                wallet = %fill.wallet,
+                signature = %fill.signature,
+                token_amount = fill.token_amount,
+                amount_lamports = fill.amount_lamports,
+                "🎯 Order filled"
+            );
+        }
+        SniperEvent::OrderFailed(failure) => {
+            warn!(side = %failure.side, mint = %failure.mint, reason = %failure.reason, "❌ Order failed");
+        }
+        SniperEvent::OrderVetoed(veto) => {
+            warn!(side = %veto.side, mint = %veto.mint, reason = %veto.reason, "🛡️ Order vetoed");
+        }
+        SniperEvent::StreamHealth(health) => match &health.status {
+            StreamStatus::Connected => {
+                info!(stream = health.stream, "🔌 Stream connected");
+            }
+            StreamStatus::Disconnected { reason } => {
+                warn!(
+                    stream = health.stream,
+                    last_slot = ?health.last_slot,
+                    %reason,
+                    "⚠️ Stream disconnected"
+                );
+            }
+            StreamStatus::Reconnecting { attempt, delay_ms } => {
+                info!(
+                    stream = health.stream,
+                    attempt, delay_ms, "🔁 Reconnecting stream"
+                );
+            }
+        },
+        // Already logged by the stage that produced them
+        SniperEvent::BatchStarted(_)
+        | SniperEvent::BatchEnded(_)
+        | SniperEvent::CurveUpdated(_)
+        | SniperEvent::Rejected(_)
+        | SniperEvent::SettingsUpdated(_) => {}
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+
+use crate::pipeline::{OrderSide, SniperEvent, StreamStatus};
+use crate::safety::MintSafety;
+use crate::types::TokenInfo;
+use crate::utils::valuation::{MarketCapMetric, Valuation};
+
+const MAX_RECENT_SIGNALS: usize = 10;
+
+/// Latest known state of a token under monitoring.
+pub struct MonitoredToken {
+    pub token: TokenInfo,
+    pub market_cap_sol: f64,
+    pub market_cap_usd: f64,
+    pub completion_percent: f64,
+    /// Fully diluted value at the spot price, over the mint's supply once
+    /// it has been inspected
+    pub fdv_sol: f64,
+    /// SOL still to be bought in before the curve completes
+    pub sol_to_graduate: f64,
+    pub elapsed_secs: u64,
+    /// Lamports per raw token unit at the last update
+    pub price_lamports: f64,
+}
+
+pub struct SignalView {
+    pub name: String,
+    pub symbol: String,
+    pub mint: String,
+    pub market_cap_sol: f64,
+    pub market_cap_usd: f64,
+}
+
+pub struct PositionView {
+    pub mint: String,
+    pub symbol: String,
+    pub amount_lamports: u64,
+    pub tokens: u64,
+    /// Value of the position at the last seen curve price, if still monitored
+    pub value_lamports: Option<f64>,
+}
+
+pub struct StreamView {
+    pub status: StreamStatus,
+    pub last_slot: Option<u64>,
+}
+
+/// Live view of the pipeline folded from bus events, shared by the dashboard
+/// and the control API.
+pub struct PipelineSnapshot {
+    /// Follows `min_market_cap_usd` as it is changed at runtime
+    pub min_market_cap_sol: f64,
+    pub market_cap_metric: MarketCapMetric,
+    pub sol_usd_price: f64,
+    pub collecting: Vec<TokenInfo>,
+    pub monitoring: Vec<MonitoredToken>,
+    pub signals: VecDeque<SignalView>,
+    pub positions: Vec<PositionView>,
+    pub streams: BTreeMap<&'static str, StreamView>,
+    safety: Arc<MintSafety>,
+}
+
+impl PipelineSnapshot {
+    pub fn new(
+        min_market_cap_sol: f64,
+        market_cap_metric: MarketCapMetric,
+        sol_usd_price: f64,
+        safety: Arc<MintSafety>,
+    ) -> Self {
+        Self {
+            min_market_cap_sol,
+            market_cap_metric,
+            sol_usd_price,
+            collecting: Vec::new(),
+            monitoring: Vec::new(),
+            signals: VecDeque::new(),
+            positions: Vec::new(),
+            streams: BTreeMap::new(),
+            safety,
+        }
+    }
+
+    pub fn apply(&mut self, event: &SniperEvent) {
+        match event {
+            SniperEvent::TokenCreated(token) => self.collecting.push(token.clone()),
+            SniperEvent::BatchStarted(batch) => {
+                self.collecting
+                    .retain(|token| !batch.iter().any(|started| started.mint == token.mint));
+                self.monitoring
+                    .extend(batch.iter().map(|token| MonitoredToken {
+                        token: token.clone(),
+                        market_cap_sol: 0.0,
+                        market_cap_usd: 0.0,
+                        completion_percent: 0.0,
+                        fdv_sol: 0.0,
+                        sol_to_graduate: 0.0,
+                        elapsed_secs: 0,
+                        price_lamports: 0.0,
+                    }));
+            }
+            SniperEvent::BatchEnded(mints) => {
+                self.monitoring
+                    .retain(|monitored| !mints.contains(&monitored.token.mint));
+            }
+            SniperEvent::CurveUpdated(update) => {
+                let market_cap = self
+                    .market_cap_metric
+                    .market_cap(&update.curve, self.sol_usd_price);
+                let price_lamports = update.curve.price_lamports();
+
+                if let Some(monitored) = self
+                    .monitoring
+                    .iter_mut()
+                    .find(|monitored| monitored.token.mint == update.token.mint)
+                {
+                    monitored.market_cap_sol = market_cap.0;
+                    monitored.market_cap_usd = market_cap.1;
+                    monitored.completion_percent = update.curve.completion_percent();
+                    monitored.fdv_sol = Valuation::of(
+                        &update.curve,
+                        self.safety.mint(&update.token.mint).as_ref(),
+                        self.sol_usd_price,
+                    )
+                    .fdv_sol;
+                    monitored.sol_to_graduate = update.curve.sol_to_graduate();
+                    monitored.elapsed_secs = update.elapsed_secs;
+                    monitored.price_lamports = price_lamports;
+                }
+
+                for position in self
+                    .positions
+                    .iter_mut()
+                    .filter(|position| position.mint == update.token.mint)
+                {
+                    position.value_lamports = Some(position.tokens as f64 * price_lamports);
+                }
+            }
+            SniperEvent::Signal(signal) => {
+                self.signals.push_front(SignalView {
+                    name: signal.token.name.clone(),
+                    symbol: signal.token.symbol.clone(),
+                    mint: signal.token.mint.clone(),
+                    market_cap_sol: signal.market_cap_sol,
+                    market_cap_usd: signal.market_cap_usd,
+                });
+                self.signals.truncate(MAX_RECENT_SIGNALS);
+            }
+            SniperEvent::OrderFilled(fill) if fill.side == OrderSide::Buy => {
+                // Another buy of a held mint adds to its position
+                if let Some(position) = self
+                    .positions
+                    .iter_mut()
+                    .find(|position| position.mint == fill.mint)
+                {
+                    let tokens = position.tokens + fill.token_amount;
+                    position.value_lamports = position
+                        .value_lamports
+                        .filter(|_| position.tokens > 0)
+                        .map(|value| value / position.tokens as f64 * tokens as f64);
+                    position.amount_lamports += fill.amount_lamports;
+                    position.tokens = tokens;
+                    return;
+                }
+                let symbol = self
+                    .signals
+                    .iter()
+                    .find(|signal| signal.mint == fill.mint)
+                    .map(|signal| signal.symbol.clone())
+                    .unwrap_or_default();
+                self.positions.push(PositionView {
+                    mint: fill.mint.clone(),
+                    symbol,
+                    amount_lamports: fill.amount_lamports,
+                    tokens: fill.token_amount,
+                    value_lamports: None,
+                });
+            }
+            SniperEvent::OrderFilled(fill) => {
+                if let Some(position) = self
+                    .positions
+                    .iter_mut()
+                    .find(|position| position.mint == fill.mint)
+                {
+                    position.tokens = position.tokens.saturating_sub(fill.token_amount);
+                }
+                self.positions.retain(|position| position.tokens > 0);
+            }
+            SniperEvent::StreamHealth(health) => {
+                self.streams.insert(
+                    health.stream,
+                    StreamView {
+                        status: health.status.clone(),
+                        last_slot: health.last_slot,
+                    },
+                );
+            }
+            SniperEvent::SettingsUpdated(settings) => {
+                self.min_market_cap_sol = settings.min_market_cap_usd / self.sol_usd_price;
+            }
+            SniperEvent::OrderSubmitted(_)
+            | SniperEvent::Rejected(_)
+            | SniperEvent::OrderFailed(_)
+            | SniperEvent::OrderVetoed(_) => {}
+        }
+    }
+
+    /// Progress of a market cap toward the eligibility threshold, in percent.
+    pub fn threshold_percent(&self, market_cap_sol: f64) -> f64 {
+        if self.min_market_cap_sol <= 0.0 {
+            return 100.0;
+        }
+        market_cap_sol / self.min_market_cap_sol * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::nonblocking::rpc_client::RpcClient;
+
+    use super::*;
+    use crate::pipeline::OrderFilled;
+    use crate::safety::SafetyConfig;
+
+    fn snapshot() -> PipelineSnapshot {
+        let safety = MintSafety::new(
+            SafetyConfig {
+                enabled: false,
+                require_report: false,
+                reject_mint_authority: false,
+                reject_freeze_authority: false,
+                max_transfer_fee_bps: 0,
+                reject_transfer_hook: false,
+                reject_permanent_delegate: false,
+                reject_mutable_metadata: false,
+                max_supply: None,
+            },
+            Arc::new(RpcClient::new("http://127.0.0.1:9".to_string())),
+        );
+        PipelineSnapshot::new(100.0, MarketCapMetric::Supply, 200.0, Arc::new(safety))
+    }
+
+    fn fill(side: OrderSide, token_amount: u64, amount_lamports: u64) -> SniperEvent {
+        SniperEvent::OrderFilled(OrderFilled {
+            side,
+            mint: "mint".to_string(),
+            wallet: "wallet".to_string(),
+            signature: "sig".to_string(),
+            amount_lamports,
+            token_amount,
+            units_consumed: None,
+        })
+    }
+
+    #[test]
+    fn repeated_buys_add_to_one_position() {
+        let mut snapshot = snapshot();
+        snapshot.apply(&fill(OrderSide::Buy, 1_000, 100));
+        snapshot.positions[0].value_lamports = Some(150.0);
+        snapshot.apply(&fill(OrderSide::Buy, 500, 60));
+
+        assert_eq!(snapshot.positions.len(), 1);
+        let position = &snapshot.positions[0];
+        assert_eq!(position.tokens, 1_500);
+        assert_eq!(position.amount_lamports, 160);
+        assert_eq!(position.value_lamports, Some(225.0));
+
+        snapshot.apply(&fill(OrderSide::Sell, 1_500, 300));
+        assert!(snapshot.positions.is_empty());
+    }
+}
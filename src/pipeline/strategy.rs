@@ -0,0 +1,356 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::bundles::BundleDetector;
+use crate::content::ContentFilter;
+use crate::holders::HolderTracker;
+use crate::metrics::METRICS;
+use crate::pipeline::controls::RuntimeControls;
+use crate::pipeline::executor::ExecutorCommand;
+use crate::pipeline::velocity::{CurveSeries, VelocityConfig};
+use crate::pipeline::{
+    EventBus, RejectKind, Rejection, Signal, SniperEvent, TrackerMessage, Verdict,
+};
+use crate::safety::MintSafety;
+use crate::utils::valuation::{MarketCapMetric, Valuation};
+
+// Curve series not updated for this long belong to tokens no longer tracked
+const STALE_SERIES: Duration = Duration::from_secs(300);
+
+/// Per-token checks the strategy applies on top of the market cap threshold.
+pub struct Gates {
+    pub safety: Arc<MintSafety>,
+    pub content: Arc<ContentFilter>,
+    pub holders: Arc<HolderTracker>,
+    pub bundles: Arc<BundleDetector>,
+    pub velocity: VelocityConfig,
+}
+
+/// Evaluates curve updates from the tracker and forwards eligible tokens to
+/// the executor. Thresholds, pause and blocklists are read from the runtime
+/// controls on every update; mints failing the safety checks, the content
+/// filters, the velocity rules, the holder load or the bundle limit are
+/// skipped, and concentrated ones wait for their holder distribution to
+/// improve. Every skip is published as a rejection with its reasons.
+/// Per-token state is dropped when the token's batch ends. Runs until the
+/// tracker side of the channel is closed.
+pub async fn run_strategy(
+    mut curve_updates: mpsc::Receiver<TrackerMessage>,
+    orders: mpsc::Sender<ExecutorCommand>,
+    bus: EventBus,
+    controls: Arc<RuntimeControls>,
+    gates: Gates,
+    market_cap_metric: MarketCapMetric,
+    coingecko_sol_usd_price: f64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Tokens that already produced a signal or were skipped for good
+    let mut found_tokens: HashSet<String> = HashSet::new();
+    // Tokens skipped during the current pause, published once
+    let mut paused_tokens: HashSet<String> = HashSet::new();
+    let mut series: HashMap<String, CurveSeries> = HashMap::new();
+    let mut last_prune = Instant::now();
+    let Gates {
+        safety,
+        content,
+        holders,
+        bundles,
+        velocity: velocity_rules,
+    } = gates;
+    let lookback = Duration::from_secs(velocity_rules.lookback_secs);
+
+    while let Some(message) = curve_updates.recv().await {
+        let update = match message {
+            TrackerMessage::CurveUpdate(update) => update,
+            TrackerMessage::BatchEnded(mints) => {
+                for mint in &mints {
+                    found_tokens.remove(mint);
+                    paused_tokens.remove(mint);
+                    series.remove(mint);
+                }
+                continue;
+            }
+        };
+        let token = &update.token;
+        if found_tokens.contains(&token.mint) {
+            continue;
+        }
+
+        let market_cap = market_cap_metric.market_cap(&update.curve, coingecko_sol_usd_price);
+
+        token.span().in_scope(|| {
+            info!(
+                elapsed_secs = update.elapsed_secs,
+                market_cap_sol = market_cap.0,
+                market_cap_usd = market_cap.1,
+                "📊 Update at {}s - Market Cap: {:.2} SOL (${:.2})",
+                update.elapsed_secs,
+                market_cap.0,
+                market_cap.1
+            )
+        });
+
+        let min_market_cap_sol =
+            controls.settings().await.min_market_cap_usd / coingecko_sol_usd_price;
+
+        if last_prune.elapsed() >= STALE_SERIES {
+            series.retain(|_, s| s.last_seen().is_some_and(|at| at.elapsed() < STALE_SERIES));
+            last_prune = Instant::now();
+        }
+        let velocity = series
+            .entry(token.mint.clone())
+            .or_insert_with(|| CurveSeries::new(token.created_at))
+            .push(&update.curve, market_cap.0, min_market_cap_sol, lookback);
+
+        let reject = |kind, violations| {
+            SniperEvent::Rejected(Rejection {
+                token: token.clone(),
+                curve: update.curve.clone(),
+                kind,
+                violations,
+                market_cap_sol: market_cap.0,
+                market_cap_usd: market_cap.1,
+                min_market_cap_sol,
+                slot: update.slot,
+            })
+        };
+
+        if !controls.is_paused() {
+            paused_tokens.clear();
+        }
+        if let Verdict::Reject(violations) = velocity_rules.check_deadline(&velocity) {
+            token.span().in_scope(|| {
+                info!(
+                    violations = %violations.join("; "),
+                    "🐢 Too slow to reach the threshold, skipping"
+                )
+            });
+            bus.publish(reject(RejectKind::Velocity, violations));
+            found_tokens.insert(token.mint.clone());
+            continue;
+        }
+        if market_cap.0 >= min_market_cap_sol {
+            if controls.is_paused() {
+                if paused_tokens.insert(token.mint.clone()) {
+                    bus.publish(reject(
+                        RejectKind::Paused,
+                        vec!["trading paused".to_string()],
+                    ));
+                }
+                continue;
+            }
+            if controls.is_blocked(&token.mint, &token.creator).await {
+                token
+                    .span()
+                    .in_scope(|| info!("🚫 Eligible but blocklisted, skipping"));
+                bus.publish(reject(
+                    RejectKind::Blocklisted,
+                    vec!["mint or creator blocklisted".to_string()],
+                ));
+                found_tokens.insert(token.mint.clone());
+                continue;
+            }
+            match safety.verdict(&token.mint) {
+                Verdict::Pass => {}
+                Verdict::Pending if !safety.require_report() => {}
+                // Checked again on the next update
+                Verdict::Pending => {
+                    token
+                        .span()
+                        .in_scope(|| debug!("Eligible but mint not inspected yet, waiting"));
+                    continue;
+                }
+                Verdict::Reject(violations) => {
+                    token.span().in_scope(|| {
+                        info!(
+                            violations = %violations.join("; "),
+                            "🧪 Eligible but mint failed safety checks, skipping"
+                        )
+                    });
+                    bus.publish(reject(RejectKind::Safety, violations));
+                    found_tokens.insert(token.mint.clone());
+                    continue;
+                }
+            }
+            match content.verdict(&token.mint) {
+                Verdict::Pass => {}
+                Verdict::Pending => {
+                    token
+                        .span()
+                        .in_scope(|| debug!("Eligible but content not screened yet, waiting"));
+                    continue;
+                }
+                Verdict::Reject(violations) => {
+                    token.span().in_scope(|| {
+                        info!(
+                            violations = %violations.join("; "),
+                            "📝 Eligible but filtered by content rules, skipping"
+                        )
+                    });
+                    bus.publish(reject(RejectKind::Content, violations));
+                    found_tokens.insert(token.mint.clone());
+                    continue;
+                }
+            }
+            match velocity_rules.check(&velocity, coingecko_sol_usd_price) {
+                Verdict::Pass => {}
+                // Checked again on the next update
+                Verdict::Pending => {
+                    token.span().in_scope(|| {
+                        debug!(
+                            inflow_sol_per_sec = ?velocity.inflow_sol_per_sec,
+                            slope_sol_per_sec = ?velocity.market_cap_slope_sol_per_sec,
+                            "Eligible but not moving fast enough, waiting"
+                        )
+                    });
+                    continue;
+                }
+                Verdict::Reject(violations) => {
+                    token.span().in_scope(|| {
+                        info!(
+                            violations = %violations.join("; "),
+                            "🐢 Eligible but failed velocity rules, skipping"
+                        )
+                    });
+                    bus.publish(reject(RejectKind::Velocity, violations));
+                    found_tokens.insert(token.mint.clone());
+                    continue;
+                }
+            }
+            match holders.verdict(&token.mint, update.curve.token_total_supply) {
+                Verdict::Pass => {}
+                // Checked again on the next update
+                Verdict::Pending => {
+                    let stats = holders.stats(&token.mint, update.curve.token_total_supply);
+                    token.span().in_scope(|| {
+                        debug!(
+                            ?stats,
+                            "Eligible but holder distribution not acceptable yet, waiting"
+                        )
+                    });
+                    continue;
+                }
+                Verdict::Reject(violations) => {
+                    token.span().in_scope(|| {
+                        info!(
+                            violations = %violations.join("; "),
+                            "👥 Eligible but holders could not be checked, skipping"
+                        )
+                    });
+                    bus.publish(reject(RejectKind::Holders, violations));
+                    found_tokens.insert(token.mint.clone());
+                    continue;
+                }
+            }
+            if let Some(stats) = holders.stats(&token.mint, update.curve.token_total_supply) {
+                token.span().in_scope(|| {
+                    info!(
+                        holder_count = stats.holder_count,
+                        top10_pct = stats.top10_pct,
+                        creator_pct = stats.creator_pct,
+                        "👥 Holder distribution"
+                    )
+                });
+            }
+            match bundles.verdict(&token.mint, update.curve.token_total_supply) {
+                Verdict::Pass => {}
+                // Checked again on the next update
+                Verdict::Pending => {
+                    token
+                        .span()
+                        .in_scope(|| debug!("Eligible but launch buys not clustered yet, waiting"));
+                    continue;
+                }
+                Verdict::Reject(violations) => {
+                    token.span().in_scope(|| {
+                        info!(
+                            violations = %violations.join("; "),
+                            "🎯 Eligible but launch was bundled, skipping"
+                        )
+                    });
+                    bus.publish(reject(RejectKind::Bundled, violations));
+                    found_tokens.insert(token.mint.clone());
+                    continue;
+                }
+            }
+            if let Some(stats) = bundles.stats(&token.mint, update.curve.token_total_supply) {
+                token.span().in_scope(|| {
+                    info!(
+                        buyers = stats.buyers,
+                        bundled_wallets = stats.bundled_wallets,
+                        clusters = stats.clusters,
+                        bundled_pct = stats.bundled_pct,
+                        largest_cluster_pct = stats.largest_cluster_pct,
+                        "🎯 Launch bundling"
+                    )
+                });
+            }
+            let valuation = Valuation::of(
+                &update.curve,
+                safety.mint(&token.mint).as_ref(),
+                coingecko_sol_usd_price,
+            );
+            token.span().in_scope(|| {
+                info!(
+                    secs_since_creation = velocity.secs_since_creation,
+                    threshold_reached_secs = ?velocity.threshold_reached_secs,
+                    inflow_sol_per_sec = ?velocity.inflow_sol_per_sec,
+                    slope_sol_per_sec = ?velocity.market_cap_slope_sol_per_sec,
+                    peak_market_cap_sol = velocity.peak_market_cap_sol,
+                    drawdown_pct = velocity.drawdown_pct,
+                    max_drawdown_pct = velocity.max_drawdown_pct,
+                    price_sol = valuation.price_sol,
+                    price_usd = valuation.price_usd,
+                    fdv_sol = valuation.fdv_sol,
+                    fdv_usd = valuation.fdv_usd,
+                    completion_percent = update.curve.completion_percent(),
+                    sol_to_graduate = update.curve.sol_to_graduate(),
+                    graduation_eta_secs = ?velocity.graduation_eta_secs,
+                    "🚀 Velocity, valuation and curve progress"
+                )
+            });
+            if let Some(copycat) = content.copycat(&token.mint) {
+                token.span().in_scope(|| {
+                    info!(
+                        is_first = copycat.is_first,
+                        clone_count = copycat.clone_count,
+                        original = ?copycat.original,
+                        "📝 Copycat standing"
+                    )
+                });
+            }
+
+            found_tokens.insert(token.mint.clone());
+            series.remove(&token.mint);
+
+            METRICS.signals.inc();
+            METRICS
+                .create_to_signal_seconds
+                .observe(token.created_at.elapsed().as_secs_f64());
+            METRICS
+                .create_to_signal_slots
+                .observe(update.slot.saturating_sub(token.created_slot) as f64);
+
+            let signal = Signal {
+                token: update.token.clone(),
+                curve: update.curve.clone(),
+                market_cap_sol: market_cap.0,
+                market_cap_usd: market_cap.1,
+                min_market_cap_sol,
+                slot: update.slot,
+                emitted_at: std::time::Instant::now(),
+            };
+            bus.publish(SniperEvent::Signal(signal.clone()));
+
+            if orders.send(ExecutorCommand::Buy(signal)).await.is_err() {
+                warn!("⚠️ Executor stopped, dropping signals");
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,357 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::pipeline::Verdict;
+use crate::types::BondingCurve;
+use crate::utils::config::optional_var;
+use crate::utils::constants::LAMPORTS_PER_SOL;
+
+/// Rules on how fast a token got where it is. Unset rules are not applied.
+#[derive(Debug, Clone)]
+pub struct VelocityConfig {
+    /// Span the inflow and slope are measured over
+    pub lookback_secs: u64,
+    /// The market cap threshold must be reached this soon after creation
+    pub max_secs_to_threshold: Option<f64>,
+    /// Largest drop from a running peak allowed before the signal
+    pub max_drawdown_pct: Option<f64>,
+    pub min_inflow_sol_per_sec: Option<f64>,
+    pub min_slope_usd_per_sec: Option<f64>,
+    /// Wait until the curve would complete within this long at the current
+    /// inflow
+    pub max_graduation_eta_secs: Option<f64>,
+}
+
+impl VelocityConfig {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(VelocityConfig {
+            lookback_secs: optional_var("VELOCITY_LOOKBACK_SECS")?.unwrap_or(10),
+            max_secs_to_threshold: optional_var("VELOCITY_MAX_SECS_TO_THRESHOLD")?,
+            max_drawdown_pct: optional_var("VELOCITY_MAX_DRAWDOWN_PCT")?,
+            min_inflow_sol_per_sec: optional_var("VELOCITY_MIN_INFLOW_SOL_PER_SEC")?,
+            min_slope_usd_per_sec: optional_var("VELOCITY_MIN_SLOPE_USD_PER_SEC")?,
+            max_graduation_eta_secs: optional_var("VELOCITY_MAX_GRADUATION_ETA_SECS")?,
+        })
+    }
+
+    /// Rejects tokens still below the threshold past the time limit. Checked
+    /// on every update, since such a token would be too late once eligible.
+    pub fn check_deadline(&self, velocity: &Velocity) -> Verdict {
+        match self.max_secs_to_threshold {
+            Some(max_secs)
+                if velocity.threshold_reached_secs.is_none()
+                    && velocity.secs_since_creation > max_secs =>
+            {
+                Verdict::Reject(vec![format!(
+                    "threshold not reached within {:.1}s",
+                    max_secs
+                )])
+            }
+            _ => Verdict::Pass,
+        }
+    }
+
+    /// Rejects eligible tokens that broke the time and drawdown rules, and
+    /// holds back the ones not moving fast enough right now.
+    pub fn check(&self, velocity: &Velocity, sol_usd_price: f64) -> Verdict {
+        let mut violations = Vec::new();
+        if let Some(max_secs) = self.max_secs_to_threshold
+            && let Some(secs) = velocity.threshold_reached_secs
+            && secs > max_secs
+        {
+            violations.push(format!(
+                "threshold reached after {:.1}s, limit {:.1}s",
+                secs, max_secs
+            ));
+        }
+        if let Some(max_pct) = self.max_drawdown_pct
+            && velocity.max_drawdown_pct > max_pct
+        {
+            violations.push(format!(
+                "drawdown of {:.1}% above {:.1}%",
+                velocity.max_drawdown_pct, max_pct
+            ));
+        }
+        if !violations.is_empty() {
+            return Verdict::Reject(violations);
+        }
+
+        if let Some(min) = self.min_inflow_sol_per_sec
+            && velocity
+                .inflow_sol_per_sec
+                .is_none_or(|inflow| inflow < min)
+        {
+            return Verdict::Pending;
+        }
+        if let Some(min) = self.min_slope_usd_per_sec
+            && velocity
+                .market_cap_slope_sol_per_sec
+                .is_none_or(|slope| slope * sol_usd_price < min)
+        {
+            return Verdict::Pending;
+        }
+        if let Some(max) = self.max_graduation_eta_secs
+            && velocity.graduation_eta_secs.is_none_or(|eta| eta > max)
+        {
+            return Verdict::Pending;
+        }
+        Verdict::Pass
+    }
+}
+
+/// Metrics derived from a token's curve history at its latest update.
+#[derive(Debug, Clone)]
+pub struct Velocity {
+    pub secs_since_creation: f64,
+    /// SOL bought into the curve per second over the lookback; `None` until
+    /// there are two samples
+    pub inflow_sol_per_sec: Option<f64>,
+    pub market_cap_slope_sol_per_sec: Option<f64>,
+    pub peak_market_cap_sol: f64,
+    /// Current drop from the peak
+    pub drawdown_pct: f64,
+    /// Largest drop from a running peak so far
+    pub max_drawdown_pct: f64,
+    /// Seconds from creation to the first update at or above the threshold
+    pub threshold_reached_secs: Option<f64>,
+    /// Seconds until the curve completes at the current inflow
+    pub graduation_eta_secs: Option<f64>,
+}
+
+struct Sample {
+    at: Instant,
+    virtual_sol_reserves: u64,
+    market_cap_sol: f64,
+}
+
+/// Curve snapshots of one token, trimmed to the lookback.
+pub struct CurveSeries {
+    created_at: Instant,
+    samples: VecDeque<Sample>,
+    peak_market_cap_sol: f64,
+    max_drawdown_pct: f64,
+    threshold_reached_secs: Option<f64>,
+}
+
+impl CurveSeries {
+    pub fn new(created_at: Instant) -> Self {
+        Self {
+            created_at,
+            samples: VecDeque::new(),
+            peak_market_cap_sol: 0.0,
+            max_drawdown_pct: 0.0,
+            threshold_reached_secs: None,
+        }
+    }
+
+    /// When the latest snapshot was taken.
+    pub fn last_seen(&self) -> Option<Instant> {
+        self.samples.back().map(|sample| sample.at)
+    }
+
+    pub fn push(
+        &mut self,
+        curve: &BondingCurve,
+        market_cap_sol: f64,
+        min_market_cap_sol: f64,
+        lookback: Duration,
+    ) -> Velocity {
+        let now = Instant::now();
+        let secs_since_creation = now.duration_since(self.created_at).as_secs_f64();
+
+        self.peak_market_cap_sol = self.peak_market_cap_sol.max(market_cap_sol);
+        let drawdown_pct = if self.peak_market_cap_sol > 0.0 {
+            (self.peak_market_cap_sol - market_cap_sol) / self.peak_market_cap_sol * 100.0
+        } else {
+            0.0
+        };
+        self.max_drawdown_pct = self.max_drawdown_pct.max(drawdown_pct);
+        if self.threshold_reached_secs.is_none() && market_cap_sol >= min_market_cap_sol {
+            self.threshold_reached_secs = Some(secs_since_creation);
+        }
+
+        self.samples.push_back(Sample {
+            at: now,
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            market_cap_sol,
+        });
+        // Keep one sample at or before the lookback start to measure from
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].at) >= lookback {
+            self.samples.pop_front();
+        }
+
+        let (inflow_sol_per_sec, market_cap_slope_sol_per_sec) = match self.samples.front() {
+            Some(first) if self.samples.len() > 1 && now > first.at => {
+                let secs = now.duration_since(first.at).as_secs_f64();
+                let inflow = (curve.virtual_sol_reserves as f64
+                    - first.virtual_sol_reserves as f64)
+                    / LAMPORTS_PER_SOL;
+                (
+                    Some(inflow / secs),
+                    Some((market_cap_sol - first.market_cap_sol) / secs),
+                )
+            }
+            _ => (None, None),
+        };
+
+        Velocity {
+            secs_since_creation,
+            inflow_sol_per_sec,
+            market_cap_slope_sol_per_sec,
+            peak_market_cap_sol: self.peak_market_cap_sol,
+            drawdown_pct,
+            max_drawdown_pct: self.max_drawdown_pct,
+            threshold_reached_secs: self.threshold_reached_secs,
+            graduation_eta_secs: inflow_sol_per_sec
+                .and_then(|inflow| curve.graduation_eta_secs(inflow)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> VelocityConfig {
+        VelocityConfig {
+            lookback_secs: 10,
+            max_secs_to_threshold: None,
+            max_drawdown_pct: None,
+            min_inflow_sol_per_sec: None,
+            min_slope_usd_per_sec: None,
+            max_graduation_eta_secs: None,
+        }
+    }
+
+    fn velocity() -> Velocity {
+        Velocity {
+            secs_since_creation: 5.0,
+            inflow_sol_per_sec: Some(1.0),
+            market_cap_slope_sol_per_sec: Some(2.0),
+            peak_market_cap_sol: 50.0,
+            drawdown_pct: 0.0,
+            max_drawdown_pct: 0.0,
+            threshold_reached_secs: Some(5.0),
+            graduation_eta_secs: Some(60.0),
+        }
+    }
+
+    fn violations(verdict: Verdict) -> Vec<String> {
+        match verdict {
+            Verdict::Reject(violations) => violations,
+            other => panic!("unexpected verdict: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_tokens_past_the_time_limit() {
+        let rules = VelocityConfig {
+            max_secs_to_threshold: Some(10.0),
+            ..rules()
+        };
+
+        let late = Velocity {
+            threshold_reached_secs: Some(12.0),
+            ..velocity()
+        };
+        assert_eq!(violations(rules.check(&late, 200.0)).len(), 1);
+        assert!(matches!(rules.check(&velocity(), 200.0), Verdict::Pass));
+    }
+
+    #[test]
+    fn rejects_tokens_below_the_threshold_past_the_deadline() {
+        let rules = VelocityConfig {
+            max_secs_to_threshold: Some(10.0),
+            ..rules()
+        };
+        let below = |secs_since_creation| Velocity {
+            secs_since_creation,
+            threshold_reached_secs: None,
+            ..velocity()
+        };
+
+        assert!(matches!(rules.check_deadline(&below(8.0)), Verdict::Pass));
+        assert_eq!(
+            violations(rules.check_deadline(&below(11.0))),
+            ["threshold not reached within 10.0s"]
+        );
+        // Reaching the threshold in time lifts the deadline
+        let reached = Velocity {
+            secs_since_creation: 30.0,
+            ..velocity()
+        };
+        assert!(matches!(rules.check_deadline(&reached), Verdict::Pass));
+        // Without a limit there is no deadline
+        let unlimited = VelocityConfig {
+            max_secs_to_threshold: None,
+            ..rules
+        };
+        assert!(matches!(
+            unlimited.check_deadline(&below(60.0)),
+            Verdict::Pass
+        ));
+    }
+
+    #[test]
+    fn rejects_deep_drawdowns() {
+        let rules = VelocityConfig {
+            max_drawdown_pct: Some(20.0),
+            ..rules()
+        };
+        let drawdown = |max_drawdown_pct| Velocity {
+            max_drawdown_pct,
+            ..velocity()
+        };
+
+        assert!(matches!(rules.check(&drawdown(20.0), 200.0), Verdict::Pass));
+        assert_eq!(violations(rules.check(&drawdown(35.0), 200.0)).len(), 1);
+    }
+
+    #[test]
+    fn holds_back_slow_tokens() {
+        let inflow = VelocityConfig {
+            min_inflow_sol_per_sec: Some(0.5),
+            ..rules()
+        };
+        assert!(matches!(inflow.check(&velocity(), 200.0), Verdict::Pass));
+        let slow = Velocity {
+            inflow_sol_per_sec: Some(0.1),
+            ..velocity()
+        };
+        assert!(matches!(inflow.check(&slow, 200.0), Verdict::Pending));
+        // Not measured yet
+        let unmeasured = Velocity {
+            inflow_sol_per_sec: None,
+            ..velocity()
+        };
+        assert!(matches!(inflow.check(&unmeasured, 200.0), Verdict::Pending));
+
+        // 2 SOL/s at $200 is $400/s
+        let slope = VelocityConfig {
+            min_slope_usd_per_sec: Some(500.0),
+            ..rules()
+        };
+        assert!(matches!(slope.check(&velocity(), 200.0), Verdict::Pending));
+        assert!(matches!(slope.check(&velocity(), 300.0), Verdict::Pass));
+
+        let eta = VelocityConfig {
+            max_graduation_eta_secs: Some(30.0),
+            ..rules()
+        };
+        assert!(matches!(eta.check(&velocity(), 200.0), Verdict::Pending));
+    }
+
+    #[test]
+    fn rejects_before_holding_back() {
+        let rules = VelocityConfig {
+            max_drawdown_pct: Some(20.0),
+            min_inflow_sol_per_sec: Some(5.0),
+            ..rules()
+        };
+        let crashed = Velocity {
+            max_drawdown_pct: 50.0,
+            ..velocity()
+        };
+        assert!(matches!(rules.check(&crashed, 200.0), Verdict::Reject(_)));
+    }
+}
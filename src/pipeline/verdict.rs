@@ -0,0 +1,9 @@
+/// Outcome of a pre-buy gate on a mint, as seen by the strategy.
+#[derive(Debug, Clone)]
+pub enum Verdict {
+    /// Not checked yet, or the check could not complete
+    Pending,
+    Pass,
+    /// Every rule the token broke
+    Reject(Vec<String>),
+}
@@ -0,0 +1,46 @@
+use std::env;
+
+use crate::utils::config::optional_var;
+
+/// Spending limits. Every limit is optional; an unset limit is not enforced.
+#[derive(Debug, Clone, Default)]
+pub struct RiskConfig {
+    pub max_trade_lamports: Option<u64>,
+    /// Cost basis of all open positions together
+    pub max_exposure_lamports: Option<u64>,
+    pub max_buys_per_window: Option<usize>,
+    pub buy_window_secs: u64,
+    /// Realized loss per UTC day after which buying stops
+    pub daily_loss_limit_lamports: Option<u64>,
+    /// Balance that must remain in the wallet after a buy
+    pub min_wallet_balance_lamports: Option<u64>,
+    /// Every order is refused while this file exists
+    pub kill_switch_file: Option<String>,
+}
+
+impl RiskConfig {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(RiskConfig {
+            max_trade_lamports: optional_var("RISK_MAX_TRADE_LAMPORTS")?,
+            max_exposure_lamports: optional_var("RISK_MAX_EXPOSURE_LAMPORTS")?,
+            max_buys_per_window: optional_var("RISK_MAX_BUYS_PER_WINDOW")?,
+            buy_window_secs: optional_var("RISK_BUY_WINDOW_SECS")?.unwrap_or(3600),
+            daily_loss_limit_lamports: optional_var("RISK_DAILY_LOSS_LIMIT_LAMPORTS")?,
+            min_wallet_balance_lamports: optional_var("RISK_MIN_WALLET_BALANCE_LAMPORTS")?,
+            kill_switch_file: env::var("KILL_SWITCH_FILE")
+                .ok()
+                .filter(|path| !path.is_empty()),
+        })
+    }
+
+    /// Limits applied to each wallet of the pool on its own.
+    pub fn wallet_from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(RiskConfig {
+            max_exposure_lamports: optional_var("WALLET_MAX_EXPOSURE_LAMPORTS")?,
+            max_buys_per_window: optional_var("WALLET_MAX_BUYS_PER_WINDOW")?,
+            buy_window_secs: optional_var("WALLET_BUY_WINDOW_SECS")?.unwrap_or(3600),
+            min_wallet_balance_lamports: optional_var("WALLET_MIN_BALANCE_LAMPORTS")?,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::pipeline::{OrderFilled, OrderSide};
+use crate::risk::RiskConfig;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, Default)]
+struct OpenPosition {
+    tokens: u64,
+    cost_lamports: u64,
+}
+
+#[derive(Debug, Default)]
+struct RiskState {
+    positions: HashMap<String, OpenPosition>,
+    recent_buys: VecDeque<Instant>,
+    /// Buys approved and being built, counted as if they went through
+    pending_buys: usize,
+    pending_lamports: u64,
+    /// UTC day the realized PnL below belongs to
+    day: u64,
+    realized_pnl_lamports: i64,
+}
+
+impl RiskState {
+    fn exposure_lamports(&self) -> u64 {
+        self.positions.values().map(|p| p.cost_lamports).sum()
+    }
+
+    /// Exposure including the buys still being built.
+    fn committed_lamports(&self) -> u64 {
+        self.exposure_lamports()
+            .saturating_add(self.pending_lamports)
+    }
+
+    /// Resets the daily PnL when the UTC day changes.
+    fn roll_day(&mut self) {
+        let today = utc_day();
+        if today != self.day {
+            self.day = today;
+            self.realized_pnl_lamports = 0;
+        }
+    }
+}
+
+/// Current limits usage, as shown by the control API.
+#[derive(Debug, Serialize)]
+pub struct RiskStatus {
+    pub kill_switch: bool,
+    pub exposure_lamports: u64,
+    pub max_exposure_lamports: Option<u64>,
+    pub buys_in_window: usize,
+    pub max_buys_per_window: Option<usize>,
+    pub realized_pnl_today_lamports: i64,
+    pub daily_loss_limit_lamports: Option<u64>,
+}
+
+/// Checks every order against the configured limits before it is built.
+/// Open positions and realized PnL are tracked from fills.
+pub struct RiskGuard {
+    config: RiskConfig,
+    kill_switch: AtomicBool,
+    state: Mutex<RiskState>,
+}
+
+impl RiskGuard {
+    pub fn new(config: RiskConfig) -> Self {
+        Self {
+            config,
+            kill_switch: AtomicBool::new(false),
+            state: Mutex::new(RiskState {
+                day: utc_day(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn set_kill_switch(&self, engaged: bool) {
+        self.kill_switch.store(engaged, Ordering::Relaxed);
+    }
+
+    /// Engaged through the API or by the presence of the kill switch file.
+    pub fn kill_switch_engaged(&self) -> bool {
+        self.kill_switch.load(Ordering::Relaxed)
+            || self
+                .config
+                .kill_switch_file
+                .as_ref()
+                .is_some_and(|path| Path::new(path).exists())
+    }
+
+    /// Approves a buy of `amount_lamports` or returns why it was refused.
+    /// Nothing is counted until the buy is reserved with
+    /// [`Self::reserve_buy`].
+    pub fn check_buy(&self, amount_lamports: u64) -> Result<(), String> {
+        if self.kill_switch_engaged() {
+            return Err("kill switch engaged".to_string());
+        }
+        if let Some(max) = self.config.max_trade_lamports
+            && amount_lamports > max
+        {
+            return Err(format!(
+                "trade of {} lamports exceeds max {} per trade",
+                amount_lamports, max
+            ));
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(max) = self.config.max_exposure_lamports {
+            let exposure = state.committed_lamports();
+            if exposure.saturating_add(amount_lamports) > max {
+                return Err(format!(
+                    "open exposure {} + {} lamports exceeds max {}",
+                    exposure, amount_lamports, max
+                ));
+            }
+        }
+
+        state.roll_day();
+        if let Some(limit) = self.config.daily_loss_limit_lamports
+            && state.realized_pnl_lamports <= -(limit as i64)
+        {
+            return Err(format!(
+                "daily loss of {} lamports reached limit {}",
+                -state.realized_pnl_lamports, limit
+            ));
+        }
+
+        let window = Duration::from_secs(self.config.buy_window_secs);
+        let now = Instant::now();
+        while state
+            .recent_buys
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= window)
+        {
+            state.recent_buys.pop_front();
+        }
+        let buys = state.recent_buys.len() + state.pending_buys;
+        if let Some(max) = self.config.max_buys_per_window
+            && buys >= max
+        {
+            return Err(format!(
+                "{} buys in the last {}s, max {}",
+                buys, self.config.buy_window_secs, max
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Holds a buy that passed every check against the limits while it is
+    /// built, so orders running at the same time cannot overshoot them.
+    pub fn reserve_buy(&self, amount_lamports: u64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.pending_buys += 1;
+        state.pending_lamports += amount_lamports;
+    }
+
+    /// Releases a reserved buy. A built buy counts toward the per-window
+    /// limit, one that failed to build is forgotten.
+    pub fn finish_buy(&self, amount_lamports: u64, built: bool) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.pending_buys = state.pending_buys.saturating_sub(1);
+        state.pending_lamports = state.pending_lamports.saturating_sub(amount_lamports);
+        if built {
+            state.recent_buys.push_back(Instant::now());
+        }
+    }
+
+    /// Sells only reduce exposure, so they are refused by the kill switch alone.
+    pub fn check_sell(&self) -> Result<(), String> {
+        if self.kill_switch_engaged() {
+            return Err("kill switch engaged".to_string());
+        }
+        Ok(())
+    }
+
+    /// Refuses a buy that would leave less than the minimum wallet balance.
+    pub fn check_balance(&self, balance_lamports: u64, amount_lamports: u64) -> Result<(), String> {
+        if let Some(min) = self.config.min_wallet_balance_lamports
+            && balance_lamports.saturating_sub(amount_lamports) < min
+        {
+            return Err(format!(
+                "balance {} - {} lamports would drop below minimum {}",
+                balance_lamports, amount_lamports, min
+            ));
+        }
+        Ok(())
+    }
+
+    /// Updates open positions and realized PnL from a fill.
+    pub fn record_fill(&self, fill: &OrderFilled) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.roll_day();
+
+        match fill.side {
+            OrderSide::Buy => {
+                let position = state.positions.entry(fill.mint.clone()).or_default();
+                position.tokens += fill.token_amount;
+                position.cost_lamports += fill.amount_lamports;
+            }
+            OrderSide::Sell => {
+                let Some(position) = state.positions.get_mut(&fill.mint) else {
+                    return;
+                };
+                let sold = fill.token_amount.min(position.tokens);
+                // Cost basis of the sold part, proportional to the tokens sold
+                let cost = if position.tokens == 0 {
+                    position.cost_lamports
+                } else {
+                    (position.cost_lamports as u128 * sold as u128 / position.tokens as u128) as u64
+                };
+                position.tokens -= sold;
+                position.cost_lamports -= cost;
+                if position.tokens == 0 {
+                    state.positions.remove(&fill.mint);
+                }
+                state.realized_pnl_lamports += fill.amount_lamports as i64 - cost as i64;
+            }
+        }
+    }
+
+    /// Cost basis of all open positions.
+    pub fn exposure_lamports(&self) -> u64 {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .exposure_lamports()
+    }
+
+    pub fn open_positions(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .positions
+            .len()
+    }
+
+    pub fn status(&self) -> RiskStatus {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.roll_day();
+        let window = Duration::from_secs(self.config.buy_window_secs);
+        let buys_in_window = state
+            .recent_buys
+            .iter()
+            .filter(|at| at.elapsed() < window)
+            .count();
+
+        RiskStatus {
+            kill_switch: self.kill_switch_engaged(),
+            exposure_lamports: state.exposure_lamports(),
+            max_exposure_lamports: self.config.max_exposure_lamports,
+            buys_in_window,
+            max_buys_per_window: self.config.max_buys_per_window,
+            realized_pnl_today_lamports: state.realized_pnl_lamports,
+            daily_loss_limit_lamports: self.config.daily_loss_limit_lamports,
+        }
+    }
+}
+
+fn utc_day() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / SECS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_recorded_buys_use_the_window() {
+        let guard = RiskGuard::new(RiskConfig {
+            max_buys_per_window: Some(1),
+            buy_window_secs: 3600,
+            ..Default::default()
+        });
+        // Checked but later vetoed, the budget is untouched
+        assert!(guard.check_buy(1_000).is_ok());
+        assert!(guard.check_buy(1_000).is_ok());
+
+        // Failed to build, forgotten once finished
+        guard.reserve_buy(1_000);
+        assert!(guard.check_buy(1_000).is_err());
+        guard.finish_buy(1_000, false);
+        assert!(guard.check_buy(1_000).is_ok());
+
+        guard.reserve_buy(1_000);
+        guard.finish_buy(1_000, true);
+        assert!(guard.check_buy(1_000).is_err());
+        assert_eq!(guard.status().buys_in_window, 1);
+    }
+
+    #[test]
+    fn pending_buys_count_toward_exposure() {
+        let guard = RiskGuard::new(RiskConfig {
+            max_exposure_lamports: Some(1_500),
+            ..Default::default()
+        });
+        guard.reserve_buy(1_000);
+        assert!(guard.check_buy(1_000).is_err());
+        guard.finish_buy(1_000, false);
+        assert!(guard.check_buy(1_000).is_ok());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod config;
+pub mod guard;
+
+pub use config::RiskConfig;
+pub use guard::RiskGuard;
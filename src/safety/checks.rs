@@ -0,0 +1,347 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use tracing::{debug, warn};
+
+use crate::execute_ixs::accounts;
+use crate::pipeline::Verdict;
+use crate::safety::SafetyConfig;
+use crate::safety::metadata::{self, Metadata};
+use crate::safety::mint::MintInfo;
+use crate::types::TokenInfo;
+
+// The mint was created moments ago, give a lagging RPC node a few tries
+const INSPECT_ATTEMPTS: u32 = 3;
+const INSPECT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// What was found on a mint and its metadata.
+#[derive(Debug, Clone)]
+pub struct SafetyReport {
+    pub token_program: Pubkey,
+    pub mint: MintInfo,
+    pub metadata: Option<Metadata>,
+}
+
+impl SafetyReport {
+    /// Whether the metadata can still be changed, from Metaplex or from the
+    /// Token-2022 metadata extension. `None` when the mint has neither.
+    pub fn metadata_mutable(&self) -> Option<bool> {
+        match (&self.metadata, self.mint.embedded_metadata_authority) {
+            (Some(metadata), _) => Some(metadata.is_mutable),
+            (None, Some(authority)) => Some(authority.is_some()),
+            (None, None) => None,
+        }
+    }
+
+    /// Every property the config rejects, empty when the mint is acceptable.
+    pub fn violations(&self, config: &SafetyConfig) -> Vec<String> {
+        let mut violations = Vec::new();
+        let mint = &self.mint;
+
+        if config.reject_mint_authority
+            && let Some(authority) = mint.mint_authority
+        {
+            violations.push(format!("mint authority {} can mint more", authority));
+        }
+        if config.reject_freeze_authority
+            && let Some(authority) = mint.freeze_authority
+        {
+            violations.push(format!("freeze authority {} can freeze holders", authority));
+        }
+        if let Some(max_supply) = config.max_supply
+            && mint.supply > max_supply
+        {
+            violations.push(format!("supply {} above {}", mint.supply, max_supply));
+        }
+        if let Some(fee_bps) = mint.transfer_fee_bps
+            && fee_bps > config.max_transfer_fee_bps
+        {
+            violations.push(format!("transfer fee {} bps", fee_bps));
+        }
+        if config.reject_transfer_hook
+            && let Some(program) = mint.transfer_hook
+        {
+            violations.push(format!("transfer hook {}", program));
+        }
+        if config.reject_permanent_delegate
+            && let Some(delegate) = mint.permanent_delegate
+        {
+            violations.push(format!("permanent delegate {}", delegate));
+        }
+        if config.reject_mutable_metadata && self.metadata_mutable() == Some(true) {
+            violations.push("metadata is mutable".to_string());
+        }
+
+        violations
+    }
+}
+
+/// Safety reports of the tracked tokens. The tracker starts an inspection
+/// when a token joins a batch, the strategy reads the verdict before buying.
+pub struct MintSafety {
+    config: SafetyConfig,
+    rpc_client: Arc<RpcClient>,
+    reports: RwLock<HashMap<String, SafetyReport>>,
+    /// Mints whose inspection gave up, so a required report never arrives
+    failed: RwLock<HashSet<String>>,
+}
+
+impl MintSafety {
+    pub fn new(config: SafetyConfig, rpc_client: Arc<RpcClient>) -> Self {
+        Self {
+            config,
+            rpc_client,
+            reports: RwLock::new(HashMap::new()),
+            failed: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn require_report(&self) -> bool {
+        self.config.require_report
+    }
+
+    /// The decoded mint, once inspected.
+    pub fn mint(&self, mint: &str) -> Option<MintInfo> {
+        self.reports
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+            .map(|report| report.mint.clone())
+    }
+
+    pub fn verdict(&self, mint: &str) -> Verdict {
+        if !self.config.enabled {
+            return Verdict::Pass;
+        }
+        match self
+            .reports
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+        {
+            Some(report) => {
+                let violations = report.violations(&self.config);
+                if violations.is_empty() {
+                    Verdict::Pass
+                } else {
+                    Verdict::Reject(violations)
+                }
+            }
+            None if self.config.require_report && self.inspection_failed(mint) => {
+                Verdict::Reject(vec!["inspection failed".to_string()])
+            }
+            None => Verdict::Pending,
+        }
+    }
+
+    fn inspection_failed(&self, mint: &str) -> bool {
+        self.failed
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(mint)
+    }
+
+    /// Fetches and decodes the mint and its metadata, retrying briefly.
+    pub async fn inspect(&self, token: &TokenInfo) {
+        if !self.config.enabled {
+            return;
+        }
+        let Ok(mint) = Pubkey::from_str(&token.mint) else {
+            return;
+        };
+
+        for attempt in 1..=INSPECT_ATTEMPTS {
+            match fetch_report(&self.rpc_client, &mint).await {
+                Ok(report) => {
+                    token.span().in_scope(|| {
+                        debug!(
+                            token_program = %report.token_program,
+                            mint_authority = ?report.mint.mint_authority,
+                            freeze_authority = ?report.mint.freeze_authority,
+                            supply = report.mint.supply,
+                            decimals = report.mint.decimals,
+                            update_authority = ?report.metadata.as_ref().map(|m| m.update_authority),
+                            transfer_fee_bps = ?report.mint.transfer_fee_bps,
+                            metadata_mutable = ?report.metadata_mutable(),
+                            "🧪 Mint inspected"
+                        )
+                    });
+                    self.reports
+                        .write()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(token.mint.clone(), report);
+                    return;
+                }
+                Err(e) if attempt < INSPECT_ATTEMPTS => {
+                    debug!(mint = %token.mint, attempt, error = %e, "Mint inspection failed, retrying");
+                    tokio::time::sleep(INSPECT_RETRY_DELAY).await;
+                }
+                Err(e) => {
+                    warn!(mint = %token.mint, error = %e, "⚠️ Mint inspection failed");
+                    self.failed
+                        .write()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(token.mint.clone());
+                }
+            }
+        }
+    }
+
+    /// Drops the reports of tokens that are no longer monitored.
+    pub fn remove(&self, mints: &[String]) {
+        let mut reports = self.reports.write().unwrap_or_else(|e| e.into_inner());
+        let mut failed = self.failed.write().unwrap_or_else(|e| e.into_inner());
+        for mint in mints {
+            reports.remove(mint);
+            failed.remove(mint);
+        }
+    }
+}
+
+/// One RPC round trip for the mint and its Metaplex metadata.
+async fn fetch_report(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+) -> Result<SafetyReport, Box<dyn std::error::Error + Send + Sync>> {
+    let metadata_address = metadata::metadata_address(mint);
+    let fetched = rpc_client
+        .get_multiple_accounts(&[*mint, metadata_address])
+        .await?;
+
+    let mint_account = fetched
+        .first()
+        .cloned()
+        .flatten()
+        .ok_or("mint account not found")?;
+    let token_program = accounts::token_program_of(mint, &mint_account.owner)?;
+    let mint_info = MintInfo::from_account_data(&mint_account.data)?;
+
+    let metadata = match fetched.get(1).cloned().flatten() {
+        Some(account) => match Metadata::from_account_data(&account.data) {
+            Ok(metadata) => Some(metadata),
+            Err(e) => {
+                warn!(%mint, error = %e, "⚠️ Unreadable metadata account");
+                None
+            }
+        },
+        None => None,
+    };
+
+    Ok(SafetyReport {
+        token_program,
+        mint: mint_info,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use base64::{Engine, engine::general_purpose::STANDARD};
+
+    use super::*;
+    use crate::safety::mint::tests::mint_data;
+    use crate::utils::stub_server::{StubResponse, StubServer};
+
+    const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    fn config(require_report: bool) -> SafetyConfig {
+        SafetyConfig {
+            enabled: true,
+            require_report,
+            reject_mint_authority: true,
+            reject_freeze_authority: true,
+            max_transfer_fee_bps: 0,
+            reject_transfer_hook: true,
+            reject_permanent_delegate: true,
+            reject_mutable_metadata: false,
+            max_supply: None,
+        }
+    }
+
+    fn token() -> TokenInfo {
+        TokenInfo::new(
+            Pubkey::new_unique().to_string(),
+            Pubkey::new_unique().to_string(),
+            "Pepe".to_string(),
+            "PEPE".to_string(),
+            "ipfs://doc".to_string(),
+            Pubkey::new_unique().to_string(),
+            1,
+        )
+    }
+
+    /// `getMultipleAccounts` answer with the mint and no metadata account.
+    fn mint_account(data: &[u8]) -> StubResponse {
+        StubResponse::json(format!(
+            r#"{{"jsonrpc": "2.0", "id": 1, "result": {{
+                "context": {{ "slot": 1 }},
+                "value": [{{
+                    "data": ["{}", "base64"],
+                    "executable": false,
+                    "lamports": 1461600,
+                    "owner": "{}",
+                    "rentEpoch": 0,
+                    "space": {}
+                }}, null]
+            }}}}"#,
+            STANDARD.encode(data),
+            TOKEN_PROGRAM,
+            data.len()
+        ))
+    }
+
+    async fn inspected(config: SafetyConfig, response: StubResponse) -> Verdict {
+        let server = StubServer::start(vec![("/", response)]).await;
+        let safety = MintSafety::new(config, Arc::new(RpcClient::new(server.url.clone())));
+        let token = token();
+        safety.inspect(&token).await;
+        safety.verdict(&token.mint)
+    }
+
+    #[tokio::test]
+    async fn passes_mints_without_authorities() {
+        let verdict = inspected(config(true), mint_account(&mint_data(None, None, 1_000))).await;
+        assert!(matches!(verdict, Verdict::Pass));
+    }
+
+    #[tokio::test]
+    async fn rejects_mints_with_authorities() {
+        let data = mint_data(
+            Some(Pubkey::new_unique()),
+            Some(Pubkey::new_unique()),
+            1_000,
+        );
+        match inspected(config(true), mint_account(&data)).await {
+            Verdict::Reject(violations) => assert_eq!(violations.len(), 2, "{:?}", violations),
+            other => panic!("unexpected verdict: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_required_reports_that_never_arrive() {
+        let verdict = inspected(
+            config(true),
+            StubResponse::status(StatusCode::INTERNAL_SERVER_ERROR),
+        )
+        .await;
+        match verdict {
+            Verdict::Reject(violations) => assert_eq!(violations, ["inspection failed"]),
+            other => panic!("unexpected verdict: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn leaves_optional_reports_pending() {
+        let verdict = inspected(
+            config(false),
+            StubResponse::status(StatusCode::INTERNAL_SERVER_ERROR),
+        )
+        .await;
+        assert!(matches!(verdict, Verdict::Pending));
+    }
+}
@@ -0,0 +1,41 @@
+use crate::utils::config::optional_var;
+
+/// Which mint properties veto a buy. Everything is read from env with
+/// conservative defaults: any authority or Token-2022 extension that lets
+/// someone mint, freeze, tax or move holders' tokens is rejected.
+#[derive(Debug, Clone)]
+pub struct SafetyConfig {
+    /// Inspect mints at all; when off no buy is vetoed for safety
+    pub enabled: bool,
+    /// Hold signals until the mint has been inspected, and reject mints
+    /// whose inspection failed
+    pub require_report: bool,
+    pub reject_mint_authority: bool,
+    pub reject_freeze_authority: bool,
+    /// Highest Token-2022 transfer fee accepted, 0 rejects any fee
+    pub max_transfer_fee_bps: u16,
+    pub reject_transfer_hook: bool,
+    pub reject_permanent_delegate: bool,
+    pub reject_mutable_metadata: bool,
+    /// Largest total supply accepted, in base units
+    pub max_supply: Option<u64>,
+}
+
+impl SafetyConfig {
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(SafetyConfig {
+            enabled: optional_var("SAFETY_CHECKS")?.unwrap_or(true),
+            require_report: optional_var("SAFETY_REQUIRE_REPORT")?.unwrap_or(true),
+            reject_mint_authority: optional_var("SAFETY_REJECT_MINT_AUTHORITY")?.unwrap_or(true),
+            reject_freeze_authority: optional_var("SAFETY_REJECT_FREEZE_AUTHORITY")?
+                .unwrap_or(true),
+            max_transfer_fee_bps: optional_var("SAFETY_MAX_TRANSFER_FEE_BPS")?.unwrap_or(0),
+            reject_transfer_hook: optional_var("SAFETY_REJECT_TRANSFER_HOOK")?.unwrap_or(true),
+            reject_permanent_delegate: optional_var("SAFETY_REJECT_PERMANENT_DELEGATE")?
+                .unwrap_or(true),
+            reject_mutable_metadata: optional_var("SAFETY_REJECT_MUTABLE_METADATA")?
+                .unwrap_or(false),
+            max_supply: optional_var("SAFETY_MAX_SUPPLY")?,
+        })
+    }
+}
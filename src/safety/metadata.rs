@@ -0,0 +1,128 @@
+use solana_sdk::pubkey::Pubkey;
+
+pub const METADATA_PROGRAM: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+// Account key of a Metaplex MetadataV1 account
+const KEY_METADATA_V1: u8 = 4;
+
+/// Metaplex metadata PDA of a mint
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    let program = Pubkey::from_str_const(METADATA_PROGRAM);
+    Pubkey::find_program_address(&[b"metadata", program.as_ref(), mint.as_ref()], &program).0
+}
+
+/// The parts of a Metaplex metadata account that matter for safety.
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub update_authority: Pubkey,
+    pub is_mutable: bool,
+}
+
+impl Metadata {
+    /// Layout: key (1), update authority (32), mint (32), name, symbol and
+    /// uri as borsh strings, seller fee bps (2), optional creators, primary
+    /// sale happened (1), is mutable (1)
+    pub fn from_account_data(
+        data: &[u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        if data.first() != Some(&KEY_METADATA_V1) || data.len() < 65 {
+            return Err("not a Metaplex metadata account".into());
+        }
+        let update_authority = Pubkey::try_from(&data[1..33])?;
+
+        // Skip name, symbol and uri, then the seller fee
+        let mut offset = 65;
+        for _ in 0..3 {
+            offset += 4 + read_u32(data, offset)? as usize;
+        }
+        offset += 2;
+
+        match data.get(offset) {
+            Some(0) => offset += 1,
+            Some(_) => {
+                let count = read_u32(data, offset + 1)? as usize;
+                // Creator: address (32), verified (1), share (1)
+                offset += 1 + 4 + count * 34;
+            }
+            None => return Err("metadata truncated before creators".into()),
+        }
+        let is_mutable = *data
+            .get(offset + 1)
+            .ok_or("metadata truncated before is_mutable")?
+            != 0;
+
+        Ok(Metadata {
+            update_authority,
+            is_mutable,
+        })
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = data.get(offset..offset + 4).ok_or("metadata truncated")?;
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// MetadataV1 account with `creators` creators.
+    fn metadata_data(update_authority: Pubkey, creators: Option<u32>, is_mutable: bool) -> Vec<u8> {
+        let mut data = vec![KEY_METADATA_V1];
+        data.extend_from_slice(update_authority.as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        for field in ["Pepe", "PEPE", "https://example.com/pepe.json"] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data.extend_from_slice(&500u16.to_le_bytes());
+        match creators {
+            Some(count) => {
+                data.push(1);
+                data.extend_from_slice(&count.to_le_bytes());
+                for _ in 0..count {
+                    data.extend_from_slice(Pubkey::new_unique().as_ref());
+                    data.extend_from_slice(&[1, 100]);
+                }
+            }
+            None => data.push(0),
+        }
+        // Primary sale happened, then is mutable
+        data.push(0);
+        data.push(is_mutable as u8);
+        data
+    }
+
+    #[test]
+    fn decodes_authority_and_mutability() {
+        let authority = Pubkey::new_unique();
+
+        let metadata = Metadata::from_account_data(&metadata_data(authority, None, true)).unwrap();
+        assert_eq!(metadata.update_authority, authority);
+        assert!(metadata.is_mutable);
+
+        let metadata = Metadata::from_account_data(&metadata_data(authority, None, false)).unwrap();
+        assert!(!metadata.is_mutable);
+    }
+
+    #[test]
+    fn skips_creators() {
+        for count in [0, 1, 3] {
+            let data = metadata_data(Pubkey::new_unique(), Some(count), true);
+            assert!(Metadata::from_account_data(&data).unwrap().is_mutable);
+            let data = metadata_data(Pubkey::new_unique(), Some(count), false);
+            assert!(!Metadata::from_account_data(&data).unwrap().is_mutable);
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_and_foreign_accounts() {
+        let data = metadata_data(Pubkey::new_unique(), Some(2), false);
+        assert!(Metadata::from_account_data(&data[..data.len() - 1]).is_err());
+        assert!(Metadata::from_account_data(&data[..80]).is_err());
+
+        let mut foreign = data.clone();
+        foreign[0] = 0;
+        assert!(Metadata::from_account_data(&foreign).is_err());
+    }
+}
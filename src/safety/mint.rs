@@ -0,0 +1,246 @@
+use solana_sdk::pubkey::Pubkey;
+
+// SPL Token mint layout: COption<Pubkey> mint authority (4 + 32), supply (8),
+// decimals (1), is_initialized (1), COption<Pubkey> freeze authority (4 + 32)
+const MINT_LEN: usize = 82;
+// Token-2022 pads the base mint to the size of a token account, then stores
+// the account type followed by the TLV extensions
+const EXTENSIONS_START: usize = 165 + 1;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+// Token-2022 extension type ids
+const EXT_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXT_PERMANENT_DELEGATE: u16 = 12;
+const EXT_TRANSFER_HOOK: u16 = 14;
+const EXT_TOKEN_METADATA: u16 = 19;
+
+/// The parts of a mint account that matter for safety.
+#[derive(Debug, Clone, Default)]
+pub struct MintInfo {
+    pub mint_authority: Option<Pubkey>,
+    pub freeze_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+    /// Highest of the current and scheduled transfer fee
+    pub transfer_fee_bps: Option<u16>,
+    /// Program invoked on every transfer
+    pub transfer_hook: Option<Pubkey>,
+    /// Account allowed to move or burn anyone's tokens
+    pub permanent_delegate: Option<Pubkey>,
+    /// Update authority of Token-2022 embedded metadata; `Some(None)` when
+    /// the extension is present but the metadata is frozen
+    pub embedded_metadata_authority: Option<Option<Pubkey>>,
+}
+
+impl MintInfo {
+    /// Decodes an SPL Token or Token-2022 mint, including the extensions
+    /// that can hurt a holder.
+    pub fn from_account_data(
+        data: &[u8],
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        if data.len() < MINT_LEN {
+            return Err("mint account too short".into());
+        }
+        if data[45] == 0 {
+            return Err("mint is not initialized".into());
+        }
+
+        let mut info = MintInfo {
+            mint_authority: read_coption_pubkey(&data[0..36]),
+            supply: u64::from_le_bytes(data[36..44].try_into()?),
+            decimals: data[44],
+            freeze_authority: read_coption_pubkey(&data[46..82]),
+            ..Default::default()
+        };
+
+        if data.len() > EXTENSIONS_START && data[EXTENSIONS_START - 1] == ACCOUNT_TYPE_MINT {
+            info.read_extensions(&data[EXTENSIONS_START..])?;
+        }
+        Ok(info)
+    }
+
+    fn read_extensions(
+        &mut self,
+        mut tlv: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        while tlv.len() >= 4 {
+            let extension = u16::from_le_bytes([tlv[0], tlv[1]]);
+            let len = u16::from_le_bytes([tlv[2], tlv[3]]) as usize;
+            // Type 0 marks the unused tail of the account
+            if extension == 0 {
+                break;
+            }
+            let value = tlv.get(4..4 + len).ok_or("mint extension out of bounds")?;
+
+            match extension {
+                EXT_TRANSFER_FEE_CONFIG if value.len() >= 108 => {
+                    // Two authorities (64) and withheld amount (8), then the
+                    // older and newer fees: epoch (8), maximum fee (8), bps (2)
+                    let older = u16::from_le_bytes([value[88], value[89]]);
+                    let newer = u16::from_le_bytes([value[106], value[107]]);
+                    self.transfer_fee_bps = Some(older.max(newer));
+                }
+                EXT_PERMANENT_DELEGATE if value.len() >= 32 => {
+                    self.permanent_delegate = read_optional_pubkey(&value[0..32]);
+                }
+                EXT_TRANSFER_HOOK if value.len() >= 64 => {
+                    // Authority (32) then the hook program id
+                    self.transfer_hook = read_optional_pubkey(&value[32..64]);
+                }
+                EXT_TOKEN_METADATA if value.len() >= 32 => {
+                    self.embedded_metadata_authority = Some(read_optional_pubkey(&value[0..32]));
+                }
+                _ => {}
+            }
+            tlv = &tlv[4 + len..];
+        }
+        Ok(())
+    }
+}
+
+/// `COption<Pubkey>`: u32 tag, then the key
+fn read_coption_pubkey(bytes: &[u8]) -> Option<Pubkey> {
+    if bytes[0..4] == [0, 0, 0, 0] {
+        return None;
+    }
+    Pubkey::try_from(&bytes[4..36]).ok()
+}
+
+/// Token-2022 `OptionalNonZeroPubkey`: all zeroes means none
+fn read_optional_pubkey(bytes: &[u8]) -> Option<Pubkey> {
+    if bytes.iter().all(|b| *b == 0) {
+        return None;
+    }
+    Pubkey::try_from(bytes).ok()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Base SPL mint layout with the given authorities.
+    pub(crate) fn mint_data(
+        mint_authority: Option<Pubkey>,
+        freeze_authority: Option<Pubkey>,
+        supply: u64,
+    ) -> Vec<u8> {
+        let mut data = Vec::with_capacity(MINT_LEN);
+        push_coption(&mut data, mint_authority);
+        data.extend_from_slice(&supply.to_le_bytes());
+        data.push(6);
+        data.push(1);
+        push_coption(&mut data, freeze_authority);
+        data
+    }
+
+    fn push_coption(data: &mut Vec<u8>, key: Option<Pubkey>) {
+        match key {
+            Some(key) => {
+                data.extend_from_slice(&[1, 0, 0, 0]);
+                data.extend_from_slice(key.as_ref());
+            }
+            None => data.extend_from_slice(&[0; 36]),
+        }
+    }
+
+    /// Token-2022 mint carrying the given `(type, value)` extensions.
+    fn token_2022_mint(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = mint_data(None, None, 1_000);
+        data.resize(EXTENSIONS_START - 1, 0);
+        data.push(ACCOUNT_TYPE_MINT);
+        for (extension, value) in extensions {
+            data.extend_from_slice(&extension.to_le_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data
+    }
+
+    fn transfer_fee_config(older_bps: u16, newer_bps: u16) -> Vec<u8> {
+        let mut value = vec![0; 108];
+        value[88..90].copy_from_slice(&older_bps.to_le_bytes());
+        value[106..108].copy_from_slice(&newer_bps.to_le_bytes());
+        value
+    }
+
+    #[test]
+    fn decodes_spl_authorities() {
+        let mint_authority = Pubkey::new_unique();
+        let freeze_authority = Pubkey::new_unique();
+
+        let info = MintInfo::from_account_data(&mint_data(
+            Some(mint_authority),
+            Some(freeze_authority),
+            42,
+        ))
+        .unwrap();
+        assert_eq!(info.mint_authority, Some(mint_authority));
+        assert_eq!(info.freeze_authority, Some(freeze_authority));
+        assert_eq!(info.supply, 42);
+        assert_eq!(info.decimals, 6);
+        assert_eq!(info.transfer_fee_bps, None);
+
+        let info = MintInfo::from_account_data(&mint_data(None, None, 42)).unwrap();
+        assert_eq!(info.mint_authority, None);
+        assert_eq!(info.freeze_authority, None);
+    }
+
+    #[test]
+    fn rejects_short_and_uninitialized_mints() {
+        let data = mint_data(None, None, 1);
+        assert!(MintInfo::from_account_data(&data[..MINT_LEN - 1]).is_err());
+
+        let mut uninitialized = data.clone();
+        uninitialized[45] = 0;
+        assert!(MintInfo::from_account_data(&uninitialized).is_err());
+    }
+
+    #[test]
+    fn decodes_token_2022_extensions() {
+        let delegate = Pubkey::new_unique();
+        let hook_program = Pubkey::new_unique();
+        let metadata_authority = Pubkey::new_unique();
+        let mut hook = vec![0; 32];
+        hook.extend_from_slice(hook_program.as_ref());
+
+        let info = MintInfo::from_account_data(&token_2022_mint(&[
+            (EXT_TRANSFER_FEE_CONFIG, transfer_fee_config(50, 300)),
+            (EXT_PERMANENT_DELEGATE, delegate.to_bytes().to_vec()),
+            (EXT_TRANSFER_HOOK, hook),
+            (EXT_TOKEN_METADATA, metadata_authority.to_bytes().to_vec()),
+        ]))
+        .unwrap();
+        assert_eq!(info.transfer_fee_bps, Some(300));
+        assert_eq!(info.permanent_delegate, Some(delegate));
+        assert_eq!(info.transfer_hook, Some(hook_program));
+        assert_eq!(
+            info.embedded_metadata_authority,
+            Some(Some(metadata_authority))
+        );
+    }
+
+    #[test]
+    fn reads_zeroed_extension_keys_as_none() {
+        let info = MintInfo::from_account_data(&token_2022_mint(&[
+            (EXT_PERMANENT_DELEGATE, vec![0; 32]),
+            (EXT_TRANSFER_HOOK, vec![0; 64]),
+            (EXT_TOKEN_METADATA, vec![0; 32]),
+            // Padding ends the extensions
+            (0, vec![0; 8]),
+            (EXT_TRANSFER_FEE_CONFIG, transfer_fee_config(100, 100)),
+        ]))
+        .unwrap();
+        assert_eq!(info.permanent_delegate, None);
+        assert_eq!(info.transfer_hook, None);
+        // Present but frozen
+        assert_eq!(info.embedded_metadata_authority, Some(None));
+        assert_eq!(info.transfer_fee_bps, None);
+    }
+
+    #[test]
+    fn rejects_extensions_running_past_the_account() {
+        let mut data = token_2022_mint(&[(EXT_PERMANENT_DELEGATE, vec![0; 32])]);
+        data.truncate(data.len() - 1);
+        assert!(MintInfo::from_account_data(&data).is_err());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod checks;
+pub mod config;
+pub mod metadata;
+pub mod mint;
+
+pub use checks::MintSafety;
+pub use config::SafetyConfig;
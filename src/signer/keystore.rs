@@ -0,0 +1,242 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, rand_core::RngCore},
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use crate::signer::read_keypair_file;
+
+const KEYSTORE_VERSION: u32 = 1;
+// scrypt cost: 2^15 iterations, r = 8, p = 1 (~32 MiB, a fraction of a second)
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+const SALT_LEN: usize = 16;
+
+/// Keypair encrypted with ChaCha20-Poly1305 under a scrypt-derived key.
+#[derive(Serialize, Deserialize)]
+struct Keystore {
+    version: u32,
+    pubkey: String,
+    kdf: KdfParams,
+    /// Base64
+    salt: String,
+    /// Base64
+    nonce: String,
+    /// Base64 of the encrypted 64-byte keypair
+    ciphertext: String,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct KdfParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+pub fn password_from_env() -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    if let Ok(path) = env::var("KEYSTORE_PASSWORD_FILE") {
+        let password = fs::read_to_string(&path)
+            .map_err(|e| format!("failed to read KEYSTORE_PASSWORD_FILE {}: {}", path, e))?;
+        return Ok(password.trim_end_matches(['\r', '\n']).to_string());
+    }
+    env::var("KEYSTORE_PASSWORD")
+        .map_err(|_| "KEYSTORE_PASSWORD or KEYSTORE_PASSWORD_FILE must be set for keystores".into())
+}
+
+/// Decrypts the keypair in the keystore at `path`.
+pub fn read_keystore(
+    path: &str,
+    password: &str,
+) -> Result<Keypair, Box<dyn std::error::Error + Send + Sync>> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("failed to read keystore {}: {}", path, e))?;
+    let keystore: Keystore = serde_json::from_str(&contents)
+        .map_err(|e| format!("keystore {} is malformed: {}", path, e))?;
+    if keystore.version != KEYSTORE_VERSION {
+        return Err(format!(
+            "keystore {} has unsupported version {}",
+            path, keystore.version
+        )
+        .into());
+    }
+
+    let salt = STANDARD.decode(&keystore.salt)?;
+    let nonce: [u8; 12] = STANDARD
+        .decode(&keystore.nonce)?
+        .try_into()
+        .map_err(|_| format!("keystore {} has a malformed nonce", path))?;
+    let ciphertext = STANDARD.decode(&keystore.ciphertext)?;
+
+    let key = derive_key(password, &salt, &keystore.kdf)?;
+    let secret = ChaCha20Poly1305::new(&Key::from(key))
+        .decrypt(&Nonce::from(nonce), ciphertext.as_ref())
+        .map_err(|_| format!("wrong password or corrupted keystore {}", path))?;
+
+    let keypair = Keypair::try_from(secret.as_slice())
+        .map_err(|e| format!("keystore {} holds an invalid keypair: {}", path, e))?;
+    if keypair.pubkey().to_string() != keystore.pubkey {
+        return Err(format!("keystore {} pubkey does not match its key", path).into());
+    }
+    Ok(keypair)
+}
+
+/// `keystore` command: encrypts a JSON keypair file into a keystore using
+/// the password from the environment.
+pub fn run(args: &[String]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let [keypair_path, output_path] = args else {
+        return Err("usage: pump-sniper-grpc keystore <keypair.json> <keystore.json>".into());
+    };
+    let password = password_from_env()?;
+    let kdf = KdfParams {
+        log_n: SCRYPT_LOG_N,
+        r: SCRYPT_R,
+        p: SCRYPT_P,
+    };
+    let pubkey = write_keystore(keypair_path, output_path, &password, kdf)?;
+    println!("Wrote keystore for {} to {}", pubkey, output_path);
+    Ok(())
+}
+
+/// Encrypts the keypair file at `keypair_path` into a new keystore at
+/// `output_path` and returns its pubkey. Never overwrites an existing file.
+fn write_keystore(
+    keypair_path: &str,
+    output_path: &str,
+    password: &str,
+    kdf: KdfParams,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    if password.is_empty() {
+        return Err("keystore password must not be empty".into());
+    }
+    // Before the key derivation, which takes a noticeable moment
+    if fs::exists(output_path)? {
+        return Err(format!("{} already exists", output_path).into());
+    }
+
+    let keypair = read_keypair_file(keypair_path)?;
+    let keystore = encrypt(&keypair, password, kdf)?;
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(output_path)
+        .and_then(|mut file| file.write_all(serde_json::to_string_pretty(&keystore)?.as_bytes()))
+        .map_err(|e| format!("failed to write keystore {}: {}", output_path, e))?;
+    Ok(keystore.pubkey)
+}
+
+fn encrypt(
+    keypair: &Keypair,
+    password: &str,
+    kdf: KdfParams,
+) -> Result<Keystore, Box<dyn std::error::Error + Send + Sync>> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(password, &salt, &kdf)?;
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = ChaCha20Poly1305::new(&Key::from(key))
+        .encrypt(&nonce, keypair.to_bytes().as_ref())
+        .map_err(|_| "encryption failed")?;
+
+    Ok(Keystore {
+        version: KEYSTORE_VERSION,
+        pubkey: keypair.pubkey().to_string(),
+        kdf,
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+fn derive_key(
+    password: &str,
+    salt: &[u8],
+    kdf: &KdfParams,
+) -> Result<[u8; 32], Box<dyn std::error::Error + Send + Sync>> {
+    let params = scrypt::Params::new(kdf.log_n, kdf.r, kdf.p, 32)
+        .map_err(|e| format!("invalid keystore kdf params: {}", e))?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| format!("key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cheap enough for tests, the format is the same
+    const TEST_KDF: KdfParams = KdfParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    fn temp_path(name: &str) -> String {
+        let path = env::temp_dir().join(format!("keystore-{}-{}.json", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    fn keypair_file(name: &str, keypair: &Keypair) -> String {
+        let path = temp_path(name);
+        fs::write(
+            &path,
+            serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap(),
+        )
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn round_trips_a_keypair() {
+        let keypair = Keypair::new();
+        let input = keypair_file("round-trip-input", &keypair);
+        let output = temp_path("round-trip");
+
+        let pubkey = write_keystore(&input, &output, "hunter2", TEST_KDF).unwrap();
+        assert_eq!(pubkey, keypair.pubkey().to_string());
+        let decrypted = read_keystore(&output, "hunter2").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+    }
+
+    #[test]
+    fn rejects_a_wrong_password() {
+        let input = keypair_file("wrong-password-input", &Keypair::new());
+        let output = temp_path("wrong-password");
+        write_keystore(&input, &output, "hunter2", TEST_KDF).unwrap();
+
+        let err = read_keystore(&output, "hunter3").unwrap_err();
+        assert!(err.to_string().contains("wrong password"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_a_truncated_keystore() {
+        let input = keypair_file("truncated-input", &Keypair::new());
+        let output = temp_path("truncated");
+        write_keystore(&input, &output, "hunter2", TEST_KDF).unwrap();
+        let contents = fs::read_to_string(&output).unwrap();
+        fs::write(&output, &contents[..contents.len() / 2]).unwrap();
+
+        let err = read_keystore(&output, "hunter2").unwrap_err();
+        assert!(err.to_string().contains("malformed"), "{}", err);
+    }
+
+    #[test]
+    fn never_overwrites_an_existing_file() {
+        let output = temp_path("existing");
+        fs::write(&output, "keep me").unwrap();
+
+        // The keypair file is not even read
+        let err =
+            write_keystore("/nonexistent/keypair.json", &output, "hunter2", TEST_KDF).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        assert_eq!(fs::read_to_string(&output).unwrap(), "keep me");
+    }
+}
@@ -0,0 +1,130 @@
+pub mod keystore;
+pub mod remote;
+
+use std::sync::Arc;
+
+use solana_sdk::{hash::Hash, signature::Keypair, signer::Signer, transaction::Transaction};
+
+use crate::signer::remote::RemoteSigner;
+
+/// A signer shared by the wallet pool and the executor: a keypair held in
+/// memory or an external signing process.
+pub type SharedSigner = Arc<dyn Signer + Send + Sync>;
+
+/// Loads a signer from a key spec:
+/// - `file:<path>`: Solana CLI JSON keypair file
+/// - `keystore:<path>`: password-encrypted keystore, password from `KEYSTORE_PASSWORD`
+/// - `socket:<path>`: external signer listening on a local Unix socket
+/// - anything else: base58-encoded secret key
+pub fn load_signer(spec: &str) -> Result<SharedSigner, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(path) = spec.strip_prefix("file:") {
+        return Ok(Arc::new(read_keypair_file(path)?));
+    }
+    if let Some(path) = spec.strip_prefix("keystore:") {
+        let password = keystore::password_from_env()?;
+        return Ok(Arc::new(keystore::read_keystore(path, &password)?));
+    }
+    if let Some(path) = spec.strip_prefix("socket:") {
+        return Ok(Arc::new(RemoteSigner::connect(path)?));
+    }
+    Ok(Arc::new(keypair_from_base58(spec)?))
+}
+
+/// Signs `transaction` on the blocking pool: an external signer may take
+/// its whole timeout to answer, which must not stall the async workers.
+pub async fn sign_transaction(
+    mut transaction: Transaction,
+    signer: &SharedSigner,
+    recent_blockhash: Hash,
+) -> Result<Transaction, Box<dyn std::error::Error + Send + Sync>> {
+    let signer = signer.clone();
+    tokio::task::spawn_blocking(move || {
+        transaction
+            .try_sign(&[signer.as_ref()], recent_blockhash)
+            .map(|()| transaction)
+    })
+    .await?
+    .map_err(|e| format!("signing failed: {}", e).into())
+}
+
+/// Parses a base58 secret key without panicking on bad input.
+pub fn keypair_from_base58(
+    secret: &str,
+) -> Result<Keypair, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = bs58::decode(secret.trim())
+        .into_vec()
+        .map_err(|e| format!("keypair is not valid base58: {}", e))?;
+    keypair_from_bytes(&bytes)
+}
+
+/// Reads a keypair file as written by `solana-keygen` (a JSON array of 64 bytes).
+pub fn read_keypair_file(path: &str) -> Result<Keypair, Box<dyn std::error::Error + Send + Sync>> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read keypair file {}: {}", path, e))?;
+    let bytes: Vec<u8> = serde_json::from_str(&contents)
+        .map_err(|e| format!("keypair file {} is not a JSON byte array: {}", path, e))?;
+    keypair_from_bytes(&bytes)
+}
+
+fn keypair_from_bytes(bytes: &[u8]) -> Result<Keypair, Box<dyn std::error::Error + Send + Sync>> {
+    if bytes.len() != 64 {
+        return Err(format!("keypair must be 64 bytes, got {}", bytes.len()).into());
+    }
+    Keypair::try_from(bytes).map_err(|e| format!("invalid keypair: {}", e).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        pubkey::Pubkey,
+    };
+
+    use super::*;
+
+    #[test]
+    fn loads_base58_secret_keys() {
+        let keypair = Keypair::new();
+        let signer = load_signer(&keypair.to_base58_string()).unwrap();
+        assert_eq!(signer.pubkey(), keypair.pubkey());
+
+        assert!(load_signer("not-base58!").is_err());
+        assert!(load_signer(&bs58::encode([1u8; 32]).into_string()).is_err());
+    }
+
+    #[test]
+    fn loads_keypair_files() {
+        let keypair = Keypair::new();
+        let path = std::env::temp_dir().join(format!("keypair-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap(),
+        )
+        .unwrap();
+
+        let signer = load_signer(&format!("file:{}", path.to_string_lossy())).unwrap();
+        assert_eq!(signer.pubkey(), keypair.pubkey());
+    }
+
+    #[tokio::test]
+    async fn signs_on_a_current_thread_runtime() {
+        let signer: SharedSigner = Arc::new(Keypair::new());
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            vec![AccountMeta::new(signer.pubkey(), true)],
+        );
+        let message = Message::new(&[instruction], Some(&signer.pubkey()));
+
+        let transaction = sign_transaction(
+            Transaction::new_unsigned(message),
+            &signer,
+            Hash::new_unique(),
+        )
+        .await
+        .unwrap();
+        assert!(transaction.is_signed());
+        assert!(transaction.verify().is_ok());
+    }
+}
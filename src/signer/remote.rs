@@ -0,0 +1,200 @@
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde_json::{Value, json};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{Signer, SignerError},
+};
+
+const SIGNER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Signer backed by an external process on a local Unix socket, so the
+/// secret key never enters this process. Speaks newline-delimited JSON:
+///
+/// - `{"method":"pubkey"}` -> `{"pubkey":"<base58>"}`
+/// - `{"method":"sign","message":"<base64>"}` -> `{"signature":"<base58>"}`
+///
+/// Either may answer `{"error":"..."}` instead. The pubkey is asked once on
+/// connect and every signature is verified against it.
+pub struct RemoteSigner {
+    socket_path: PathBuf,
+    pubkey: Pubkey,
+}
+
+impl RemoteSigner {
+    pub fn connect(path: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let socket_path = PathBuf::from(path);
+        let response = request(&socket_path, &json!({ "method": "pubkey" }))
+            .map_err(|e| format!("external signer at {}: {}", path, e))?;
+        let pubkey = response["pubkey"]
+            .as_str()
+            .ok_or_else(|| format!("external signer at {} returned no pubkey", path))?;
+        let pubkey = Pubkey::from_str(pubkey)
+            .map_err(|e| format!("external signer at {} returned a bad pubkey: {}", path, e))?;
+
+        tracing::info!(%pubkey, %path, "🔑 External signer connected");
+        Ok(Self {
+            socket_path,
+            pubkey,
+        })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.pubkey)
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let response = request(
+            &self.socket_path,
+            &json!({ "method": "sign", "message": STANDARD.encode(message) }),
+        )
+        .map_err(SignerError::Connection)?;
+
+        let signature = response["signature"]
+            .as_str()
+            .ok_or_else(|| SignerError::Protocol("response has no signature".to_string()))?;
+        let signature = Signature::from_str(signature)
+            .map_err(|e| SignerError::Protocol(format!("bad signature: {}", e)))?;
+
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Custom(
+                "external signer returned a signature that does not verify".to_string(),
+            ));
+        }
+        Ok(signature)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// One request per connection, so a restarted signer is picked up without
+/// reconnect logic.
+#[cfg(unix)]
+fn request(socket_path: &Path, body: &Value) -> Result<Value, String> {
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket_path).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(SIGNER_TIMEOUT))
+        .and_then(|()| stream.set_write_timeout(Some(SIGNER_TIMEOUT)))
+        .map_err(|e| e.to_string())?;
+
+    writeln!(stream, "{}", body).map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| e.to_string())?;
+
+    let response: Value = serde_json::from_str(&line).map_err(|e| e.to_string())?;
+    if let Some(error) = response["error"].as_str() {
+        return Err(error.to_string());
+    }
+    Ok(response)
+}
+
+#[cfg(not(unix))]
+fn request(_socket_path: &Path, _body: &Value) -> Result<Value, String> {
+    Err("external signers are only supported on Unix".to_string())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::net::UnixListener;
+
+    use solana_sdk::signature::Keypair;
+
+    use super::*;
+
+    /// Answers every request on a fresh socket with `respond`.
+    fn serve(name: &str, respond: impl Fn(&Value) -> Value + Send + 'static) -> String {
+        let path =
+            std::env::temp_dir().join(format!("signer-{}-{}.sock", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).expect("bind signer socket");
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut line = String::new();
+                if BufReader::new(&stream).read_line(&mut line).is_err() {
+                    continue;
+                }
+                let request: Value = serde_json::from_str(&line).unwrap_or(Value::Null);
+                let _ = writeln!(stream, "{}", respond(&request));
+            }
+        });
+        path.to_string_lossy().into_owned()
+    }
+
+    /// A signer process holding `keypair`.
+    fn keypair_signer(name: &str, keypair: Keypair) -> String {
+        serve(name, move |request| match request["method"].as_str() {
+            Some("pubkey") => json!({ "pubkey": keypair.pubkey().to_string() }),
+            Some("sign") => {
+                let message = STANDARD
+                    .decode(request["message"].as_str().unwrap_or_default())
+                    .unwrap_or_default();
+                json!({ "signature": keypair.sign_message(&message).to_string() })
+            }
+            _ => json!({ "error": "unknown method" }),
+        })
+    }
+
+    #[test]
+    fn signs_through_the_external_process() {
+        let keypair = Keypair::new();
+        let pubkey = keypair.pubkey();
+        let signer = RemoteSigner::connect(&keypair_signer("sign", keypair)).unwrap();
+
+        assert_eq!(signer.pubkey(), pubkey);
+        let signature = signer.try_sign_message(b"hello").unwrap();
+        assert!(signature.verify(pubkey.as_ref(), b"hello"));
+    }
+
+    #[test]
+    fn rejects_signatures_from_another_key() {
+        let pubkey = Keypair::new().pubkey();
+        let other = Keypair::new();
+        let path = serve("wrong-key", move |request| {
+            match request["method"].as_str() {
+                Some("pubkey") => json!({ "pubkey": pubkey.to_string() }),
+                _ => json!({ "signature": other.sign_message(b"hello").to_string() }),
+            }
+        });
+        let signer = RemoteSigner::connect(&path).unwrap();
+
+        assert!(matches!(
+            signer.try_sign_message(b"hello"),
+            Err(SignerError::Custom(_))
+        ));
+    }
+
+    #[test]
+    fn surfaces_signer_errors() {
+        let pubkey = Keypair::new().pubkey();
+        let path = serve("error", move |request| match request["method"].as_str() {
+            Some("pubkey") => json!({ "pubkey": pubkey.to_string() }),
+            _ => json!({ "error": "locked" }),
+        });
+        let signer = RemoteSigner::connect(&path).unwrap();
+
+        match signer.try_sign_message(b"hello") {
+            Err(SignerError::Connection(error)) => assert_eq!(error, "locked"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn fails_to_connect_without_a_signer() {
+        let path = std::env::temp_dir().join(format!("signer-{}-missing.sock", std::process::id()));
+        assert!(RemoteSigner::connect(&path.to_string_lossy()).is_err());
+    }
+}
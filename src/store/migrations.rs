@@ -0,0 +1,96 @@
+use rusqlite::Connection;
+use tracing::info;
+
+/// Schema changes in order. The index of the last applied entry + 1 is kept
+/// in `PRAGMA user_version`; never edit an entry once released, append a new one.
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "CREATE TABLE tokens (
+        mint TEXT PRIMARY KEY,
+        bonding_curve TEXT NOT NULL,
+        name TEXT NOT NULL,
+        symbol TEXT NOT NULL,
+        creator TEXT NOT NULL,
+        created_slot INTEGER NOT NULL,
+        seen_at INTEGER NOT NULL
+    );
+    CREATE TABLE curve_snapshots (
+        id INTEGER PRIMARY KEY,
+        mint TEXT NOT NULL,
+        slot INTEGER NOT NULL,
+        elapsed_secs INTEGER NOT NULL,
+        virtual_token_reserves INTEGER NOT NULL,
+        virtual_sol_reserves INTEGER NOT NULL,
+        real_token_reserves INTEGER NOT NULL,
+        real_sol_reserves INTEGER NOT NULL,
+        token_total_supply INTEGER NOT NULL,
+        complete INTEGER NOT NULL,
+        recorded_at INTEGER NOT NULL
+    );
+    CREATE INDEX curve_snapshots_mint ON curve_snapshots (mint, recorded_at);
+    CREATE TABLE decisions (
+        id INTEGER PRIMARY KEY,
+        mint TEXT NOT NULL,
+        decision TEXT NOT NULL,
+        reason TEXT,
+        slot INTEGER NOT NULL,
+        market_cap_sol REAL NOT NULL,
+        market_cap_usd REAL NOT NULL,
+        min_market_cap_sol REAL NOT NULL,
+        virtual_sol_reserves INTEGER NOT NULL,
+        virtual_token_reserves INTEGER NOT NULL,
+        decided_at INTEGER NOT NULL
+    );
+    CREATE INDEX decisions_mint ON decisions (mint);
+    CREATE TABLE transactions (
+        id INTEGER PRIMARY KEY,
+        mint TEXT NOT NULL,
+        side TEXT NOT NULL,
+        signature TEXT,
+        status TEXT NOT NULL,
+        amount_lamports INTEGER,
+        token_amount INTEGER,
+        units_consumed INTEGER,
+        error TEXT,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE INDEX transactions_mint ON transactions (mint, side);
+    CREATE INDEX transactions_signature ON transactions (signature);
+    CREATE TABLE positions (
+        mint TEXT PRIMARY KEY,
+        tokens INTEGER NOT NULL,
+        cost_lamports INTEGER NOT NULL,
+        proceeds_lamports INTEGER NOT NULL,
+        opened_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL,
+        closed_at INTEGER
+    );",
+    // 2: wallet that signed each transaction
+    "ALTER TABLE transactions ADD COLUMN wallet TEXT;",
+    // 3: off-chain metadata uri of each token
+    "ALTER TABLE tokens ADD COLUMN uri TEXT;",
+];
+
+/// Applies every migration newer than the database's `user_version`.
+pub fn migrate(conn: &mut Connection) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "store schema version {} is newer than this build ({})",
+            version,
+            MIGRATIONS.len()
+        )
+        .into());
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+        info!(version = index + 1, "🗄️ Applied store migration");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+pub mod migrations;
+pub mod query;
+pub mod recorder;
+
+pub use recorder::run_recorder;
+
+use rusqlite::Connection;
+
+/// Opens (or creates) the store and brings its schema up to date.
+pub fn open(path: &str) -> Result<Connection, Box<dyn std::error::Error + Send + Sync>> {
+    let mut conn = Connection::open(path)?;
+    // WAL lets the query command read while the sniper is writing
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.pragma_update(None, "synchronous", "NORMAL")?;
+    migrations::migrate(&mut conn)?;
+    Ok(conn)
+}
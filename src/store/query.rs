@@ -0,0 +1,111 @@
+use rusqlite::{Connection, OpenFlags, types::ValueRef};
+
+const DEFAULT_LIMIT: u32 = 50;
+
+const USAGE: &str = "usage: pump-sniper-grpc query <tokens|decisions|transactions|positions|snapshots <mint>|sql <statement>> [--limit N]";
+
+/// `query` subcommand: prints recent rows of a table, or the result of an
+/// arbitrary read-only statement, as tab-separated values.
+pub fn run(path: &str, args: &[String]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (args, limit) = split_limit(args)?;
+    let Some(kind) = args.first() else {
+        return Err(USAGE.into());
+    };
+
+    let sql = match kind.as_str() {
+        "tokens" => "SELECT mint, name, symbol, creator, created_slot, seen_at
+                     FROM tokens ORDER BY seen_at DESC LIMIT ?1"
+            .to_string(),
+        "decisions" => "SELECT d.decided_at, d.mint, t.symbol, d.decision, d.reason,
+                               d.market_cap_sol, d.market_cap_usd, d.min_market_cap_sol, d.slot
+                        FROM decisions d LEFT JOIN tokens t ON t.mint = d.mint
+                        ORDER BY d.id DESC LIMIT ?1"
+            .to_string(),
+        "transactions" => {
+            "SELECT created_at, mint, side, wallet, status, signature, amount_lamports,
+                                  token_amount, units_consumed, error
+                           FROM transactions ORDER BY id DESC LIMIT ?1"
+                .to_string()
+        }
+        "positions" => "SELECT p.mint, t.symbol, p.tokens, p.cost_lamports, p.proceeds_lamports,
+                               p.opened_at, p.closed_at
+                        FROM positions p LEFT JOIN tokens t ON t.mint = p.mint
+                        ORDER BY p.updated_at DESC LIMIT ?1"
+            .to_string(),
+        "snapshots" => {
+            let mint = args.get(1).ok_or(USAGE)?;
+            return print_rows(
+                &open_read_only(path)?,
+                "SELECT recorded_at, slot, elapsed_secs, virtual_sol_reserves,
+                        virtual_token_reserves, real_token_reserves, complete
+                 FROM curve_snapshots WHERE mint = ?1 ORDER BY id LIMIT ?2",
+                rusqlite::params![mint, limit],
+            );
+        }
+        "sql" => {
+            let statement = args[1..].join(" ");
+            if statement.is_empty() {
+                return Err(USAGE.into());
+            }
+            return print_rows(&open_read_only(path)?, &statement, []);
+        }
+        _ => return Err(USAGE.into()),
+    };
+
+    print_rows(&open_read_only(path)?, &sql, [limit])
+}
+
+/// Read-only so ad-hoc statements can't modify the history.
+fn open_read_only(path: &str) -> Result<Connection, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+fn split_limit(
+    args: &[String],
+) -> Result<(Vec<String>, u32), Box<dyn std::error::Error + Send + Sync>> {
+    let mut rest = Vec::new();
+    let mut limit = DEFAULT_LIMIT;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--limit" {
+            limit = iter.next().ok_or(USAGE)?.parse()?;
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    Ok((rest, limit))
+}
+
+fn print_rows(
+    conn: &Connection,
+    sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut statement = conn.prepare(sql)?;
+    let columns: Vec<String> = statement
+        .column_names()
+        .iter()
+        .map(|name| name.to_string())
+        .collect();
+    println!("{}", columns.join("\t"));
+
+    let mut rows = statement.query(params)?;
+    while let Some(row) = rows.next()? {
+        let values: Vec<String> = (0..columns.len())
+            .map(|index| match row.get_ref(index) {
+                Ok(ValueRef::Null) => String::new(),
+                Ok(ValueRef::Integer(value)) => value.to_string(),
+                Ok(ValueRef::Real(value)) => value.to_string(),
+                Ok(ValueRef::Text(text)) => String::from_utf8_lossy(text).into_owned(),
+                Ok(ValueRef::Blob(blob)) => format!("<{} bytes>", blob.len()),
+                Err(_) => String::new(),
+            })
+            .collect();
+        println!("{}", values.join("\t"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use rusqlite::{Connection, OptionalExtension, params};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+use crate::pipeline::{
+    CurveUpdate, OrderFailed, OrderFilled, OrderSide, OrderSubmitted, OrderVetoed, Rejection,
+    Signal, SniperEvent,
+};
+use crate::types::TokenInfo;
+
+/// Writes pipeline events to the SQLite store. Curve updates are sampled at
+/// most once per `curve_sample` per token; everything else is recorded as is.
+struct Recorder {
+    conn: Connection,
+    curve_sample: Duration,
+    last_sample: HashMap<String, Instant>,
+}
+
+/// Records every bus event until all publishers are gone. SQLite calls block,
+/// so this runs on the blocking thread pool.
+pub fn run_recorder(
+    mut events: broadcast::Receiver<SniperEvent>,
+    conn: Connection,
+    curve_sample_ms: u64,
+) {
+    let mut recorder = Recorder {
+        conn,
+        curve_sample: Duration::from_millis(curve_sample_ms),
+        last_sample: HashMap::new(),
+    };
+
+    loop {
+        match events.blocking_recv() {
+            Ok(event) => {
+                if let Err(e) = recorder.record(&event) {
+                    warn!(error = %e, "⚠️ Failed to write event to store");
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                warn!(
+                    skipped,
+                    "⚠️ Store recorder lagged behind, events not recorded"
+                );
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+impl Recorder {
+    fn record(&mut self, event: &SniperEvent) -> rusqlite::Result<()> {
+        match event {
+            SniperEvent::TokenCreated(token) => self.insert_token(token),
+            SniperEvent::CurveUpdated(update) => self.insert_snapshot(update),
+            SniperEvent::BatchEnded(mints) => {
+                for mint in mints {
+                    self.last_sample.remove(mint);
+                }
+                Ok(())
+            }
+            SniperEvent::Signal(signal) => self.insert_decision(signal),
+            SniperEvent::Rejected(rejection) => self.insert_rejection(rejection),
+            SniperEvent::OrderSubmitted(order) => self.insert_transaction(order),
+            SniperEvent::OrderFilled(fill) => self.record_fill(fill),
+            SniperEvent::OrderFailed(failure) => self.record_failure(failure),
+            SniperEvent::OrderVetoed(veto) => self.insert_veto(veto),
+            SniperEvent::BatchStarted(_)
+            | SniperEvent::StreamHealth(_)
+            | SniperEvent::SettingsUpdated(_) => Ok(()),
+        }
+    }
+
+    fn insert_token(&self, token: &TokenInfo) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO tokens
+                (mint, bonding_curve, name, symbol, uri, creator, created_slot, seen_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                token.mint,
+                token.bonding_curve,
+                token.name,
+                token.symbol,
+                token.uri,
+                token.creator,
+                token.created_slot as i64,
+                now_ms(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_snapshot(&mut self, update: &CurveUpdate) -> rusqlite::Result<()> {
+        let now = Instant::now();
+        if let Some(last) = self.last_sample.get(&update.token.mint)
+            && now.duration_since(*last) < self.curve_sample
+            && !update.curve.complete
+        {
+            return Ok(());
+        }
+        self.last_sample.insert(update.token.mint.clone(), now);
+
+        let curve = &update.curve;
+        self.conn.execute(
+            "INSERT INTO curve_snapshots
+                (mint, slot, elapsed_secs, virtual_token_reserves, virtual_sol_reserves,
+                 real_token_reserves, real_sol_reserves, token_total_supply, complete, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                update.token.mint,
+                update.slot as i64,
+                update.elapsed_secs as i64,
+                curve.virtual_token_reserves as i64,
+                curve.virtual_sol_reserves as i64,
+                curve.real_token_reserves as i64,
+                curve.real_sol_reserves as i64,
+                curve.token_total_supply as i64,
+                curve.complete,
+                now_ms(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_decision(&self, signal: &Signal) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO decisions
+                (mint, decision, slot, market_cap_sol, market_cap_usd, min_market_cap_sol,
+                 virtual_sol_reserves, virtual_token_reserves, decided_at)
+             VALUES (?1, 'signal', ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                signal.token.mint,
+                signal.slot as i64,
+                signal.market_cap_sol,
+                signal.market_cap_usd,
+                signal.min_market_cap_sol,
+                signal.curve.virtual_sol_reserves as i64,
+                signal.curve.virtual_token_reserves as i64,
+                now_ms(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_rejection(&self, rejection: &Rejection) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO decisions
+                (mint, decision, reason, slot, market_cap_sol, market_cap_usd, min_market_cap_sol,
+                 virtual_sol_reserves, virtual_token_reserves, decided_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                rejection.token.mint,
+                rejection.kind.to_string(),
+                rejection.violations.join("; "),
+                rejection.slot as i64,
+                rejection.market_cap_sol,
+                rejection.market_cap_usd,
+                rejection.min_market_cap_sol,
+                rejection.curve.virtual_sol_reserves as i64,
+                rejection.curve.virtual_token_reserves as i64,
+                now_ms(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_transaction(&self, order: &OrderSubmitted) -> rusqlite::Result<()> {
+        let now = now_ms();
+        self.conn.execute(
+            "INSERT INTO transactions
+                (mint, side, wallet, signature, status, amount_lamports, token_amount,
+                 created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, 'built', ?5, ?6, ?7, ?7)",
+            params![
+                order.mint,
+                order.side.to_string(),
+                order.wallet,
+                order.signature,
+                order.amount_lamports as i64,
+                order.token_amount as i64,
+                now,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn record_fill(&self, fill: &OrderFilled) -> rusqlite::Result<()> {
+        let now = now_ms();
+        self.conn.execute(
+            "UPDATE transactions SET status = 'simulated', units_consumed = ?1, updated_at = ?2
+             WHERE signature = ?3",
+            params![
+                fill.units_consumed.map(|units| units as i64),
+                now,
+                fill.signature
+            ],
+        )?;
+
+        match fill.side {
+            OrderSide::Buy => self.conn.execute(
+                "INSERT INTO positions
+                    (mint, tokens, cost_lamports, proceeds_lamports, opened_at, updated_at)
+                 VALUES (?1, ?2, ?3, 0, ?4, ?4)
+                 ON CONFLICT (mint) DO UPDATE SET
+                    tokens = tokens + excluded.tokens,
+                    cost_lamports = cost_lamports + excluded.cost_lamports,
+                    updated_at = excluded.updated_at,
+                    closed_at = NULL",
+                params![
+                    fill.mint,
+                    fill.token_amount as i64,
+                    fill.amount_lamports as i64,
+                    now
+                ],
+            )?,
+            OrderSide::Sell => self.conn.execute(
+                "UPDATE positions SET
+                    tokens = MAX(tokens - ?1, 0),
+                    proceeds_lamports = proceeds_lamports + ?2,
+                    updated_at = ?3,
+                    closed_at = CASE WHEN tokens - ?1 <= 0 THEN ?3 ELSE closed_at END
+                 WHERE mint = ?4",
+                params![
+                    fill.token_amount as i64,
+                    fill.amount_lamports as i64,
+                    now,
+                    fill.mint
+                ],
+            )?,
+        };
+        Ok(())
+    }
+
+    fn record_failure(&self, failure: &OrderFailed) -> rusqlite::Result<()> {
+        let now = now_ms();
+        let side = failure.side.to_string();
+
+        // A failed simulation belongs to the order built just before it
+        let pending: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT id FROM transactions
+                 WHERE mint = ?1 AND side = ?2 AND status = 'built'
+                 ORDER BY id DESC LIMIT 1",
+                params![failure.mint, side],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match pending {
+            Some(id) => self.conn.execute(
+                "UPDATE transactions SET status = 'failed', error = ?1, updated_at = ?2
+                 WHERE id = ?3",
+                params![failure.reason, now, id],
+            )?,
+            // Failed before a transaction was built
+            None => self.conn.execute(
+                "INSERT INTO transactions (mint, side, status, error, created_at, updated_at)
+                 VALUES (?1, ?2, 'failed', ?3, ?4, ?4)",
+                params![failure.mint, side, failure.reason, now],
+            )?,
+        };
+        Ok(())
+    }
+
+    fn insert_veto(&self, veto: &OrderVetoed) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO transactions (mint, side, status, error, created_at, updated_at)
+             VALUES (?1, ?2, 'vetoed', ?3, ?4, ?4)",
+            params![veto.mint, veto.side.to_string(), veto.reason, now_ms()],
+        )?;
+        Ok(())
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
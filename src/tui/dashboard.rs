@@ -0,0 +1,255 @@
+use ratatui::{
+    Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, Paragraph, Row, Table},
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::{Duration, interval};
+
+use crate::pipeline::snapshot::PipelineSnapshot;
+use crate::pipeline::{Shutdown, SniperEvent, StreamStatus};
+
+const REDRAW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Full-screen dashboard fed from the event bus. Takes over the terminal
+/// until shutdown; `q`, `Esc` or Ctrl-C trigger shutdown since raw mode
+/// swallows the usual SIGINT.
+pub async fn run_dashboard(
+    mut events: broadcast::Receiver<SniperEvent>,
+    mut state: PipelineSnapshot,
+    shutdown: Shutdown,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut terminal = ratatui::init();
+    let mut redraw = interval(REDRAW_INTERVAL);
+
+    let result = loop {
+        tokio::select! {
+            _ = shutdown.wait() => break Ok(()),
+            event = events.recv() => match event {
+                Ok(event) => state.apply(&event),
+                // Missed events only make the view slightly stale
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break Ok(()),
+            },
+            _ = redraw.tick() => {
+                if quit_requested()? {
+                    shutdown.trigger();
+                    break Ok(());
+                }
+                if let Err(e) = terminal.draw(|frame| render(frame, &state)) {
+                    break Err(e.into());
+                }
+            }
+        }
+    };
+
+    ratatui::restore();
+    result
+}
+
+fn quit_requested() -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            let ctrl_c =
+                key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if ctrl_c || key.code == KeyCode::Char('q') || key.code == KeyCode::Esc {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn render(frame: &mut Frame, state: &PipelineSnapshot) {
+    let [header, middle, bottom] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(8),
+        Constraint::Length(12),
+    ])
+    .areas(frame.area());
+    let [monitoring, collecting] =
+        Layout::horizontal([Constraint::Percentage(70), Constraint::Percentage(30)]).areas(middle);
+    let [signals, positions] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+
+    render_health(frame, header, state);
+    render_monitoring(frame, monitoring, state);
+    render_collecting(frame, collecting, state);
+    render_signals(frame, signals, state);
+    render_positions(frame, positions, state);
+}
+
+fn render_health(frame: &mut Frame, area: Rect, state: &PipelineSnapshot) {
+    let mut spans = vec![Span::raw(format!(
+        "SOL ${:.2}  threshold {:.2} SOL   ",
+        state.sol_usd_price, state.min_market_cap_sol
+    ))];
+    for (stream, view) in &state.streams {
+        let (label, color) = match &view.status {
+            StreamStatus::Connected => ("up".to_string(), Color::Green),
+            StreamStatus::Disconnected { .. } => ("down".to_string(), Color::Red),
+            StreamStatus::Reconnecting { attempt, .. } => {
+                (format!("retry #{}", attempt), Color::Yellow)
+            }
+        };
+        spans.push(Span::raw(format!("{}: ", stream)));
+        spans.push(Span::styled(label, Style::default().fg(color)));
+        if let Some(slot) = view.last_slot {
+            spans.push(Span::raw(format!(" @{}", slot)));
+        }
+        spans.push(Span::raw("   "));
+    }
+
+    frame.render_widget(
+        Paragraph::new(Line::from(spans)).block(Block::bordered().title(" Streams (q to quit) ")),
+        area,
+    );
+}
+
+fn render_monitoring(frame: &mut Frame, area: Rect, state: &PipelineSnapshot) {
+    let rows = state.monitoring.iter().map(|monitored| {
+        let threshold = state.threshold_percent(monitored.market_cap_sol);
+        let style = if threshold >= 100.0 {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+        };
+        Row::new(vec![
+            monitored.token.symbol.clone(),
+            monitored.token.name.clone(),
+            format!("{:.2}", monitored.market_cap_sol),
+            format!("${:.0}", monitored.market_cap_usd),
+            format!("{:.0}%", threshold),
+            format!("{:.1}%", monitored.completion_percent),
+            format!("{:.1}", monitored.sol_to_graduate),
+            format!("{}s", monitored.elapsed_secs),
+        ])
+        .style(style)
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Min(12),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "Symbol",
+            "Name",
+            "MC SOL",
+            "MC USD",
+            "Threshold",
+            "Curve",
+            "To grad",
+            "Age",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(format!(" Monitoring ({}) ", state.monitoring.len())));
+
+    frame.render_widget(table, area);
+}
+
+fn render_collecting(frame: &mut Frame, area: Rect, state: &PipelineSnapshot) {
+    let items: Vec<ListItem> = state
+        .collecting
+        .iter()
+        .rev()
+        .map(|token| {
+            ListItem::new(format!(
+                "{} ({}) {}s",
+                token.symbol,
+                token.name,
+                token.created_at.elapsed().as_secs()
+            ))
+        })
+        .collect();
+
+    frame.render_widget(
+        List::new(items)
+            .block(Block::bordered().title(format!(" Collecting ({}) ", state.collecting.len()))),
+        area,
+    );
+}
+
+fn render_signals(frame: &mut Frame, area: Rect, state: &PipelineSnapshot) {
+    let items: Vec<ListItem> = state
+        .signals
+        .iter()
+        .map(|signal| {
+            ListItem::new(format!(
+                "{} ({}) {:.2} SOL (${:.0}) {}",
+                signal.name,
+                signal.symbol,
+                signal.market_cap_sol,
+                signal.market_cap_usd,
+                signal.mint
+            ))
+        })
+        .collect();
+
+    frame.render_widget(
+        List::new(items).block(Block::bordered().title(" Recent signals ")),
+        area,
+    );
+}
+
+fn render_positions(frame: &mut Frame, area: Rect, state: &PipelineSnapshot) {
+    let rows = state.positions.iter().map(|position| {
+        let cost_sol = position.amount_lamports as f64 / 1_000_000_000.0;
+        let (value, pnl, style) = match position.value_lamports {
+            Some(value_lamports) if cost_sol > 0.0 => {
+                let value_sol = value_lamports / 1_000_000_000.0;
+                let pnl_percent = (value_sol - cost_sol) / cost_sol * 100.0;
+                let color = if pnl_percent >= 0.0 {
+                    Color::Green
+                } else {
+                    Color::Red
+                };
+                (
+                    format!("{:.4}", value_sol),
+                    format!("{:+.1}%", pnl_percent),
+                    Style::default().fg(color),
+                )
+            }
+            _ => ("-".to_string(), "-".to_string(), Style::default()),
+        };
+        Row::new(vec![
+            position.symbol.clone(),
+            format!("{:.4}", cost_sol),
+            value,
+            pnl,
+        ])
+        .style(style)
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(10),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+        ],
+    )
+    .header(
+        Row::new(vec!["Symbol", "Cost SOL", "Value SOL", "PnL"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(format!(" Positions ({}) ", state.positions.len())));
+
+    frame.render_widget(table, area);
+}
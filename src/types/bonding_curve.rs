@@ -1,6 +1,11 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 
-#[derive(Debug, BorshDeserialize, BorshSerialize, BorshSchema)]
+use crate::utils::constants::{INITIAL_REAL_TOKEN_RESERVES, LAMPORTS_PER_SOL};
+
+// Discriminator (8), five u64 fields (40), complete (1), creator (32)
+const ACCOUNT_LEN: usize = 81;
+
+#[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub struct BondingCurve {
     pub virtual_token_reserves: u64,
     pub virtual_sol_reserves: u64,
@@ -12,10 +17,54 @@ pub struct BondingCurve {
 }
 
 impl BondingCurve {
+    /// Spot price in lamports per raw token unit.
+    pub fn price_lamports(&self) -> f64 {
+        if self.virtual_token_reserves == 0 {
+            return 0.0;
+        }
+        self.virtual_sol_reserves as f64 / self.virtual_token_reserves as f64
+    }
+
+    /// SOL still to be bought in before the curve completes, from the
+    /// constant product over the virtual reserves. Trading fees are not
+    /// included.
+    pub fn sol_to_graduate(&self) -> f64 {
+        if self.complete || self.real_token_reserves == 0 {
+            return 0.0;
+        }
+        let remaining_tokens = self
+            .virtual_token_reserves
+            .saturating_sub(self.real_token_reserves);
+        if remaining_tokens == 0 {
+            return 0.0;
+        }
+        let k = self.virtual_sol_reserves as f64 * self.virtual_token_reserves as f64;
+        let sol_at_completion = k / remaining_tokens as f64;
+        (sol_at_completion - self.virtual_sol_reserves as f64).max(0.0) / LAMPORTS_PER_SOL
+    }
+
+    /// Seconds until the curve completes if SOL keeps coming in at this
+    /// rate. `None` when nothing is coming in.
+    pub fn graduation_eta_secs(&self, inflow_sol_per_sec: f64) -> Option<f64> {
+        if self.complete {
+            return Some(0.0);
+        }
+        (inflow_sol_per_sec > 0.0).then(|| self.sol_to_graduate() / inflow_sol_per_sec)
+    }
+
+    /// Percentage of the sellable supply already bought off the curve.
+    pub fn completion_percent(&self) -> f64 {
+        if self.complete {
+            return 100.0;
+        }
+        let sold = INITIAL_REAL_TOKEN_RESERVES.saturating_sub(self.real_token_reserves);
+        sold as f64 / INITIAL_REAL_TOKEN_RESERVES as f64 * 100.0
+    }
+
     pub fn from_account_data(
         data: &[u8],
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
-        if data.len() < 57 {
+        if data.len() < ACCOUNT_LEN {
             return Err("Account data too short".into());
         }
         let offset = 8;
@@ -39,3 +88,101 @@ impl BondingCurve {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fresh pump.fun curve: 30 virtual SOL over 1.073B virtual tokens
+    fn fresh_curve() -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserves: 1_073_000_000_000_000,
+            virtual_sol_reserves: 30_000_000_000,
+            real_token_reserves: INITIAL_REAL_TOKEN_RESERVES,
+            real_sol_reserves: 0,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+            creator: [7; 32],
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6 * expected.abs().max(1.0),
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn prices_from_the_virtual_reserves() {
+        assert_close(fresh_curve().price_lamports(), 30e9 / 1.073e15);
+
+        let empty = BondingCurve {
+            virtual_token_reserves: 0,
+            ..fresh_curve()
+        };
+        assert_eq!(empty.price_lamports(), 0.0);
+    }
+
+    #[test]
+    fn measures_the_way_to_completion() {
+        let curve = fresh_curve();
+        // 30 * 1.073e15 / 2.799e14 SOL at completion
+        let to_graduate = 30.0 * 1.073e15 / 2.799e14 - 30.0;
+        assert_close(curve.sol_to_graduate(), to_graduate);
+        assert_close(curve.graduation_eta_secs(2.0).unwrap(), to_graduate / 2.0);
+        assert_eq!(curve.graduation_eta_secs(0.0), None);
+        assert_eq!(curve.completion_percent(), 0.0);
+
+        let half_sold = BondingCurve {
+            real_token_reserves: INITIAL_REAL_TOKEN_RESERVES / 2,
+            ..fresh_curve()
+        };
+        assert_close(half_sold.completion_percent(), 50.0);
+    }
+
+    #[test]
+    fn complete_curves_have_nothing_left() {
+        let complete = BondingCurve {
+            complete: true,
+            ..fresh_curve()
+        };
+        assert_eq!(complete.sol_to_graduate(), 0.0);
+        assert_eq!(complete.graduation_eta_secs(0.0), Some(0.0));
+        assert_eq!(complete.completion_percent(), 100.0);
+
+        let sold_out = BondingCurve {
+            real_token_reserves: 0,
+            ..fresh_curve()
+        };
+        assert_eq!(sold_out.sol_to_graduate(), 0.0);
+    }
+
+    #[test]
+    fn decodes_account_data() {
+        let curve = fresh_curve();
+        let mut data = vec![0xAA; 8];
+        data.extend(borsh::to_vec(&curve).unwrap());
+        assert_eq!(data.len(), ACCOUNT_LEN);
+
+        let decoded = BondingCurve::from_account_data(&data).unwrap();
+        assert_eq!(decoded.virtual_token_reserves, curve.virtual_token_reserves);
+        assert_eq!(decoded.virtual_sol_reserves, curve.virtual_sol_reserves);
+        assert_eq!(decoded.real_token_reserves, curve.real_token_reserves);
+        assert_eq!(decoded.token_total_supply, curve.token_total_supply);
+        assert!(!decoded.complete);
+        assert_eq!(decoded.creator, curve.creator);
+    }
+
+    #[test]
+    fn rejects_short_account_data() {
+        let mut data = vec![0; 8];
+        data.extend(borsh::to_vec(&fresh_curve()).unwrap());
+
+        // Long enough for the reserves but cut off inside the creator
+        assert!(BondingCurve::from_account_data(&data[..60]).is_err());
+        assert!(BondingCurve::from_account_data(&data[..ACCOUNT_LEN - 1]).is_err());
+    }
+}
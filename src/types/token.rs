@@ -4,7 +4,10 @@ pub struct TokenInfo {
     pub bonding_curve: String,
     pub name: String,
     pub symbol: String,
+    /// Off-chain metadata JSON
+    pub uri: String,
     pub creator: String,
+    pub created_slot: u64,
     pub created_at: std::time::Instant,
 }
 
@@ -14,26 +17,39 @@ impl TokenInfo {
         bonding_curve: String,
         name: String,
         symbol: String,
+        uri: String,
         creator: String,
+        created_slot: u64,
     ) -> Self {
         Self {
             mint,
             bonding_curve,
             name,
             symbol,
+            uri,
             creator,
+            created_slot,
             created_at: std::time::Instant::now(),
         }
     }
 
-    pub fn print_creation(&self) {
-        println!("🆕 NEW TOKEN CREATED!");
-        println!("   Name: {}", self.name);
-        println!("   Symbol: {}", self.symbol);
-        println!("   Mint: {}", self.mint);
-        println!("   Bonding Curve: {}", self.bonding_curve);
-        println!("   Creator: {}", self.creator);
-        println!("   📦 Added to current collection batch");
-        println!();
+    /// Span carrying the token's identity; everything logged for this token
+    /// should happen inside it.
+    pub fn span(&self) -> tracing::Span {
+        tracing::info_span!(
+            "token",
+            mint = %self.mint,
+            symbol = %self.symbol,
+            bonding_curve = %self.bonding_curve
+        )
+    }
+
+    pub fn log_creation(&self) {
+        tracing::info!(
+            name = %self.name,
+            creator = %self.creator,
+            slot = self.created_slot,
+            "🆕 New token created"
+        );
     }
 }
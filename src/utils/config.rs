@@ -1,5 +1,8 @@
 use std::env;
 
+use crate::utils::valuation::MarketCapMetric;
+use crate::wallet::Routing;
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub api_key: String,
@@ -7,14 +10,52 @@ pub struct Config {
     pub helius_rpc_url: String,
     pub slippage_bps: u64,
     pub buy_amount_lamports: u64,
-    pub buyer_keypair: String,
+    pub priority_fee_lamports: u64,
+    pub funding_shrink: bool,
+    pub funding_min_buy_lamports: u64,
+    /// Signer specs of the wallet pool (see `signer::load_signer`), at least one
+    pub buyer_keypairs: Vec<String>,
+    pub wallet_routing: Routing,
     pub min_market_cap_usd: f64,
+    pub market_cap_metric: MarketCapMetric,
     pub collection_window_secs: u64,
     pub monitoring_window_secs: u64,
+    pub stream_replay: bool,
+    pub reconnect_initial_backoff_ms: u64,
+    pub reconnect_max_backoff_ms: u64,
+    pub shutdown_timeout_secs: u64,
+    pub dedup_ttl_secs: u64,
+    pub dedup_capacity: usize,
+    pub dedup_state_path: Option<String>,
+    pub metrics_addr: Option<String>,
+    pub control_api_addr: Option<String>,
+    pub store_path: Option<String>,
+    pub store_curve_sample_ms: u64,
+    pub tui: bool,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let collection_window_secs: u64 = env::var("COLLECTION_WINDOW_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse()?;
+        let monitoring_window_secs: u64 = env::var("MONITORING_WINDOW_SECS")
+            .unwrap_or_else(|_| "40".to_string())
+            .parse()?;
+
+        // BUYER_KEYPAIRS (comma separated) takes precedence over the single BUYER_KEYPAIR
+        let buyer_keypairs: Vec<String> = match env::var("BUYER_KEYPAIRS") {
+            Ok(keys) => keys
+                .split(',')
+                .map(|key| key.trim().to_string())
+                .filter(|key| !key.is_empty())
+                .collect(),
+            Err(_) => vec![env::var("BUYER_KEYPAIR")?],
+        };
+        if buyer_keypairs.is_empty() {
+            return Err("BUYER_KEYPAIRS must contain at least one key".into());
+        }
+
         Ok(Config {
             api_key: env::var("HELIUS_API_KEY")?,
             laserstream_endpoint: env::var("LASERSTREAM_ENDPOINT")?,
@@ -25,15 +66,62 @@ impl Config {
             buy_amount_lamports: env::var("BUY_LAMPORTS")
                 .unwrap_or_else(|_| "100000000".to_string())
                 .parse()?,
-            buyer_keypair: env::var("BUYER_KEYPAIR")?,
+            priority_fee_lamports: env::var("PRIORITY_FEE_LAMPORTS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            funding_shrink: env::var("FUNDING_SHRINK")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+            funding_min_buy_lamports: env::var("FUNDING_MIN_BUY_LAMPORTS")
+                .unwrap_or_else(|_| "10000000".to_string())
+                .parse()?,
+            buyer_keypairs,
+            wallet_routing: env::var("WALLET_ROUTING")
+                .unwrap_or_else(|_| "round_robin".to_string())
+                .parse()?,
             min_market_cap_usd: env::var("MIN_MARKET_CAP_USD")
                 .unwrap_or_else(|_| "8000.0".to_string())
                 .parse()?,
-            collection_window_secs: env::var("COLLECTION_WINDOW_SECS")
-                .unwrap_or_else(|_| "30".to_string())
+            market_cap_metric: env::var("MARKET_CAP_METRIC")
+                .unwrap_or_else(|_| "supply".to_string())
+                .parse()?,
+            collection_window_secs,
+            monitoring_window_secs,
+            stream_replay: env::var("STREAM_REPLAY")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+            reconnect_initial_backoff_ms: env::var("RECONNECT_INITIAL_BACKOFF_MS")
+                .unwrap_or_else(|_| "500".to_string())
                 .parse()?,
-            monitoring_window_secs: env::var("MONITORING_WINDOW_SECS")
-                .unwrap_or_else(|_| "40".to_string())
+            reconnect_max_backoff_ms: env::var("RECONNECT_MAX_BACKOFF_MS")
+                .unwrap_or_else(|_| "30000".to_string())
+                .parse()?,
+            shutdown_timeout_secs: env::var("SHUTDOWN_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()?,
+            // A day, so mints replayed by the stream after a restart are still
+            // remembered; the capacity keeps the memory bounded
+            dedup_ttl_secs: env::var("DEDUP_TTL_SECS")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()?,
+            dedup_capacity: env::var("DEDUP_CAPACITY")
+                .unwrap_or_else(|_| "100000".to_string())
+                .parse()?,
+            dedup_state_path: env::var("DEDUP_STATE_PATH")
+                .ok()
+                .filter(|path| !path.is_empty()),
+            metrics_addr: env::var("METRICS_ADDR")
+                .ok()
+                .filter(|addr| !addr.is_empty()),
+            control_api_addr: env::var("CONTROL_API_ADDR")
+                .ok()
+                .filter(|addr| !addr.is_empty()),
+            store_path: env::var("STORE_PATH").ok().filter(|path| !path.is_empty()),
+            store_curve_sample_ms: env::var("STORE_CURVE_SAMPLE_MS")
+                .unwrap_or_else(|_| "1000".to_string())
+                .parse()?,
+            tui: env::var("TUI")
+                .unwrap_or_else(|_| "false".to_string())
                 .parse()?,
         })
     }
@@ -43,19 +131,34 @@ impl Config {
     }
 
     pub fn print_info(&self, coingecko_sol_usd_price: f64) {
-        println!(
-            "🎯 Minimum Market Cap: {:.2} SOL (${:.0})",
+        tracing::info!(
+            min_market_cap_sol = self.min_market_cap_sol(coingecko_sol_usd_price),
+            min_market_cap_usd = self.min_market_cap_usd,
+            "🎯 Minimum market cap: {:.2} SOL (${:.0})",
             self.min_market_cap_sol(coingecko_sol_usd_price),
             self.min_market_cap_usd
         );
-        println!(
-            "⏱️  Collection window: {} seconds",
-            self.collection_window_secs
-        );
-        println!(
-            "⏱️  Monitoring window: {} seconds",
+        tracing::info!(
+            collection_window_secs = self.collection_window_secs,
+            monitoring_window_secs = self.monitoring_window_secs,
+            "⏱️  Collection window: {}s, monitoring window: {}s",
+            self.collection_window_secs,
             self.monitoring_window_secs
         );
-        println!("🔍 Monitoring for new tokens...\n");
+        tracing::info!("🔍 Monitoring for new tokens...");
+    }
+}
+
+/// Parses an env var if it is set and not empty.
+pub(crate) fn optional_var<T>(
+    name: &str,
+) -> Result<Option<T>, Box<dyn std::error::Error + Send + Sync>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match env::var(name) {
+        Ok(value) if !value.is_empty() => Ok(Some(value.parse()?)),
+        _ => Ok(None),
     }
 }
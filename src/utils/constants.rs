@@ -1,2 +1,15 @@
 pub const CREATE_DISCRIMINATOR: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
 pub const PUMP_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+// real_token_reserves of a freshly created bonding curve (6 decimals)
+pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000;
+pub const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+// Capacity of the bounded channels between pipeline stages
+pub const STAGE_CHANNEL_CAPACITY: usize = 256;
+// Capacity of the broadcast bus; slow subscribers past this start lagging
+pub const EVENT_BUS_CAPACITY: usize = 4096;
+
+// Store read by the `query` command when STORE_PATH is not set
+pub const DEFAULT_STORE_PATH: &str = "pump-sniper.db";
+// How often the processed-token set is pruned, reported and persisted
+pub const DEDUP_MAINTENANCE_INTERVAL_SECS: u64 = 60;
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Set of recently seen keys bounded by age and by size. Entries older than
+/// `ttl` are dropped on access, and the oldest entry is evicted when the set
+/// is full, so memory stays flat no matter how long the process runs.
+pub struct ExpiringSet {
+    ttl: Duration,
+    capacity: usize,
+    entries: HashMap<String, SystemTime>,
+    // Insertion order, oldest first; every key here is also in `entries`
+    order: VecDeque<(String, SystemTime)>,
+    evicted: u64,
+}
+
+impl ExpiringSet {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            evicted: 0,
+        }
+    }
+
+    /// Inserts `key`, returning false if it was already present and not expired.
+    pub fn insert(&mut self, key: &str) -> bool {
+        self.insert_at(key, SystemTime::now())
+    }
+
+    fn insert_at(&mut self, key: &str, seen_at: SystemTime) -> bool {
+        self.prune();
+        if self.entries.contains_key(key) {
+            return false;
+        }
+
+        while self.entries.len() >= self.capacity {
+            self.pop_oldest();
+        }
+
+        self.entries.insert(key.to_string(), seen_at);
+        self.order.push_back((key.to_string(), seen_at));
+        true
+    }
+
+    /// Drops every entry older than the ttl.
+    pub fn prune(&mut self) {
+        let now = SystemTime::now();
+        while let Some((_, seen_at)) = self.order.front() {
+            let age = now.duration_since(*seen_at).unwrap_or_default();
+            if age < self.ttl {
+                break;
+            }
+            self.pop_oldest();
+        }
+    }
+
+    fn pop_oldest(&mut self) {
+        if let Some((key, _)) = self.order.pop_front() {
+            self.entries.remove(&key);
+            self.evicted += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Number of entries removed so far, by expiry or by capacity.
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+
+    /// Loads entries saved by `save`, skipping the ones that already expired.
+    /// A missing file yields an empty set.
+    pub fn load(path: &Path, ttl: Duration, capacity: usize) -> io::Result<Self> {
+        let mut set = Self::new(ttl, capacity);
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(set),
+            Err(e) => return Err(e),
+        };
+
+        for line in contents.lines() {
+            let Some((key, secs)) = line.split_once(' ') else {
+                continue;
+            };
+            let Ok(secs) = secs.parse::<u64>() else {
+                continue;
+            };
+            set.insert_at(key, UNIX_EPOCH + Duration::from_secs(secs));
+        }
+        set.prune();
+        // Loading does not count as eviction
+        set.evicted = 0;
+
+        Ok(set)
+    }
+
+    /// Writes the live entries as `<key> <unix seconds>` lines, replacing
+    /// the file atomically.
+    pub fn save(&mut self, path: &Path) -> io::Result<()> {
+        self.prune();
+
+        let tmp_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path)?;
+        for (key, seen_at) in &self.order {
+            let secs = seen_at
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            writeln!(file, "{} {}", key, secs)?;
+        }
+        file.sync_all()?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("dedup-{}-{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn refuses_keys_until_they_expire() {
+        let mut set = ExpiringSet::new(HOUR, 10);
+        assert!(set.insert_at("a", SystemTime::now() - HOUR * 2));
+        assert!(set.insert("b"));
+        assert!(!set.insert("b"));
+
+        // Expired, so pruned by the insert
+        assert!(set.insert("a"));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.evicted(), 1);
+    }
+
+    #[test]
+    fn evicts_the_oldest_key_when_full() {
+        let mut set = ExpiringSet::new(HOUR, 2);
+        set.insert("a");
+        set.insert("b");
+        set.insert("c");
+
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.evicted(), 1);
+        assert!(!set.insert("b"));
+        assert!(!set.insert("c"));
+        assert!(set.insert("a"));
+    }
+
+    #[test]
+    fn round_trips_live_keys_through_a_file() {
+        let path = temp_path("round-trip");
+        let mut set = ExpiringSet::new(HOUR, 10);
+        set.insert_at("expired", SystemTime::now() - HOUR * 2);
+        set.insert("a");
+        set.insert("b");
+        set.save(&path).unwrap();
+
+        let mut loaded = ExpiringSet::load(&path, HOUR, 10).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.evicted(), 0);
+        assert!(!loaded.insert("a"));
+        assert!(!loaded.insert("b"));
+        assert!(loaded.insert("expired"));
+    }
+
+    #[test]
+    fn skips_expired_and_malformed_lines_on_load() {
+        let path = temp_path("stale");
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        fs::write(
+            &path,
+            format!("old {}\ngarbage\nbad nan\nnew {}\n", now - 7200, now),
+        )
+        .unwrap();
+
+        let mut loaded = ExpiringSet::load(&path, HOUR, 10).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(!loaded.insert("new"));
+        assert!(loaded.insert("old"));
+    }
+
+    #[test]
+    fn loads_a_missing_file_as_empty() {
+        let set = ExpiringSet::load(&temp_path("missing"), HOUR, 10).unwrap();
+        assert_eq!(set.len(), 0);
+    }
+}
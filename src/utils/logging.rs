@@ -0,0 +1,93 @@
+use std::env;
+use std::fs::OpenOptions;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use tracing_subscriber::{EnvFilter, fmt};
+
+/// Installs the global tracing subscriber.
+///
+/// `LOG_FORMAT` selects `pretty` (default, human-friendly) or `json` (one
+/// object per line with the active spans, for log aggregation). `LOG_LEVEL`
+/// takes an `EnvFilter` directive so levels can be set per module, e.g.
+/// `info,pump_sniper_grpc::execute_ixs=debug`; `RUST_LOG` is used if unset.
+///
+/// With `TUI=true` the terminal belongs to the dashboard, so logs are
+/// appended to `LOG_FILE` (default `pump-sniper.log`) instead.
+pub fn init_logging() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let filter = match env::var("LOG_LEVEL") {
+        Ok(directives) if !directives.is_empty() => EnvFilter::try_new(directives)?,
+        _ => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+    let format: LogFormat = env::var("LOG_FORMAT")
+        .unwrap_or_else(|_| "pretty".to_string())
+        .parse()?;
+
+    let tui = env::var("TUI").is_ok_and(|tui| tui == "true");
+    if tui {
+        let path = env::var("LOG_FILE").unwrap_or_else(|_| "pump-sniper.log".to_string());
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let builder = fmt()
+            .with_env_filter(filter)
+            .with_ansi(false)
+            .with_writer(Mutex::new(file));
+        match format {
+            LogFormat::Json => builder
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .try_init()?,
+            LogFormat::Pretty => builder.try_init()?,
+        }
+        return Ok(());
+    }
+
+    match format {
+        LogFormat::Json => fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_current_span(true)
+            .with_span_list(true)
+            .try_init()?,
+        LogFormat::Pretty => fmt()
+            .with_env_filter(filter)
+            .with_target(false)
+            .try_init()?,
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Pretty,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!(
+                "Unknown LOG_FORMAT: {} (expected pretty or json)",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_log_format() {
+        assert_eq!("pretty".parse(), Ok(LogFormat::Pretty));
+        assert_eq!("json".parse(), Ok(LogFormat::Json));
+        assert!("JSON".parse::<LogFormat>().is_err());
+        assert!("text".parse::<LogFormat>().is_err());
+    }
+}
@@ -1,3 +1,8 @@
 pub mod config;
 pub mod constants;
+pub mod dedup;
 pub mod helper_functions;
+pub mod logging;
+#[cfg(test)]
+pub mod stub_server;
+pub mod valuation;
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::State,
+    http::{StatusCode, Uri, header},
+    response::{IntoResponse, Response},
+};
+
+/// A request the stub server received.
+#[derive(Debug, Clone)]
+pub struct StubRequest {
+    pub path: String,
+    pub body: String,
+}
+
+/// Canned answer for one path.
+#[derive(Debug, Clone)]
+pub struct StubResponse {
+    status: StatusCode,
+    body: String,
+    delay: Duration,
+    chunked: bool,
+}
+
+impl StubResponse {
+    pub fn json(body: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: body.into(),
+            delay: Duration::ZERO,
+            chunked: false,
+        }
+    }
+
+    pub fn status(status: StatusCode) -> Self {
+        Self {
+            status,
+            body: String::new(),
+            delay: Duration::ZERO,
+            chunked: false,
+        }
+    }
+
+    /// Answers only after `delay`, to run into client timeouts.
+    pub fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Streams the body in small chunks without a `Content-Length`.
+    pub fn chunked(mut self) -> Self {
+        self.chunked = true;
+        self
+    }
+}
+
+struct StubState {
+    routes: HashMap<String, StubResponse>,
+    requests: Mutex<Vec<StubRequest>>,
+}
+
+/// Local HTTP server on a random port standing in for metadata gateways,
+/// notification APIs and RPC nodes. Records every request and answers
+/// unknown paths with a 404. A route named `<path>#<method>` answers the
+/// JSON-RPC calls of that method on the path, ahead of a route for the
+/// path itself.
+pub struct StubServer {
+    pub url: String,
+    state: Arc<StubState>,
+}
+
+impl StubServer {
+    pub async fn start(routes: Vec<(&str, StubResponse)>) -> Self {
+        let state = Arc::new(StubState {
+            routes: routes
+                .into_iter()
+                .map(|(path, response)| (path.to_string(), response))
+                .collect(),
+            requests: Mutex::new(Vec::new()),
+        });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind stub server");
+        let url = format!("http://{}", listener.local_addr().expect("stub address"));
+        let app = Router::new().fallback(handle).with_state(state.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        Self { url, state }
+    }
+
+    pub fn requests(&self) -> Vec<StubRequest> {
+        self.state
+            .requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Requests received on `path`.
+    pub fn hits(&self, path: &str) -> usize {
+        self.requests()
+            .iter()
+            .filter(|request| request.path == path)
+            .count()
+    }
+}
+
+async fn handle(State(state): State<Arc<StubState>>, uri: Uri, body: String) -> Response {
+    state
+        .requests
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(StubRequest {
+            path: uri.path().to_string(),
+            body: body.clone(),
+        });
+
+    let method = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|request| request["method"].as_str().map(str::to_string));
+    let response = method
+        .and_then(|method| state.routes.get(&format!("{}#{}", uri.path(), method)))
+        .or_else(|| state.routes.get(uri.path()))
+        .cloned();
+    let Some(response) = response else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    tokio::time::sleep(response.delay).await;
+    let body = if response.chunked {
+        let chunks: Vec<Result<Vec<u8>, Infallible>> = response
+            .body
+            .into_bytes()
+            .chunks(1024)
+            .map(|chunk| Ok(chunk.to_vec()))
+            .collect();
+        Body::from_stream(futures_util::stream::iter(chunks))
+    } else {
+        Body::from(response.body)
+    };
+    (
+        response.status,
+        [(header::CONTENT_TYPE, "application/json")],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,219 @@
+use std::str::FromStr;
+
+use crate::safety::mint::MintInfo;
+use crate::types::BondingCurve;
+use crate::utils::constants::LAMPORTS_PER_SOL;
+use crate::utils::helper_functions::calculate_market_cap;
+
+// Pump.fun mints use 6 decimals; used until the mint has been read
+const DEFAULT_DECIMALS: u8 = 6;
+
+/// Which number is compared against `MIN_MARKET_CAP_USD`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketCapMetric {
+    /// Spot price times total supply, as pump.fun shows it
+    Supply,
+    /// `virtual_sol_reserves` in SOL, the number earlier versions used
+    VirtualSol,
+}
+
+impl FromStr for MarketCapMetric {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "supply" => Ok(MarketCapMetric::Supply),
+            "virtual_sol" => Ok(MarketCapMetric::VirtualSol),
+            other => Err(format!(
+                "unknown market cap metric '{}', expected supply or virtual_sol",
+                other
+            )),
+        }
+    }
+}
+
+impl MarketCapMetric {
+    /// Market cap in SOL and USD by this metric.
+    pub fn market_cap(self, curve: &BondingCurve, sol_usd_price: f64) -> (f64, f64) {
+        match self {
+            MarketCapMetric::Supply => {
+                let valuation = Valuation::of(curve, None, sol_usd_price);
+                (valuation.market_cap_sol, valuation.market_cap_usd)
+            }
+            MarketCapMetric::VirtualSol => {
+                calculate_market_cap(curve.virtual_sol_reserves, sol_usd_price)
+            }
+        }
+    }
+}
+
+/// A token's value at the curve's spot price.
+#[derive(Debug, Clone)]
+pub struct Valuation {
+    /// SOL per whole token
+    pub price_sol: f64,
+    pub price_usd: f64,
+    /// Spot price times the curve's `token_total_supply`
+    pub market_cap_sol: f64,
+    pub market_cap_usd: f64,
+    /// Spot price times the mint's supply, which only differs from the
+    /// market cap if tokens were minted outside the curve
+    pub fdv_sol: f64,
+    pub fdv_usd: f64,
+}
+
+impl Valuation {
+    /// Values the token at the curve's spot price. The mint, when known,
+    /// gives the decimals and the supply for the FDV; otherwise pump.fun's
+    /// 6 decimals and the curve's total supply are assumed.
+    pub fn of(curve: &BondingCurve, mint: Option<&MintInfo>, sol_usd_price: f64) -> Self {
+        // Lamports per raw unit: decimals cancel out for anything priced
+        // over a raw supply
+        let price_lamports = curve.price_lamports();
+        let decimals = mint.map_or(DEFAULT_DECIMALS, |mint| mint.decimals);
+        let supply = mint.map_or(curve.token_total_supply, |mint| mint.supply);
+
+        let price_sol = price_lamports * 10f64.powi(decimals as i32) / LAMPORTS_PER_SOL;
+        let market_cap_sol = price_lamports * curve.token_total_supply as f64 / LAMPORTS_PER_SOL;
+        let fdv_sol = price_lamports * supply as f64 / LAMPORTS_PER_SOL;
+
+        Valuation {
+            price_sol,
+            price_usd: price_sol * sol_usd_price,
+            market_cap_sol,
+            market_cap_usd: market_cap_sol * sol_usd_price,
+            fdv_sol,
+            fdv_usd: fdv_sol * sol_usd_price,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL_USD: f64 = 150.0;
+    const TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
+    fn curve(virtual_token_reserves: u64, virtual_sol_reserves: u64) -> BondingCurve {
+        BondingCurve {
+            virtual_token_reserves,
+            virtual_sol_reserves,
+            // Not read by the valuation
+            real_token_reserves: 0,
+            real_sol_reserves: 0,
+            token_total_supply: TOTAL_SUPPLY,
+            complete: false,
+            creator: [0; 32],
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        let tolerance = expected.abs().max(1.0) * 1e-9;
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn assert_valuation(valuation: &Valuation, price_sol: f64, market_cap_sol: f64, fdv_sol: f64) {
+        assert_close(valuation.price_sol, price_sol);
+        assert_close(valuation.price_usd, price_sol * SOL_USD);
+        assert_close(valuation.market_cap_sol, market_cap_sol);
+        assert_close(valuation.market_cap_usd, market_cap_sol * SOL_USD);
+        assert_close(valuation.fdv_sol, fdv_sol);
+        assert_close(valuation.fdv_usd, fdv_sol * SOL_USD);
+    }
+
+    #[test]
+    fn fresh_curve() {
+        let curve = curve(1_073_000_000_000_000, 30_000_000_000);
+        let valuation = Valuation::of(&curve, None, SOL_USD);
+        // 30 SOL over 1.073B tokens
+        assert_valuation(
+            &valuation,
+            30.0 / 1_073_000_000.0,
+            30.0 / 1.073,
+            30.0 / 1.073,
+        );
+        assert_close(valuation.market_cap_sol, 27.958993476);
+
+        let (sol, usd) = MarketCapMetric::Supply.market_cap(&curve, SOL_USD);
+        assert_close(sol, valuation.market_cap_sol);
+        assert_close(usd, valuation.market_cap_usd);
+        let (sol, usd) = MarketCapMetric::VirtualSol.market_cap(&curve, SOL_USD);
+        assert_close(sol, 30.0);
+        assert_close(usd, 4_500.0);
+    }
+
+    #[test]
+    fn mid_curve() {
+        // Same constant product as a fresh curve, 473M tokens bought
+        let curve = curve(600_000_000_000_000, 53_650_000_000);
+        let valuation = Valuation::of(&curve, None, SOL_USD);
+        assert_valuation(&valuation, 53.65 / 600_000_000.0, 53.65 / 0.6, 53.65 / 0.6);
+        assert_close(valuation.market_cap_sol, 89.416666667);
+
+        let (sol, usd) = MarketCapMetric::VirtualSol.market_cap(&curve, SOL_USD);
+        assert_close(sol, 53.65);
+        assert_close(usd, 8_047.5);
+    }
+
+    #[test]
+    fn near_completion() {
+        let curve = curve(280_000_000_000_000, 114_964_285_714);
+        let valuation = Valuation::of(&curve, None, SOL_USD);
+        assert_valuation(
+            &valuation,
+            114.964285714 / 280_000_000.0,
+            114.964285714 / 0.28,
+            114.964285714 / 0.28,
+        );
+        // The real market cap is well past the old virtual SOL number here
+        let (sol, _) = MarketCapMetric::VirtualSol.market_cap(&curve, SOL_USD);
+        assert_close(sol, 114.964285714);
+        assert!(valuation.market_cap_sol > sol * 3.0);
+    }
+
+    #[test]
+    fn empty_virtual_token_reserves() {
+        let curve = curve(0, 30_000_000_000);
+        let valuation = Valuation::of(&curve, None, SOL_USD);
+        assert_valuation(&valuation, 0.0, 0.0, 0.0);
+
+        let (sol, usd) = MarketCapMetric::VirtualSol.market_cap(&curve, SOL_USD);
+        assert_close(sol, 30.0);
+        assert_close(usd, 4_500.0);
+    }
+
+    #[test]
+    fn fdv_uses_mint_supply_and_decimals() {
+        let curve = curve(1_073_000_000_000_000, 30_000_000_000);
+        let mint = MintInfo {
+            supply: 2 * TOTAL_SUPPLY,
+            decimals: 9,
+            ..Default::default()
+        };
+        let valuation = Valuation::of(&curve, Some(&mint), SOL_USD);
+        // Raw units are the same, a whole token is 1000 times more of them
+        assert_valuation(
+            &valuation,
+            30.0 / 1_073_000.0,
+            30.0 / 1.073,
+            2.0 * 30.0 / 1.073,
+        );
+    }
+
+    #[test]
+    fn parses_metric() {
+        assert_eq!(
+            "supply".parse::<MarketCapMetric>(),
+            Ok(MarketCapMetric::Supply)
+        );
+        assert_eq!(
+            " virtual_sol ".parse::<MarketCapMetric>(),
+            Ok(MarketCapMetric::VirtualSol)
+        );
+        assert!("fdv".parse::<MarketCapMetric>().is_err());
+    }
+}
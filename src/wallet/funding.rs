@@ -0,0 +1,158 @@
+use crate::execute_ixs::buy::FEE_BUFFER_BPS;
+use crate::utils::config::Config;
+
+// Rent-exempt minimum of a 165-byte token account
+pub const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+// Base fee per signature
+pub const SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+/// Outcome of the pre-trade affordability check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Funding {
+    /// The full amount can be spent
+    Full,
+    /// Only this smaller amount fits in the balance
+    Shrunk {
+        amount_lamports: u64,
+    },
+    Insufficient {
+        reason: String,
+    },
+}
+
+/// Affordability settings applied to every buy.
+#[derive(Debug, Clone, Copy)]
+pub struct FundingRules {
+    pub priority_fee_lamports: u64,
+    /// Shrink a buy to what the balance covers instead of skipping it
+    pub shrink: bool,
+    pub min_buy_lamports: u64,
+}
+
+impl FundingRules {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            priority_fee_lamports: config.priority_fee_lamports,
+            shrink: config.funding_shrink,
+            min_buy_lamports: config.funding_min_buy_lamports,
+        }
+    }
+}
+
+/// Lamports a buy of `amount_lamports` can take from the wallet at most:
+/// the amount plus the fee buffer, token account rent if the ATA has to be
+/// created, the signature fee and the priority fee.
+pub fn buy_cost(
+    amount_lamports: u64,
+    needs_token_account: bool,
+    priority_fee_lamports: u64,
+) -> u64 {
+    let rent = if needs_token_account {
+        TOKEN_ACCOUNT_RENT_LAMPORTS
+    } else {
+        0
+    };
+    amount_lamports
+        + amount_lamports * FEE_BUFFER_BPS / 10_000
+        + rent
+        + SIGNATURE_FEE_LAMPORTS
+        + priority_fee_lamports
+}
+
+/// Checks a buy against the wallet balance. With `shrink` the amount is
+/// reduced to what the balance covers, as long as it stays above
+/// `min_buy_lamports`; otherwise the order is skipped.
+pub fn plan_buy(
+    balance_lamports: u64,
+    amount_lamports: u64,
+    needs_token_account: bool,
+    priority_fee_lamports: u64,
+    shrink: bool,
+    min_buy_lamports: u64,
+) -> Funding {
+    let cost = buy_cost(amount_lamports, needs_token_account, priority_fee_lamports);
+    if cost <= balance_lamports {
+        return Funding::Full;
+    }
+
+    let fixed = buy_cost(0, needs_token_account, priority_fee_lamports);
+    // Largest amount whose cost including the fee buffer fits
+    let affordable = balance_lamports.saturating_sub(fixed) * 10_000 / (10_000 + FEE_BUFFER_BPS);
+
+    if shrink && affordable >= min_buy_lamports && affordable > 0 {
+        Funding::Shrunk {
+            amount_lamports: affordable,
+        }
+    } else {
+        Funding::Insufficient {
+            reason: format!(
+                "insufficient funds: buy needs {} lamports, wallet has {}",
+                cost, balance_lamports
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    #[test]
+    fn cost_covers_buffer_rent_and_fees() {
+        assert_eq!(
+            buy_cost(SOL, true, 10_000),
+            SOL + SOL / 50 + TOKEN_ACCOUNT_RENT_LAMPORTS + SIGNATURE_FEE_LAMPORTS + 10_000
+        );
+        assert_eq!(
+            buy_cost(SOL, false, 0),
+            SOL + SOL / 50 + SIGNATURE_FEE_LAMPORTS
+        );
+    }
+
+    #[test]
+    fn full_when_the_balance_covers_the_cost() {
+        let cost = buy_cost(SOL / 10, true, 10_000);
+        assert_eq!(
+            plan_buy(cost, SOL / 10, true, 10_000, false, 0),
+            Funding::Full
+        );
+        assert_eq!(
+            plan_buy(10 * SOL, SOL / 10, true, 10_000, true, 0),
+            Funding::Full
+        );
+    }
+
+    #[test]
+    fn shrinks_to_what_the_balance_covers() {
+        // 0.102 SOL left after fixed costs covers 0.1 SOL plus the 2% buffer
+        let balance = buy_cost(0, true, 0) + 102_000_000;
+        assert_eq!(
+            plan_buy(balance, SOL / 5, true, 0, true, SOL / 100),
+            Funding::Shrunk {
+                amount_lamports: SOL / 10
+            }
+        );
+        assert!(buy_cost(SOL / 10, true, 0) <= balance);
+    }
+
+    #[test]
+    fn insufficient_without_shrink_or_below_the_minimum() {
+        let balance = buy_cost(0, true, 0) + 102_000_000;
+        let expected = Funding::Insufficient {
+            reason: format!(
+                "insufficient funds: buy needs {} lamports, wallet has {}",
+                buy_cost(SOL / 5, true, 0),
+                balance
+            ),
+        };
+        assert_eq!(plan_buy(balance, SOL / 5, true, 0, false, 0), expected);
+        assert_eq!(plan_buy(balance, SOL / 5, true, 0, true, SOL / 5), expected);
+        // Not even the fixed costs are covered
+        assert!(matches!(
+            plan_buy(1_000, SOL / 5, true, 0, true, 0),
+            Funding::Insufficient { .. }
+        ));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod funding;
+pub mod monitor;
+pub mod pool;
+pub mod state;
+
+pub use monitor::run_wallet_monitor;
+pub use pool::{Routing, WalletPool};
+pub use state::Wallet;
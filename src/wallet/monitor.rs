@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use helius_laserstream::grpc::{
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+    SubscribeRequestFilterAccountsFilter, SubscribeRequestFilterAccountsFilterMemcmp,
+    subscribe_request_filter_accounts_filter::Filter,
+    subscribe_request_filter_accounts_filter_memcmp::Data, subscribe_update::UpdateOneof,
+};
+use solana_account_decoder_client_types::UiAccountData;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::pubkey::Pubkey;
+use tracing::{info, warn};
+
+use crate::execute_ixs::accounts::{TOKEN_2022_PROGRAM, TOKEN_PROGRAM};
+use crate::monitors::supervisor::spawn_supervised_stream;
+use crate::pipeline::{EventBus, Shutdown};
+use crate::utils::config::Config;
+use crate::wallet::Wallet;
+use crate::wallet::state::{ACCOUNT_TYPE_ACCOUNT, TOKEN_ACCOUNT_LEN, TokenHolding};
+
+// Offset of the owner field in a token account
+const TOKEN_ACCOUNT_OWNER_OFFSET: u64 = 32;
+
+/// Loads each wallet's balance and token accounts over RPC, then keeps them
+/// current from one Laserstream subscription on the wallet accounts and every
+/// token account they own. Runs until shutdown.
+pub async fn run_wallet_monitor(
+    wallets: Vec<Arc<Wallet>>,
+    rpc_client: Arc<RpcClient>,
+    config: Config,
+    bus: EventBus,
+    shutdown: Shutdown,
+) {
+    for wallet in &wallets {
+        if let Err(e) = load_wallet(wallet, &rpc_client).await {
+            warn!(wallet = %wallet.owner(), error = %e, "⚠️ Initial wallet load failed, waiting for stream updates");
+        }
+    }
+
+    let mut accounts = HashMap::from([(
+        "wallets".to_string(),
+        SubscribeRequestFilterAccounts {
+            account: wallets.iter().map(|w| w.owner().to_string()).collect(),
+            ..Default::default()
+        },
+    )]);
+    // Filters within an entry are ANDed, so each owner and token program
+    // needs its own entry
+    for (index, wallet) in wallets.iter().enumerate() {
+        let owner_filter = SubscribeRequestFilterAccountsFilter {
+            filter: Some(Filter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                offset: TOKEN_ACCOUNT_OWNER_OFFSET,
+                data: Some(Data::Bytes(wallet.owner().to_bytes().to_vec())),
+            })),
+        };
+        // SPL Token accounts are always 165 bytes
+        let spl_filter = SubscribeRequestFilterAccountsFilter {
+            filter: Some(Filter::Datasize(TOKEN_ACCOUNT_LEN as u64)),
+        };
+        // Token-2022 accounts with extensions carry their account type
+        // right after the base layout
+        let token_2022_filter = SubscribeRequestFilterAccountsFilter {
+            filter: Some(Filter::Memcmp(SubscribeRequestFilterAccountsFilterMemcmp {
+                offset: TOKEN_ACCOUNT_LEN as u64,
+                data: Some(Data::Bytes(vec![ACCOUNT_TYPE_ACCOUNT])),
+            })),
+        };
+        for (name, program, layout_filter) in [
+            ("spl", TOKEN_PROGRAM, spl_filter),
+            ("token_2022", TOKEN_2022_PROGRAM, token_2022_filter),
+        ] {
+            accounts.insert(
+                format!("wallet_tokens_{}_{}", name, index),
+                SubscribeRequestFilterAccounts {
+                    owner: vec![program.to_string()],
+                    filters: vec![owner_filter.clone(), layout_filter],
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    let request = SubscribeRequest {
+        accounts,
+        commitment: Some(CommitmentLevel::Confirmed.into()),
+        ..Default::default()
+    };
+
+    let mut updates = spawn_supervised_stream("wallet", request, &config, bus);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.wait() => break,
+            update = updates.recv() => match update {
+                Some(update) => {
+                    if let Some(UpdateOneof::Account(account_update)) = update.update_oneof
+                        && let Some(account) = account_update.account
+                        && let Ok(address) = Pubkey::try_from(account.pubkey.as_slice())
+                    {
+                        match wallets.iter().find(|wallet| wallet.owner() == address) {
+                            Some(wallet) => wallet.set_lamports(account.lamports, account_update.slot),
+                            // Each wallet keeps the account only if it owns it
+                            None => {
+                                for wallet in &wallets {
+                                    wallet.apply_token_account(address, &account.data);
+                                }
+                            }
+                        }
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+}
+
+async fn load_wallet(
+    wallet: &Wallet,
+    rpc_client: &RpcClient,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let owner = wallet.owner();
+
+    let slot = rpc_client.get_slot().await?;
+    let lamports = rpc_client.get_balance(&owner).await?;
+    wallet.set_lamports(lamports, slot);
+
+    for program in [TOKEN_PROGRAM, TOKEN_2022_PROGRAM] {
+        let token_accounts = rpc_client
+            .get_token_accounts_by_owner(
+                &owner,
+                TokenAccountsFilter::ProgramId(Pubkey::from_str_const(program)),
+            )
+            .await?;
+        for keyed in token_accounts {
+            match (
+                Pubkey::from_str(&keyed.pubkey),
+                parse_holding(&keyed.account.data),
+            ) {
+                (Ok(address), Some(holding)) => wallet.insert_holding(address, holding),
+                _ => warn!(token_account = %keyed.pubkey, "⚠️ Unreadable token account, skipping"),
+            }
+        }
+    }
+
+    info!(
+        wallet = %owner,
+        sol = lamports as f64 / 1_000_000_000.0,
+        token_accounts = wallet.holdings().len(),
+        "👛 Wallet loaded"
+    );
+    Ok(())
+}
+
+/// Mint and raw amount of a token account returned in `jsonParsed` encoding.
+fn parse_holding(data: &UiAccountData) -> Option<TokenHolding> {
+    let UiAccountData::Json(account) = data else {
+        return None;
+    };
+    let info = account.parsed.get("info")?;
+    let mint = Pubkey::from_str(info.get("mint")?.as_str()?).ok()?;
+    let amount = info
+        .get("tokenAmount")?
+        .get("amount")?
+        .as_str()?
+        .parse()
+        .ok()?;
+    Some(TokenHolding { mint, amount })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_account_decoder_client_types::ParsedAccount;
+
+    use super::*;
+
+    #[test]
+    fn parses_json_token_account() {
+        let mint = Pubkey::new_unique();
+        let data = UiAccountData::Json(ParsedAccount {
+            program: "spl-token-2022".to_string(),
+            parsed: serde_json::json!({
+                "type": "account",
+                "info": {
+                    "mint": mint.to_string(),
+                    "owner": Pubkey::new_unique().to_string(),
+                    "tokenAmount": { "amount": "1234567", "decimals": 6 }
+                }
+            }),
+            space: 170,
+        });
+
+        let holding = parse_holding(&data).unwrap();
+        assert_eq!(holding.mint, mint);
+        assert_eq!(holding.amount, 1_234_567);
+        assert!(parse_holding(&UiAccountData::LegacyBinary(String::new())).is_none());
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+use crate::pipeline::OrderFilled;
+use crate::risk::{RiskConfig, RiskGuard};
+use crate::signer::SharedSigner;
+use crate::wallet::Wallet;
+
+/// How buys are spread over the wallets in the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Routing {
+    /// Each buy goes to the next wallet in turn
+    RoundRobin,
+    /// Each buy goes to the wallet with the smallest open exposure
+    LeastExposure,
+    /// A mint always uses the wallet it was first bought with; new mints go
+    /// to the wallet holding the fewest positions
+    PerToken,
+}
+
+impl FromStr for Routing {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "round_robin" => Ok(Routing::RoundRobin),
+            "least_exposure" => Ok(Routing::LeastExposure),
+            "per_token" => Ok(Routing::PerToken),
+            other => Err(format!(
+                "unknown wallet routing '{}', expected round_robin, least_exposure or per_token",
+                other
+            )),
+        }
+    }
+}
+
+/// A wallet of the pool with its own balance view and limits.
+pub struct PooledWallet {
+    pub signer: SharedSigner,
+    pub wallet: Arc<Wallet>,
+    pub limits: RiskGuard,
+}
+
+/// Buyer wallets and the routing that picks one per order.
+pub struct WalletPool {
+    wallets: Vec<PooledWallet>,
+    routing: Routing,
+    next: AtomicUsize,
+    /// Wallet that last bought each mint, so sells come from the same one
+    assignments: Mutex<HashMap<String, usize>>,
+}
+
+impl WalletPool {
+    pub fn new(signers: Vec<SharedSigner>, routing: Routing, limits: RiskConfig) -> Self {
+        let wallets = signers
+            .into_iter()
+            .map(|signer| PooledWallet {
+                wallet: Arc::new(Wallet::new(signer.pubkey())),
+                limits: RiskGuard::new(limits.clone()),
+                signer,
+            })
+            .collect();
+
+        Self {
+            wallets,
+            routing,
+            next: AtomicUsize::new(0),
+            assignments: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.wallets.len()
+    }
+
+    pub fn get(&self, index: usize) -> &PooledWallet {
+        &self.wallets[index]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PooledWallet> {
+        self.wallets.iter()
+    }
+
+    /// Wallets to try for a buy of `mint`, in routing order. The caller
+    /// takes the first one within its own limits that can fund the buy; the
+    /// wallet's quota is only used once the buy is reserved.
+    pub fn select_for_buy(&self, mint: &str) -> Vec<usize> {
+        self.candidates(mint)
+    }
+
+    /// The wallet that bought `mint`, or failing that the one holding most of it.
+    pub fn select_for_sell(&self, mint: &str) -> usize {
+        if let Some(index) = self
+            .assignments
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(mint)
+        {
+            return *index;
+        }
+
+        let Ok(mint) = Pubkey::from_str(mint) else {
+            return 0;
+        };
+        (0..self.wallets.len())
+            .max_by_key(|index| self.wallets[*index].wallet.token_balance(&mint))
+            .unwrap_or(0)
+    }
+
+    /// Holds a funded buy against the wallet's limits while it is built.
+    pub fn reserve_buy(&self, index: usize, amount_lamports: u64) {
+        self.wallets[index].limits.reserve_buy(amount_lamports);
+    }
+
+    /// Releases a reserved buy; a built one counts against the wallet's
+    /// limits and ties the mint to it for sells.
+    pub fn finish_buy(&self, index: usize, mint: &str, amount_lamports: u64, built: bool) {
+        self.wallets[index]
+            .limits
+            .finish_buy(amount_lamports, built);
+        if built {
+            self.assignments
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(mint.to_string(), index);
+        }
+    }
+
+    pub fn record_fill(&self, index: usize, fill: &OrderFilled) {
+        self.wallets[index].limits.record_fill(fill);
+    }
+
+    fn candidates(&self, mint: &str) -> Vec<usize> {
+        let count = self.wallets.len();
+        match self.routing {
+            Routing::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed) % count;
+                (0..count).map(|offset| (start + offset) % count).collect()
+            }
+            Routing::LeastExposure => {
+                let mut indexes: Vec<usize> = (0..count).collect();
+                indexes.sort_by_key(|index| self.wallets[*index].limits.exposure_lamports());
+                indexes
+            }
+            Routing::PerToken => {
+                let assigned = self
+                    .assignments
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(mint)
+                    .copied();
+                let mut indexes: Vec<usize> = (0..count).collect();
+                indexes.sort_by_key(|index| self.wallets[*index].limits.open_positions());
+                if let Some(assigned) = assigned {
+                    indexes.retain(|index| *index != assigned);
+                    indexes.insert(0, assigned);
+                }
+                indexes
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use solana_sdk::pubkey::Pubkey;
+
+// SPL token account layout: mint, owner, amount
+pub(crate) const TOKEN_ACCOUNT_LEN: usize = 165;
+// Token-2022 account type byte of a token account, right after the base layout
+pub(crate) const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+#[derive(Debug, Clone)]
+pub struct TokenHolding {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Debug, Default)]
+struct WalletState {
+    lamports: Option<u64>,
+    lamports_slot: u64,
+    /// Keyed by token account address
+    token_accounts: HashMap<Pubkey, TokenHolding>,
+}
+
+/// Live view of the buyer wallet: SOL balance and token accounts, kept up
+/// to date by the wallet monitor. Read synchronously by the executor.
+pub struct Wallet {
+    owner: Pubkey,
+    state: RwLock<WalletState>,
+}
+
+impl Wallet {
+    pub fn new(owner: Pubkey) -> Self {
+        Self {
+            owner,
+            state: RwLock::new(WalletState::default()),
+        }
+    }
+
+    pub fn owner(&self) -> Pubkey {
+        self.owner
+    }
+
+    /// SOL balance, `None` until the first load or update.
+    pub fn lamports(&self) -> Option<u64> {
+        self.state
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .lamports
+    }
+
+    pub fn has_token_account(&self, token_account: &Pubkey) -> bool {
+        self.state
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .token_accounts
+            .contains_key(token_account)
+    }
+
+    /// Raw amount of `mint` held across all token accounts.
+    pub fn token_balance(&self, mint: &Pubkey) -> u64 {
+        self.state
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .token_accounts
+            .values()
+            .filter(|holding| holding.mint == *mint)
+            .map(|holding| holding.amount)
+            .sum()
+    }
+
+    pub fn holdings(&self) -> Vec<TokenHolding> {
+        self.state
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .token_accounts
+            .values()
+            .filter(|holding| holding.amount > 0)
+            .cloned()
+            .collect()
+    }
+
+    /// Applies the wallet's system account, ignoring updates older than the
+    /// last one seen.
+    pub fn set_lamports(&self, lamports: u64, slot: u64) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if slot >= state.lamports_slot {
+            state.lamports = Some(lamports);
+            state.lamports_slot = slot;
+        }
+    }
+
+    /// Records a token account the RPC node already resolved for the wallet.
+    pub fn insert_holding(&self, address: Pubkey, holding: TokenHolding) {
+        self.state
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .token_accounts
+            .insert(address, holding);
+    }
+
+    /// Applies a token account owned by the wallet. Closed accounts arrive
+    /// with empty data and are removed.
+    pub fn apply_token_account(&self, address: Pubkey, data: &[u8]) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        match parse_token_account(data) {
+            Some((mint, owner, amount)) if owner == self.owner => {
+                state
+                    .token_accounts
+                    .insert(address, TokenHolding { mint, amount });
+            }
+            _ => {
+                state.token_accounts.remove(&address);
+            }
+        }
+    }
+}
+
+/// Mint, owner and amount of an SPL token account (Token-2022 accounts share
+/// the same base layout).
+pub(crate) fn parse_token_account(data: &[u8]) -> Option<(Pubkey, Pubkey, u64)> {
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        return None;
+    }
+    let mint = Pubkey::try_from(&data[0..32]).ok()?;
+    let owner = Pubkey::try_from(&data[32..64]).ok()?;
+    let amount = u64::from_le_bytes(data[64..72].try_into().ok()?);
+    Some((mint, owner, amount))
+}